@@ -1,11 +1,25 @@
 """app/api/admin_routes.py — Engine lifecycle introspection and control.
 
-Exposes three endpoints the parent process (Tauri Rust) uses to coordinate
+Exposes these endpoints the parent process (Tauri Rust) uses to coordinate
 with the engine without reaching across to kill its children directly:
 
     GET  /admin/status      Snapshot of every managed service the engine owns.
                             Returns the same data dump_diagnostics() captures,
                             without the heavy psutil walk — cheap to poll.
+                            Also carries `active_jobs` (running tool calls),
+                            which the shell checks before quitting,
+                            `draining`, `paused`, and `last_activity` (epoch
+                            seconds of the last tool call) for idle suspend.
+
+    POST /admin/drain       Stop accepting new tool calls so in-flight ones
+                            can finish before a restart. The shell polls
+                            /admin/status until active_jobs reaches 0. There
+                            is no undrain — the process is about to exit.
+
+    POST /admin/pause       Go quiet to save battery without losing state:
+    POST /admin/resume      new tool calls are refused and the scheduler
+                            scanner stops until resume. Running calls
+                            finish. Both are idempotent.
 
     POST /admin/shutdown    Signal the engine to gracefully tear down its
                             children and exit. Replies 200 immediately and
@@ -19,6 +33,16 @@ with the engine without reaching across to kill its children directly:
                             Useful for "the engine seems wedged but isn't
                             crashed" support tickets.
 
+    POST /admin/dump        Plain-text stacks of every Python thread and
+                            asyncio task, for working out where a hung
+                            engine is stuck. Nothing is written engine-side;
+                            the shell saves the reply next to its logs.
+
+    GET  /admin/log-level   Current level of the engine's system logger.
+    POST /admin/log-level   Change it at runtime ({"level": "debug"}) —
+                            no restart needed. Replies with the level now
+                            in effect.
+
 ────────────────────────────────────────────────────────────────────────────
 Why these endpoints exist
 ────────────────────────────────────────────────────────────────────────────
@@ -37,25 +61,31 @@ children behind its back) or fall back to SIGTERM if the engine is wedged.
 Trust model
 ────────────────────────────────────────────────────────────────────────────
 The engine binds to 127.0.0.1 only — only processes on the same machine
-can reach these endpoints. They are listed in _PUBLIC_PATHS so they can be
-called without a JWT (Rust has no JWT to send). Anyone with local code
-execution can already SIGTERM the engine, so a localhost-only shutdown
-endpoint adds no new attack surface.
+can reach these endpoints. Status, diagnose and shutdown are listed in
+_PUBLIC_PATHS so they can be called without a JWT (Rust has no JWT to send).
+Anyone with local code execution can already SIGTERM the engine, so a
+localhost-only shutdown endpoint adds no new attack surface. The others
+(dump, drain, pause, resume, log-level) require the engine token the shell
+sends as X-Engine-Token; see app/api/auth.py.
 ────────────────────────────────────────────────────────────────────────────
 """
 
 from __future__ import annotations
 
 import asyncio
+import io
 import logging
 import os
 import signal
+import sys
 import threading
 import time
+import traceback
 from typing import Any
 
-from fastapi import APIRouter, BackgroundTasks
-from fastapi.responses import JSONResponse
+from fastapi import APIRouter, BackgroundTasks, HTTPException, Request
+from fastapi.responses import JSONResponse, PlainTextResponse
+from pydantic import BaseModel
 
 from app.launcher import dump_diagnostics, get_registry
 
@@ -78,7 +108,67 @@ async def admin_status() -> dict[str, Any]:
     Cheap — does not walk all OS processes or read psutil network connections.
     Safe to poll every few seconds. For the heavy snapshot use POST /admin/diagnose.
     """
-    return get_registry().snapshot()
+    # Import here to avoid a circular import with main.py
+    from app.main import websocket_manager  # type: ignore[attr-defined]
+
+    snapshot = get_registry().snapshot()
+    snapshot["active_jobs"] = websocket_manager.running_task_count
+    snapshot["draining"] = websocket_manager.draining
+    snapshot["paused"] = websocket_manager.paused
+    snapshot["last_activity"] = websocket_manager.last_activity
+    return snapshot
+
+
+@router.post("/drain")
+async def admin_drain() -> dict[str, Any]:
+    """Refuse new tool calls from now on; running ones are left to finish.
+
+    Idempotent. Replies with the number of calls still in flight.
+    """
+    from app.main import websocket_manager  # type: ignore[attr-defined]
+
+    if not websocket_manager.draining:
+        websocket_manager.draining = True
+        logger.info(
+            "[launcher] /admin/drain — refusing new work, %d job(s) in flight",
+            websocket_manager.running_task_count,
+        )
+    return {"draining": True, "active_jobs": websocket_manager.running_task_count}
+
+
+@router.post("/pause")
+async def admin_pause(request: Request) -> dict[str, Any]:
+    """Refuse new tool calls and stop the scheduler scanner until /admin/resume."""
+    from app.main import websocket_manager  # type: ignore[attr-defined]
+
+    if not websocket_manager.paused:
+        websocket_manager.paused = True
+        state = request.app.state
+        if getattr(state, "scheduler_host_started", False):
+            from app.services.scheduler_host import stop_scheduler_host
+
+            await stop_scheduler_host()
+            state.scheduler_host_started = False
+            state.scheduler_paused = True
+        logger.info("[launcher] /admin/pause — engine paused")
+    return {"paused": True}
+
+
+@router.post("/resume")
+async def admin_resume(request: Request) -> dict[str, Any]:
+    """Undo /admin/pause: accept tool calls again and restart the scheduler."""
+    from app.main import websocket_manager  # type: ignore[attr-defined]
+
+    if websocket_manager.paused:
+        websocket_manager.paused = False
+        state = request.app.state
+        if getattr(state, "scheduler_paused", False):
+            from app.services.scheduler_host import start_scheduler_host
+
+            state.scheduler_host_started = await start_scheduler_host()
+            state.scheduler_paused = False
+        logger.info("[launcher] /admin/resume — engine resumed")
+    return {"paused": False}
 
 
 @router.post("/shutdown")
@@ -142,6 +232,93 @@ async def admin_diagnose(reason: str | None = None) -> dict[str, Any]:
     }
 
 
+@router.post("/dump", response_class=PlainTextResponse)
+async def admin_dump() -> str:
+    """Return the full stack of every thread and asyncio task as text.
+
+    Unlike /admin/diagnose this keeps every frame and includes the event
+    loop's tasks — a task parked on a lock or a blocking call is usually
+    the answer to "why is the engine hung?".
+    """
+    return _format_dump(asyncio.all_tasks())
+
+
+def _format_dump(tasks: set[asyncio.Task[Any]]) -> str:
+    lines = [
+        f"engine pid {os.getpid()} — dump at "
+        f"{time.strftime('%Y-%m-%dT%H:%M:%S%z', time.localtime())}",
+        "",
+    ]
+    threads_by_id = {t.ident: t for t in threading.enumerate()}
+    frames = sys._current_frames()
+    lines.append(f"== Threads ({len(frames)}) ==")
+    for tid, frame in frames.items():
+        t = threads_by_id.get(tid)
+        name = t.name if t else "?"
+        daemon = " daemon" if t is not None and t.daemon else ""
+        lines.append(f"\n-- Thread {name} (id {tid}{daemon}) --")
+        lines.extend(line.rstrip("\n") for line in traceback.format_stack(frame))
+
+    lines.append(f"\n== Asyncio tasks ({len(tasks)}) ==")
+    for task in tasks:
+        buf = io.StringIO()
+        task.print_stack(file=buf)
+        lines.append(f"\n-- Task {task.get_name()} --")
+        lines.append(buf.getvalue().rstrip("\n"))
+    return "\n".join(lines) + "\n"
+
+
+# Levels accepted by /admin/log-level, named the way the Tauri shell names
+# them. "trace" has no stdlib equivalent, so it gets its own number below DEBUG.
+_TRACE = 5
+logging.addLevelName(_TRACE, "TRACE")
+_LOG_LEVELS: dict[str, int] = {
+    "trace": _TRACE,
+    "debug": logging.DEBUG,
+    "info": logging.INFO,
+    "warn": logging.WARNING,
+    "error": logging.ERROR,
+}
+
+
+class LogLevelRequest(BaseModel):
+    level: str
+
+
+def _current_log_level() -> str:
+    level = logging.getLogger("system_logger").getEffectiveLevel()
+    for name, value in _LOG_LEVELS.items():
+        if value == level:
+            return name
+    return logging.getLevelName(level).lower()
+
+
+@router.get("/log-level")
+async def admin_get_log_level() -> dict[str, Any]:
+    """Return the level the engine's system logger is running at."""
+    return {"level": _current_log_level()}
+
+
+@router.post("/log-level")
+async def admin_set_log_level(body: LogLevelRequest) -> dict[str, Any]:
+    """Change the system logger's level (and its handlers') at runtime.
+
+    Not persisted — the Tauri shell passes LOG_LEVEL on the next spawn.
+    """
+    level = _LOG_LEVELS.get(body.level.lower())
+    if level is None:
+        raise HTTPException(
+            status_code=400,
+            detail=f"Unknown log level {body.level!r}; expected one of {list(_LOG_LEVELS)}",
+        )
+    system_logger = logging.getLogger("system_logger")
+    system_logger.setLevel(level)
+    for handler in system_logger.handlers:
+        handler.setLevel(level)
+    logger.info("[launcher] /admin/log-level — now %s", _current_log_level())
+    return {"level": _current_log_level()}
+
+
 # ── Internal ──────────────────────────────────────────────────────────────────
 
 
@@ -5,6 +5,31 @@ on the remote scraper server. We just ensure callers provide a Bearer token
 (the Supabase JWT or the local API key) to prevent unauthorized access from
 other processes on the machine.
 
+When spawned by the Tauri shell the engine also gets MATRX_ENGINE_TOKEN, a
+per-launch secret the desktop UI sends as ``X-Engine-Token`` (the bearer
+slot already carries the user's JWT, which is forwarded upstream). A request
+that presents a wrong or stale engine token is rejected; a valid one stands
+in for the bearer token, so the shell itself (which has no JWT) can call
+authenticated routes such as /jobs. While MATRX_ENGINE_TOKEN is set, a
+non-public request without a valid engine token is rejected. EventSource
+and WebSocket clients, which can't set headers, pass it as
+``?engine_token=``.
+
+The web app, the browser extension and tunnel clients can't hold the token.
+Each is let through without it only if the user allowed it in the shell's
+external-clients setting, passed as MATRX_EXTERNAL_CLIENTS (see
+external_client for how a request is classified). The classification rests
+on headers a local process could fake, so allowing a client kind lowers the
+bar to "knows what that client's requests look like"; the bearer check below
+still applies to it.
+
+A client that isn't on this machine (the engine binds 0.0.0.0 when the user
+turns on LAN access) must present the engine token on every request —
+public paths, admin and bootstrap routes included — and no client kind is
+let through without it. An engine started without MATRX_ENGINE_TOKEN
+refuses such clients outright. /version reports ``lan_auth`` so the shell
+can check the engine enforces this before opening it to the network.
+
 Public routes (health, discovery, and the OAuth callback) are excluded from
 the auth check. The OAuth callback MUST be public because the external browser
 delivers it with no auth token — it's the result of an OAuth flow, not an
@@ -13,11 +38,17 @@ authenticated API call.
 
 from __future__ import annotations
 
+import hmac
+import ipaddress
+import os
+import re
+
 from fastapi import APIRouter, Request
 from fastapi.responses import HTMLResponse, JSONResponse
 from starlette.middleware.base import BaseHTTPMiddleware
 
 from app.common.system_logger import get_logger
+from app.config import _TAURI_ORIGINS, ALLOWED_ORIGIN_REGEX, ALLOWED_ORIGINS
 
 logger = get_logger()
 
@@ -72,8 +103,11 @@ _PUBLIC_PATHS = frozenset(
         # Admin lifecycle endpoints — called by the Tauri shell (Rust) to
         # coordinate engine startup/shutdown without reaching across to kill
         # engine-owned children. See app/launcher.py and app/api/admin_routes.py
-        # for the full ownership contract. The engine binds 127.0.0.1 only,
-        # so localhost is the trust boundary; Rust has no JWT to send.
+        # for the full ownership contract. Only the read-only probes and
+        # shutdown are public: the shell must be able to stop an engine it
+        # adopted from an earlier session, whose token it never had. The
+        # rest (dump, drain, pause, resume, log-level) need the engine token,
+        # which the shell sends as X-Engine-Token in place of a JWT.
         "/admin/status",
         "/admin/shutdown",
         "/admin/diagnose",
@@ -196,18 +230,96 @@ async def oauth_callback(request: Request):
 # ---------------------------------------------------------------------------
 
 
+# Set by the Tauri shell on every spawn; unset when the engine runs standalone.
+_ENGINE_TOKEN = os.environ.get("MATRX_ENGINE_TOKEN") or None
+
+# Client kinds ("web", "extension", "tunnel") the user lets in without the
+# engine token. Unset means none.
+_EXTERNAL_CLIENTS = frozenset(
+    kind.strip()
+    for kind in os.environ.get("MATRX_EXTERNAL_CLIENTS", "").split(",")
+    if kind.strip()
+)
+
+_EXTENSION_SCHEMES = ("chrome-extension://", "moz-extension://", "safari-web-extension://")
+
+
+def external_client(headers) -> str | None:
+    """Which token-less client sent a request: "tunnel", "extension", "web", or None.
+
+    Tunnel traffic is what cloudflared forwards (it adds CF-Connecting-IP);
+    the extension and the web app are told apart by their Origin. The
+    desktop UI's own origins are never external — it has the token.
+    """
+    if headers.get("cf-connecting-ip"):
+        return "tunnel"
+    origin = headers.get("origin") or ""
+    if origin.startswith(_EXTENSION_SCHEMES):
+        return "extension"
+    if origin and origin not in _TAURI_ORIGINS:
+        if origin in ALLOWED_ORIGINS or re.fullmatch(ALLOWED_ORIGIN_REGEX, origin):
+            return "web"
+    return None
+
+
+def _is_loopback(host: str | None) -> bool:
+    try:
+        ip = ipaddress.ip_address(host or "")
+    except ValueError:
+        return False
+    mapped = getattr(ip, "ipv4_mapped", None)
+    return ip.is_loopback or (mapped is not None and mapped.is_loopback)
+
+
+def check_engine_token(
+    headers, query_params, client_host: str | None, public: bool
+) -> tuple[bool, str | None]:
+    """Check the engine token on an HTTP request or WebSocket upgrade.
+
+    Returns ``(from_shell, denial)``: whether a valid token was presented,
+    and the reason to reject the caller, or None to let it through.
+    """
+    local = _is_loopback(client_host)
+    if not _ENGINE_TOKEN:
+        return False, None if local else "Engine token required"
+    presented = headers.get("x-engine-token") or query_params.get("engine_token")
+    if presented is not None:
+        if not hmac.compare_digest(presented, _ENGINE_TOKEN):
+            return False, "Invalid engine token"
+        return True, None
+    if local and (public or external_client(headers) in _EXTERNAL_CLIENTS):
+        return False, None
+    return False, "Engine token required"
+
+
 class AuthMiddleware(BaseHTTPMiddleware):
     async def dispatch(self, request: Request, call_next):
         path = request.url.path.rstrip("/") or "/"
 
         # Skip auth for public routes, device status, fetch-proxy (iframe nav), and OPTIONS.
-        if (
+        public = (
             path in _PUBLIC_PATHS
             or path.startswith("/devices/")
             or path.startswith("/fetch-proxy")
             or path.startswith("/setup/")
             or request.method == "OPTIONS"
-        ):
+        )
+
+        client_host = request.client.host if request.client else None
+        from_shell, denial = check_engine_token(
+            request.headers, request.query_params, client_host, public
+        )
+        if denial:
+            logger.warning(
+                "[auth] rejected %s %s — %s",
+                request.method,
+                path,
+                denial.lower(),
+            )
+            return JSONResponse(status_code=401, content={"detail": denial})
+        request.state.from_shell = from_shell
+
+        if public:
             return await call_next(request)
 
         # Extract Bearer token — prefer Authorization header, fall back to
@@ -219,6 +331,9 @@ class AuthMiddleware(BaseHTTPMiddleware):
         else:
             token = request.query_params.get("token") or None
 
+        if not token and from_shell:
+            return await call_next(request)
+
         if not token:
             logger.warning(
                 "[auth] rejected %s %s — missing bearer token or ?token= query",
@@ -0,0 +1,76 @@
+"""app/api/config_routes.py — The engine's effective configuration.
+
+    GET   /config   Every setting, with defaults filled in.
+    PATCH /config   Change some of them. Body: ``{"key": value, ...}``.
+                    Replies ``{"config", "updated", "restart_required"}``.
+
+Settings live in the settings sync store (``~/.matrx/settings.json``), so
+this is the same data the cloud sync and ``/settings`` see. Most keys are
+read when they are used and take effect at once; the few in
+RESTART_REQUIRED_KEYS are only read during startup, and PATCH lists any of
+those it changed so the shell can offer a restart. Keys set by a
+``--config`` file win over stored values for as long as the engine runs.
+
+The Tauri shell proxies these as get_engine_config / patch_engine_config.
+"""
+
+from __future__ import annotations
+
+from typing import Any
+
+from fastapi import APIRouter, HTTPException
+
+from app.common.system_logger import get_logger
+from app.services.cloud_sync.settings_sync import DEFAULT_SETTINGS, get_settings_sync
+
+logger = get_logger()
+
+router = APIRouter(prefix="/config", tags=["config"])
+
+# Read once in the lifespan startup phases (see app/main.py).
+RESTART_REQUIRED_KEYS = frozenset({"proxy_enabled", "proxy_port", "tunnel_enabled"})
+
+
+def _type_error(key: str, value: Any) -> str | None:
+    """Why ``value`` can't be stored under ``key``, or None if it can."""
+    default = DEFAULT_SETTINGS[key]
+    # bool is an int subclass — check it first so True isn't a valid port.
+    if isinstance(default, bool):
+        ok = isinstance(value, bool)
+    elif isinstance(default, int):
+        ok = isinstance(value, int) and not isinstance(value, bool)
+    elif isinstance(default, float):
+        # JSON has no int/float distinction — 1 is a fine scrape_delay.
+        ok = isinstance(value, (int, float)) and not isinstance(value, bool)
+    else:
+        ok = isinstance(value, type(default))
+    if ok:
+        return None
+    return f"{key} must be a {type(default).__name__}, got {type(value).__name__}"
+
+
+@router.get("")
+async def get_config() -> dict[str, Any]:
+    return get_settings_sync().get_all()
+
+
+@router.patch("")
+async def patch_config(changes: dict[str, Any]) -> dict[str, Any]:
+    unknown = sorted(k for k in changes if k not in DEFAULT_SETTINGS)
+    if unknown:
+        raise HTTPException(status_code=422, detail=f"Unknown config keys: {', '.join(unknown)}")
+    errors = [e for k, v in changes.items() if (e := _type_error(k, v))]
+    if errors:
+        raise HTTPException(status_code=422, detail="; ".join(errors))
+
+    sync = get_settings_sync()
+    before = sync.get_all()
+    updated = sorted(k for k, v in changes.items() if before.get(k) != v)
+    if updated:
+        sync.set_many({k: changes[k] for k in updated})
+        logger.info("[config] Updated %s", ", ".join(updated))
+    return {
+        "config": sync.get_all(),
+        "updated": updated,
+        "restart_required": [k for k in updated if k in RESTART_REQUIRED_KEYS],
+    }
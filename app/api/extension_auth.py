@@ -56,6 +56,7 @@ from typing import Any, Optional
 
 from fastapi import HTTPException, Request, WebSocket
 
+from app.api.auth import check_engine_token
 from app.common.system_logger import get_logger
 from app.config import SUPABASE_URL
 
@@ -555,6 +556,17 @@ async def validate_extension_principal_ws(
         ExtensionPrincipal on success, or ``None`` if the socket was
         already closed (caller should ``return`` immediately on ``None``).
     """
+    # AuthMiddleware never sees the upgrade, so its engine token check runs
+    # here too.
+    client_host = websocket.client.host if websocket.client else None
+    _, denial = check_engine_token(
+        websocket.headers, websocket.query_params, client_host, False
+    )
+    if denial:
+        _log_rejection("ws", websocket.url.path, denial.lower().replace(" ", "_"))
+        await websocket.close(code=WS_CLOSE_POLICY_VIOLATION, reason=denial)
+        return None
+
     token = _extract_bearer_ws(websocket)
     if not token:
         _log_rejection("ws", websocket.url.path, "missing_bearer_token")
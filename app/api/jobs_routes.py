@@ -0,0 +1,41 @@
+"""app/api/jobs_routes.py — Inspect and cancel running tool calls.
+
+    GET  /jobs              Every tool call still executing, across all
+                            WebSocket connections, oldest first.
+    POST /jobs/{id}/cancel  Cancel one of them. 404 if it already finished.
+
+The Tauri shell proxies these (list_active_jobs / cancel_job) to back the
+UI's "stop" button. A job's id identifies the connection as well as the
+client's request id, so any client can cancel any job — the same trust
+level as the rest of the local API.
+"""
+
+from __future__ import annotations
+
+from typing import Any
+
+from fastapi import APIRouter, HTTPException
+
+from app.common.system_logger import get_logger
+
+logger = get_logger()
+
+router = APIRouter(prefix="/jobs", tags=["jobs"])
+
+
+@router.get("")
+async def list_jobs() -> dict[str, Any]:
+    # Import here to avoid a circular import with main.py
+    from app.main import websocket_manager  # type: ignore[attr-defined]
+
+    return {"jobs": websocket_manager.list_jobs()}
+
+
+@router.post("/{job_id}/cancel")
+async def cancel_job(job_id: str) -> dict[str, Any]:
+    from app.main import websocket_manager  # type: ignore[attr-defined]
+
+    if not websocket_manager.cancel_job(job_id):
+        raise HTTPException(status_code=404, detail=f"No running job with id {job_id}")
+    logger.info("[jobs] Cancelled job %s", job_id)
+    return {"id": job_id, "cancelled": True}
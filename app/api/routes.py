@@ -45,6 +45,23 @@ def _read_version() -> str:
 
 _APP_VERSION = _read_version()
 
+
+def _read_build_date() -> Optional[str]:
+    """Build date of the packaged engine binary (UTC, ISO date), taken from the
+    executable's mtime. None in dev mode, where there is no build."""
+    import sys as _sys
+    from datetime import datetime, timezone
+
+    if not getattr(_sys, "frozen", False):
+        return None
+    try:
+        mtime = Path(_sys.executable).stat().st_mtime
+        return datetime.fromtimestamp(mtime, tz=timezone.utc).date().isoformat()
+    except OSError:
+        return None
+
+_BUILD_DATE = _read_build_date()
+
 from fastapi import APIRouter, HTTPException, Query, Request
 from fastapi.responses import FileResponse, JSONResponse
 from pydantic import BaseModel, Field
@@ -90,8 +107,18 @@ async def health():
 
 @router.get("/version")
 async def version():
-    """Return the engine version."""
-    return {"version": _APP_VERSION, "service": "matrx-local"}
+    """Return the engine version and build info."""
+    import platform
+
+    return {
+        "version": _APP_VERSION,
+        "service": "matrx-local",
+        "python_version": platform.python_version(),
+        "build_date": _BUILD_DATE,
+        # Non-loopback clients need the engine token (app/api/auth.py); the
+        # shell checks this before turning on LAN access.
+        "lan_auth": True,
+    }
 
 
 @router.get("/ports")
@@ -10,6 +10,8 @@ from fastapi.middleware.cors import CORSMiddleware
 from starlette.middleware.base import BaseHTTPMiddleware
 
 from app.api.admin_routes import router as admin_router
+from app.api.jobs_routes import router as jobs_router
+from app.api.config_routes import router as config_router
 from app.api.routes import router as api_router
 from app.api.tool_routes import router as tool_router
 from app.api.sandbox_routes import router as sandbox_router
@@ -22,7 +24,7 @@ from app.api.chat_routes import router as chat_router, build_ai_sub_app
 from app.api.data_routes import router as data_router
 from app.api.permissions_routes import router as permissions_router
 from app.api.capabilities_routes import router as capabilities_router
-from app.api.auth import AuthMiddleware, auth_router
+from app.api.auth import AuthMiddleware, auth_router, check_engine_token
 from app.launcher import get_registry as _get_launcher_registry
 from app.api.token_routes import router as token_router
 from app.api.fetch_proxy_routes import router as fetch_proxy_router
@@ -979,11 +981,13 @@ app = FastAPI(
 
 app.include_router(auth_router)  # OAuth callback — must be before AuthMiddleware
 app.include_router(token_router)  # Token sync — React pushes JWT to Python
-# Admin endpoints (/admin/status, /admin/shutdown, /admin/diagnose) — used by
+# Admin endpoints (/admin/status, /admin/shutdown, /admin/diagnose, ...) — used by
 # the Tauri shell to coordinate engine lifecycle without reaching across to
 # kill engine-owned children. Listed in _PUBLIC_PATHS in app/api/auth.py.
 # See app/launcher.py for the ownership/propagation contract.
 app.include_router(admin_router)
+app.include_router(jobs_router)
+app.include_router(config_router)
 app.include_router(api_router)
 app.include_router(tool_router, prefix="/tools", tags=["tools"])
 # Orchestrator-shape sandbox dispatch — invoked by aidream's local-proxy
@@ -1174,7 +1178,14 @@ app.add_middleware(
     allow_origin_regex=ALLOWED_ORIGIN_REGEX,
     allow_credentials=True,
     allow_methods=["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"],
-    allow_headers=["Authorization", "Content-Type", "X-User-Id", "X-API-Key", "Accept"],
+    allow_headers=[
+        "Authorization",
+        "Content-Type",
+        "X-User-Id",
+        "X-API-Key",
+        "X-Engine-Token",  # desktop UI's per-launch engine token (see app/api/auth.py)
+        "Accept",
+    ],
     allow_private_network=True,
     max_age=600,
 )
@@ -1187,6 +1198,15 @@ async def websocket_endpoint(websocket: WebSocket):
     url = _sanitize_url(websocket.url)
     logger.info(f"WebSocket connecting: {url}")
 
+    client_host = websocket.client.host if websocket.client else None
+    _, denial = check_engine_token(
+        websocket.headers, websocket.query_params, client_host, False
+    )
+    if denial:
+        logger.warning(f"WebSocket rejected - {denial.lower()}: {url}")
+        await websocket.close(code=1008, reason=denial)
+        return
+
     token = websocket.query_params.get("token")
     if not token:
         logger.warning(
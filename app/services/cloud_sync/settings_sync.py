@@ -42,6 +42,10 @@ logger = logging.getLogger(__name__)
 from app.config import MATRX_HOME_DIR
 LOCAL_SETTINGS_FILE = MATRX_HOME_DIR / "settings.json"
 
+# Set by run.py from ``--config PATH``: a TOML, YAML or JSON file whose keys
+# override the stored settings for this run. Never written back.
+CONFIG_FILE_ENV = "MATRX_CONFIG_FILE"
+
 # Default settings — every possible setting with its default value.
 # This MUST stay in sync with DEFAULTS in desktop/src/lib/settings.ts.
 DEFAULT_SETTINGS: dict[str, Any] = {
@@ -141,6 +145,7 @@ class SettingsSync:
         self._configure_called_at: Optional[str] = None
 
         self._load_local()
+        self._file_overrides: dict[str, Any] = _load_config_file()
 
     # ── configuration ───────────────────────────────────────────────────
 
@@ -203,11 +208,13 @@ class SettingsSync:
 
     def get_all(self) -> dict[str, Any]:
         """Get all current settings (merged with defaults)."""
-        merged = {**DEFAULT_SETTINGS, **self._settings}
+        merged = {**DEFAULT_SETTINGS, **self._settings, **self._file_overrides}
         return merged
 
     def get(self, key: str, default: Any = None) -> Any:
         """Get a single setting value."""
+        if key in self._file_overrides:
+            return self._file_overrides[key]
         return self._settings.get(key, DEFAULT_SETTINGS.get(key, default))
 
     def set(self, key: str, value: Any) -> None:
@@ -599,6 +606,48 @@ class SettingsSync:
             logger.debug("Heartbeat failed (non-critical): %s", exc)
 
 
+def _load_config_file() -> dict[str, Any]:
+    """Settings from the ``--config`` file, if one was given.
+
+    The format follows the extension (.toml, .yaml/.yml, otherwise JSON).
+    Keys that aren't settings are ignored. A file that can't be read or
+    parsed is logged and skipped rather than stopping the engine — the
+    shell has already checked that it exists.
+    """
+    import os
+
+    path_str = os.environ.get(CONFIG_FILE_ENV, "").strip()
+    if not path_str:
+        return {}
+    path = Path(path_str)
+    try:
+        text = path.read_text(encoding="utf-8")
+        suffix = path.suffix.lower()
+        if suffix == ".toml":
+            import tomllib
+
+            data = tomllib.loads(text)
+        elif suffix in (".yaml", ".yml"):
+            import yaml
+
+            data = yaml.safe_load(text) or {}
+        else:
+            data = json.loads(text)
+    except Exception as exc:
+        logger.error("Ignoring config file %s: %s", path, exc)
+        return {}
+    if not isinstance(data, dict):
+        logger.error("Ignoring config file %s: expected a table of settings", path)
+        return {}
+
+    unknown = sorted(k for k in data if k not in DEFAULT_SETTINGS)
+    if unknown:
+        logger.warning("Config file %s: ignoring unknown keys %s", path, ", ".join(unknown))
+    overrides = {k: v for k, v in data.items() if k in DEFAULT_SETTINGS}
+    logger.info("Loaded %d setting(s) from config file %s", len(overrides), path)
+    return overrides
+
+
 # Module-level singleton
 _settings_sync: Optional[SettingsSync] = None
 
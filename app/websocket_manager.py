@@ -4,6 +4,7 @@ from __future__ import annotations
 
 import asyncio
 import json
+import time
 
 from fastapi import WebSocket
 
@@ -15,12 +16,14 @@ logger = get_logger()
 
 
 class Connection:
-    __slots__ = ("websocket", "session", "_running_tasks")
+    __slots__ = ("websocket", "session", "_running_tasks", "_task_info")
 
     def __init__(self, websocket: WebSocket, session: ToolSession) -> None:
         self.websocket = websocket
         self.session = session
         self._running_tasks: dict[str, asyncio.Task] = {}
+        # request id → (tool name, wall-clock start), for GET /jobs.
+        self._task_info: dict[str, tuple[str, float]] = {}
 
     def cancel_all(self) -> int:
         count = 0
@@ -35,6 +38,16 @@ class Connection:
 class WebSocketManager:
     def __init__(self) -> None:
         self.connections: dict[int, Connection] = {}
+        # Set by POST /admin/drain ahead of a restart: new tool calls are
+        # refused while in-flight ones run to completion.
+        self.draining = False
+        # Set by POST /admin/pause (battery saving): new tool calls are
+        # refused until /admin/resume. Unlike draining, this is reversible.
+        self.paused = False
+        # Epoch seconds of the last tool call started or finished, reported by
+        # /admin/status so the shell can suspend an idle engine. The Dashboard's
+        # polling tools don't count — they would keep it awake forever.
+        self.last_activity = time.time()
 
     async def connect(self, websocket: WebSocket) -> Connection:
         await websocket.accept()
@@ -114,6 +127,22 @@ class WebSocketManager:
 
         req_id = request_id or f"auto-{id(msg)}"
 
+        if self.draining:
+            await self._send(conn, {
+                "id": req_id,
+                "type": "error",
+                "output": "Engine is restarting — try again in a moment",
+            })
+            return
+
+        if self.paused:
+            await self._send(conn, {
+                "id": req_id,
+                "type": "error",
+                "output": "Engine is paused — resume it to run tools",
+            })
+            return
+
         # High-frequency monitoring tools — always DEBUG to avoid terminal flooding.
         # These fire every 10s from the Dashboard/Ports pages and produce repetitive output.
         _QUIET_TOOLS = frozenset({"ListPorts", "SystemResources", "SystemInfo", "ListProcesses"})
@@ -129,9 +158,19 @@ class WebSocketManager:
             logger.info("→ WS tool=%s  id=%s", tool_name, req_id)
             logger.debug("   input: %s", input_str)
 
+        if not is_quiet:
+            self.last_activity = time.time()
         task = asyncio.create_task(self._run_tool(conn, req_id, tool_name, tool_input))
         conn._running_tasks[req_id] = task
-        task.add_done_callback(lambda _: conn._running_tasks.pop(req_id, None))
+        conn._task_info[req_id] = (tool_name, time.time())
+
+        def _done(_: asyncio.Task) -> None:
+            conn._running_tasks.pop(req_id, None)
+            conn._task_info.pop(req_id, None)
+            if not is_quiet:
+                self.last_activity = time.time()
+
+        task.add_done_callback(_done)
 
     async def _run_tool(
         self, conn: Connection, request_id: str, tool_name: str, tool_input: dict
@@ -222,3 +261,52 @@ class WebSocketManager:
     @property
     def active_count(self) -> int:
         return len(self.connections)
+
+    def list_jobs(self) -> list[dict]:
+        """Running tool calls across every connection, oldest first.
+
+        A job id is ``<connection>:<request id>`` — request ids are chosen
+        by the client and only unique within one connection.
+        """
+        now = time.time()
+        jobs = []
+        for conn_id, conn in self.connections.items():
+            for request_id, task in conn._running_tasks.items():
+                if task.done():
+                    continue
+                tool, started_at = conn._task_info.get(request_id, ("?", now))
+                jobs.append({
+                    "id": f"{conn_id}:{request_id}",
+                    "request_id": request_id,
+                    "tool": tool,
+                    "started_at": started_at,
+                    "elapsed_secs": round(now - started_at, 3),
+                })
+        jobs.sort(key=lambda job: job["started_at"])
+        return jobs
+
+    def cancel_job(self, job_id: str) -> bool:
+        """Cancel one job from list_jobs(). False if it isn't running.
+
+        The client that started it gets the usual "was cancelled" error.
+        """
+        conn_id, _, request_id = job_id.partition(":")
+        try:
+            conn = self.connections.get(int(conn_id))
+        except ValueError:
+            return False
+        task = conn._running_tasks.get(request_id) if conn else None
+        if task is None or task.done():
+            return False
+        task.cancel()
+        return True
+
+    @property
+    def running_task_count(self) -> int:
+        """Tool calls still executing, across every connection."""
+        return sum(
+            1
+            for conn in self.connections.values()
+            for task in conn._running_tasks.values()
+            if not task.done()
+        )
@@ -0,0 +1,226 @@
+//! adopt — reuse an engine a previous session left running.
+//!
+//! After an unclean shutdown the old engine can still be serving on our
+//! port. Before spawning, start_sidecar and the setup warm start probe the
+//! port the next spawn would try first: if what answers `/health` also
+//! identifies as our engine on `/version` (and is a version this UI works
+//! with), it is adopted instead of swept away and replaced. Something else
+//! answering there is a `PortInUse`; `probe_existing_sidecar` reports that,
+//! while the start paths just carry on and let the spawn fall back to
+//! another port.
+//!
+//! An adopted engine is driven like an external one — by its URL, with no
+//! child handle, supervisor or watchdog, and reported with `mode:
+//! "adopted"` — except that it is ours to stop: stop, restart and reload
+//! shut it down (see release) before doing their usual work. Its launch
+//! token died with the old session, so routes that need it — including
+//! /admin/pause, /admin/drain, /admin/dump and /admin/log-level — won't
+//! accept calls from the shell until the engine is restarted.
+//!
+//! check_port runs the same identification on any port without acting on
+//! it, so the UI can tell a port held by another app from one an orphaned
+//! engine of ours could be reclaimed from.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::engine_http::EngineHttp;
+use crate::{
+    settings, version_compat, LockRecover, SidecarError, SidecarLifecycle, SidecarProcess,
+    SidecarState, ENGINE_SIDECAR,
+};
+
+/// Bound on each probe request — a live engine answers in milliseconds.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// What `/version` says about itself. `service` is the signature.
+const ENGINE_SERVICE: &str = "matrx-local";
+
+/// An engine from a previous session in use in place of a spawned one.
+#[derive(Debug, Clone, Copy)]
+pub struct AdoptedEngine {
+    pub port: u16,
+    /// From the discovery file; `None` if it names another process.
+    pub pid: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct VersionBody {
+    #[serde(default)]
+    service: String,
+    version: String,
+}
+
+/// Look for one of our engines on the port the next spawn would use (the
+/// last engine port, else `preferred-port`) and adopt it. Returns the port
+/// if an engine is adopted — or already was. Nothing answering, or an engine
+/// too old or new for this UI (which the spawn's orphan sweep replaces),
+/// is `None`. Fails with `PortInUse` if something else is listening there,
+/// `AlreadyRunning` if the app has spawned its own engine.
+#[tauri::command]
+pub async fn probe_existing_sidecar(app: AppHandle) -> Result<Option<u16>, SidecarError> {
+    let sidecars = app.state::<SidecarState>();
+    let engine = sidecars.engine();
+    if let Some(adopted) = *engine.adopted.lock_recover() {
+        return Ok(Some(adopted.port));
+    }
+    if settings::dev_engine_url(&app).is_some() {
+        return Ok(None);
+    }
+    if engine.child.lock_recover().is_some() {
+        return Err(SidecarError::AlreadyRunning);
+    }
+
+    let port = engine.port.load(Ordering::SeqCst);
+    let base = format!("http://127.0.0.1:{}", port);
+    let client = app.state::<EngineHttp>().client().clone();
+    let answering = client
+        .get(format!("{}/health", base))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .is_ok_and(|r| r.status().is_success());
+    if !answering {
+        return Ok(None);
+    }
+    let Some(version) = engine_version(&client, port).await else {
+        log::warn!(
+            "[adopt] Port {} is held by something that isn't our engine",
+            port
+        );
+        return Err(SidecarError::PortInUse(port));
+    };
+    if !version_compat::is_compatible(&version.version) {
+        log::info!(
+            "[adopt] Engine {} left on port {} doesn't fit this app — replacing it",
+            version.version,
+            port
+        );
+        return Ok(None);
+    }
+
+    let pid = crate::orphaned_engine_on_port(port);
+    *engine.adopted.lock_recover() = Some(AdoptedEngine { port, pid });
+    *engine.bound_addr.lock_recover() = None;
+    *engine.token.lock_recover() = None;
+    *engine.version_cache.lock_recover() = None;
+    *engine.openapi_cache.lock_recover() = None;
+    engine.ready.store(true, Ordering::SeqCst);
+    version_compat::evaluate(&app, version.version.clone());
+    if let Err(e) = settings::remember_engine_port(&app, port) {
+        log::warn!("[adopt] {}", e);
+    }
+    log::info!(
+        "[adopt] Adopted engine {} already serving on port {} (pid={:?})",
+        version.version,
+        port,
+        pid
+    );
+    crate::set_sidecar_tray_status(
+        &app,
+        ENGINE_SIDECAR,
+        &format!("Status: Running (port {})", port),
+    );
+    crate::emit_sidecar_state(&app, ENGINE_SIDECAR, SidecarLifecycle::Running, pid);
+    Ok(Some(port))
+}
+
+/// Who holds a port, per check_port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortOccupant {
+    None,
+    /// One of our engines — the running one, or an orphan to reclaim.
+    Ours,
+    Foreign,
+}
+
+/// Result of check_port.
+#[derive(Debug, Clone, Serialize)]
+pub struct PortCheck {
+    pub free: bool,
+    pub occupant: PortOccupant,
+}
+
+/// Whether `port` could be bound right now and, if not, whether what holds
+/// it is one of our engines: it identifies as one on `/version`, or the
+/// discovery file names a live engine there. Anything else — including a
+/// listener that doesn't answer HTTP — is `foreign`. Only looks; nothing
+/// is stopped or adopted.
+#[tauri::command]
+pub async fn check_port(app: AppHandle, port: u16) -> Result<PortCheck, SidecarError> {
+    if port == 0 {
+        return Err("Port 0 is not a real port".to_string().into());
+    }
+    if std::net::TcpListener::bind(("127.0.0.1", port)).is_ok() {
+        return Ok(PortCheck {
+            free: true,
+            occupant: PortOccupant::None,
+        });
+    }
+    let client = app.state::<EngineHttp>().client().clone();
+    let ours = engine_version(&client, port).await.is_some()
+        || crate::orphaned_engine_on_port(port).is_some();
+    Ok(PortCheck {
+        free: false,
+        occupant: if ours {
+            PortOccupant::Ours
+        } else {
+            PortOccupant::Foreign
+        },
+    })
+}
+
+/// What answers `/version` on `port`, if it identifies as our engine.
+async fn engine_version(client: &reqwest::Client, port: u16) -> Option<VersionBody> {
+    let response = client
+        .get(format!("http://127.0.0.1:{}/version", port))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .ok()?;
+    let version = response.json::<VersionBody>().await.ok()?;
+    (version.service == ENGINE_SERVICE).then_some(version)
+}
+
+/// probe_existing_sidecar for the start paths: true if an engine is now in
+/// use. A foreign listener is only logged — the spawn picks another port.
+pub async fn try_adopt(app: &AppHandle) -> bool {
+    match probe_existing_sidecar(app.clone()).await {
+        Ok(adopted) => adopted.is_some(),
+        Err(e) => {
+            log::warn!("[adopt] Not adopting: {}", e);
+            false
+        }
+    }
+}
+
+/// Shut down an adopted engine and forget it, so the caller can spawn a
+/// managed one. A no-op unless `state` is the engine and one was adopted.
+pub async fn release(app: &AppHandle, state: &SidecarProcess) {
+    let Some(adopted) = state.adopted.lock_recover().take() else {
+        return;
+    };
+    state.ready.store(false, Ordering::SeqCst);
+    log::info!(
+        "[adopt] Stopping adopted engine on port {} (pid={:?})",
+        adopted.port,
+        adopted.pid
+    );
+    crate::emit_sidecar_state(app, ENGINE_SIDECAR, SidecarLifecycle::Stopping, adopted.pid);
+    let _ = tauri::async_runtime::spawn_blocking(move || {
+        crate::request_admin_shutdown(Some(adopted.port));
+        if let Some(pid) = adopted.pid {
+            if !crate::wait_for_process_exit(pid, crate::SIDECAR_STOP_TIMEOUT) {
+                crate::terminate_orphaned_engine(pid, crate::SIDECAR_KILL_TIMEOUT);
+            }
+        }
+    })
+    .await;
+    crate::set_sidecar_tray_status(app, ENGINE_SIDECAR, "Status: Stopped");
+    crate::emit_sidecar_state(app, ENGINE_SIDECAR, SidecarLifecycle::Stopped, adopted.pid);
+}
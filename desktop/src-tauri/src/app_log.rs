@@ -0,0 +1,138 @@
+//! app_log — a leveled log for the Rust side itself.
+//!
+//! engine.log holds what the engine prints; this is the shell's own detail
+//! — spawn steps, each health-check result, restart decisions — logged
+//! through the `log` macros and written both to stderr and to
+//! `{app_log_dir}/matrx-app.log`. The level starts at `info` and
+//! set_app_log_level changes it live: `debug` shows every ping and restart
+//! decision, `trace` more still. Only this crate logs below `info`, so
+//! `debug` is not buried under dependencies' chatter.
+//!
+//! The shell's `[tag]` messages go through the macros too — `info` for
+//! progress, `warn` and `error` for trouble — so they land in the file. Only
+//! the terminal echo of the engine's own output bypasses it; that is kept
+//! in engine.log. The file is rotated like engine.log, but keeps a single
+//! `.1` archive, and one already over the limit when it is opened is
+//! rotated before the first write.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::LockRecover;
+
+const LOG_FILE_NAME: &str = "matrx-app.log";
+
+/// Move the file to `matrx-app.log.1` once it would grow past this size.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024; // 5 MB
+
+/// The level before set_app_log_level is called.
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// Prefix of this crate's own log targets.
+const CRATE_TARGET: &str = env!("CARGO_CRATE_NAME");
+
+struct OpenLog {
+    file: File,
+    size: u64,
+}
+
+struct AppLogger {
+    dir: PathBuf,
+    /// Opened on the first record, so an unwritable log dir never blocks
+    /// startup.
+    file: Mutex<Option<OpenLog>>,
+}
+
+/// Install the logger, writing into `log_dir`. Called once from setup.
+pub fn init(log_dir: PathBuf) {
+    let logger = AppLogger {
+        dir: log_dir,
+        file: Mutex::new(None),
+    };
+    match log::set_boxed_logger(Box::new(logger)) {
+        Ok(()) => log::set_max_level(DEFAULT_LEVEL),
+        Err(e) => eprintln!("[app_log] Could not install the logger: {}", e),
+    }
+}
+
+/// Change how much the Rust side logs: "error", "warn", "info", "debug",
+/// "trace" or "off".
+#[tauri::command]
+pub fn set_app_log_level(level: String) -> Result<(), String> {
+    let filter: LevelFilter = level
+        .parse()
+        .map_err(|_| format!("Unknown log level '{}'", level))?;
+    log::set_max_level(filter);
+    log::info!("Log level set to {}", filter);
+    Ok(())
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+            && (metadata.level() <= Level::Info || metadata.target().starts_with(CRATE_TARGET))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        let line = format!(
+            "[{}.{:03}] {:<5} {}: {}\n",
+            ts.as_secs(),
+            ts.subsec_millis(),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        eprint!("{}", line);
+        if let Err(e) = self.append(&line) {
+            eprintln!("[app_log] Failed to write {}: {}", LOG_FILE_NAME, e);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(open) = self.file.lock_recover().as_mut() {
+            let _ = open.file.flush();
+        }
+    }
+}
+
+impl AppLogger {
+    fn append(&self, line: &str) -> std::io::Result<()> {
+        let mut guard = self.file.lock_recover();
+        let path = self.dir.join(LOG_FILE_NAME);
+        if guard.is_none() {
+            std::fs::create_dir_all(&self.dir)?;
+            *guard = Some(open_log(&path)?);
+        }
+        // Also catches a file left oversized by an earlier session.
+        if guard
+            .as_ref()
+            .is_some_and(|open| open.size + line.len() as u64 > MAX_LOG_BYTES)
+        {
+            *guard = None;
+            std::fs::rename(&path, self.dir.join(format!("{}.1", LOG_FILE_NAME)))?;
+            *guard = Some(open_log(&path)?);
+        }
+        let Some(open) = guard.as_mut() else {
+            return Ok(());
+        };
+        open.file.write_all(line.as_bytes())?;
+        open.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+fn open_log(path: &Path) -> std::io::Result<OpenLog> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    Ok(OpenLog { file, size })
+}
@@ -0,0 +1,92 @@
+//! app_menu — the native application menu (File / Edit / View / Help).
+//!
+//! macOS gets it as the global menu bar, with the usual app-name menu first;
+//! Windows and Linux get it as the main window's menu bar only, so the
+//! transcript overlay stays chrome-free.
+//!
+//! Items that also exist in the tray reuse the tray's ids ("restart_engine",
+//! "open_logs", "open_api_docs", "quit"), so crate::handle_menu_event serves
+//! both menus.
+
+use tauri::menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+#[cfg(not(target_os = "macos"))]
+use tauri::Manager;
+
+pub fn setup(app: &tauri::App) -> tauri::Result<()> {
+    let restart_engine = MenuItemBuilder::with_id("restart_engine", "Restart Engine").build(app)?;
+    crate::register_restart_item(app.handle(), restart_engine.clone());
+    let open_logs = MenuItemBuilder::with_id("open_logs", "Open Logs").build(app)?;
+    let open_api_docs = MenuItemBuilder::with_id("open_api_docs", "Open API Docs")
+        .enabled(false)
+        .build(app)?;
+    crate::register_api_docs_item(app.handle(), open_api_docs.clone());
+    let check_updates =
+        MenuItemBuilder::with_id("check_updates", "Check for Updates…").build(app)?;
+    let quit = MenuItemBuilder::with_id("quit", "Quit AI Matrx")
+        .accelerator("CmdOrCtrl+Q")
+        .build(app)?;
+
+    let file = SubmenuBuilder::new(app, "File")
+        .item(&restart_engine)
+        .item(&open_logs)
+        .separator();
+    // Quit lives in the app-name menu on macOS, in File elsewhere.
+    #[cfg(target_os = "macos")]
+    let file = file.close_window();
+    #[cfg(not(target_os = "macos"))]
+    let file = file.item(&quit);
+    let file = file.build()?;
+
+    let edit = SubmenuBuilder::new(app, "Edit")
+        .undo()
+        .redo()
+        .separator()
+        .cut()
+        .copy()
+        .paste()
+        .select_all()
+        .build()?;
+    let view = SubmenuBuilder::new(app, "View").fullscreen().build()?;
+    let help = SubmenuBuilder::new(app, "Help")
+        .item(&open_api_docs)
+        .item(&check_updates)
+        .build()?;
+
+    #[cfg(target_os = "macos")]
+    {
+        let app_name = SubmenuBuilder::new(app, "AI Matrx")
+            .about(None)
+            .separator()
+            .services()
+            .separator()
+            .hide()
+            .hide_others()
+            .show_all()
+            .separator()
+            .item(&quit)
+            .build()?;
+        let menu = MenuBuilder::new(app)
+            .item(&app_name)
+            .item(&file)
+            .item(&edit)
+            .item(&view)
+            .item(&help)
+            .build()?;
+        app.handle().set_menu(menu)?;
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let menu = MenuBuilder::new(app)
+            .item(&file)
+            .item(&edit)
+            .item(&view)
+            .item(&help)
+            .build()?;
+        if let Some(window) = app.get_webview_window("main") {
+            window.set_menu(menu)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! autostart — "Start at Login" backed by tauri-plugin-autostart.
+//!
+//! The OS registration (LaunchAgent / registry Run key / XDG autostart entry)
+//! is the source of truth for whether we launch at login; the `start-at-login`
+//! setting mirrors it so startup code and the settings UI can read it without
+//! a round trip to the OS. Every toggle — command or tray checkbox — goes
+//! through apply(), which updates the registration, the setting and the tray
+//! checkbox together.
+//!
+//! Login launches carry AUTOSTART_ARG so setup can tell them apart from the
+//! user opening the app, and start hidden in the tray when
+//! `start-hidden-at-login` is set.
+
+use tauri::menu::CheckMenuItem;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::settings;
+
+/// Passed by the OS login launcher (see the plugin init in lib.rs).
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+/// The tray's "Start at Login" checkbox. Managed in setup_tray().
+pub struct AutostartMenuItem(pub CheckMenuItem<Wry>);
+
+/// True when this process was started by the OS at login.
+pub fn launched_at_login() -> bool {
+    std::env::args().any(|a| a == AUTOSTART_ARG)
+}
+
+/// Whether the OS registration is currently in place.
+pub fn is_enabled(app: &AppHandle) -> bool {
+    app.autolaunch().is_enabled().unwrap_or(false)
+}
+
+/// Register/unregister launch at login and persist the choice.
+pub fn apply(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let launcher = app.autolaunch();
+    let result = if enabled {
+        launcher.enable()
+    } else {
+        launcher.disable()
+    };
+    // Keep the checkbox truthful even when the OS call failed.
+    let registered = is_enabled(app);
+    if let Some(item) = app.try_state::<AutostartMenuItem>() {
+        let _ = item.0.set_checked(registered);
+    }
+    result.map_err(|e| {
+        format!(
+            "Failed to {} start at login: {}",
+            if enabled { "enable" } else { "disable" },
+            e
+        )
+    })?;
+
+    settings::set(
+        app,
+        settings::KEY_START_AT_LOGIN,
+        serde_json::Value::Bool(registered),
+    )
+}
+
+/// Tray checkbox handler. The checkbox has already flipped by the time the
+/// click arrives, so its new state is the requested one.
+pub fn toggle_from_menu(app: &AppHandle) {
+    let Some(requested) = app
+        .try_state::<AutostartMenuItem>()
+        .and_then(|item| item.0.is_checked().ok())
+    else {
+        return;
+    };
+    if let Err(e) = apply(app, requested) {
+        log::warn!("[autostart] {}", e);
+    }
+}
+
+/// Enable or disable launching the app at login.
+#[tauri::command]
+pub fn set_autostart(app: AppHandle, enabled: bool) -> Result<(), String> {
+    apply(&app, enabled)
+}
+
+/// Whether the app is registered to launch at login.
+#[tauri::command]
+pub fn is_autostart_enabled(app: AppHandle) -> bool {
+    is_enabled(&app)
+}
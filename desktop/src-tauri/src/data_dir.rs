@@ -0,0 +1,367 @@
+//! data_dir — move the engine's data folder, e.g. to a bigger drive.
+//!
+//! The engine reads its data folder from `MATRX_DATA_DIR`, which lives in the
+//! `sidecar-config` env like any other engine variable. `set_data_dir` points
+//! it somewhere new: it checks the target is an empty, writable folder, stops
+//! the engine, copies the old folder's contents across (emitting
+//! `data-dir-progress` as it goes), saves the setting, deletes the originals
+//! and starts the engine again on the new folder.
+//!
+//! Data is copied rather than renamed because the point is usually to change
+//! drives, where a rename fails. The originals go only once the setting
+//! points at the copy, so no failure along the way leaves the engine without
+//! its data. If the copy fails partway or the setting can't be saved, the
+//! partial copy is removed and the engine restarts on the old folder. If the
+//! copy turns out incomplete or the old folder can't be cleared, the setting
+//! is rolled back and the copy removed the same way, so a failed move never
+//! leaves data split across two places.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::engine_http::{self, EngineHttp};
+use crate::{settings, LockRecover, SidecarState};
+
+const DATA_DIR_ENV: &str = "MATRX_DATA_DIR";
+
+/// Minimum gap between `data-dir-progress` events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Payload of the `data-dir-progress` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct DataDirProgress {
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+    pub files_done: u64,
+    pub files_total: u64,
+}
+
+/// Point the engine at a new, empty data folder, moving the current data
+/// there unless `move_data` is false. Returns the folder now in use.
+///
+/// Refuses a folder that already has anything in it, so two data sets are
+/// never merged. The engine is stopped for the move and started again
+/// afterwards if it was running.
+#[tauri::command]
+pub async fn set_data_dir(
+    app: AppHandle,
+    http: State<'_, EngineHttp>,
+    path: String,
+    move_data: Option<bool>,
+) -> Result<String, String> {
+    let move_data = move_data.unwrap_or(true);
+    let sidecars = app.state::<SidecarState>();
+    let engine = sidecars.engine();
+    // An adopted engine is ours to stop and start; only a dev engine isn't.
+    if settings::dev_engine_url(&app).is_some() {
+        return Err("The data folder of an external dev engine can't be changed here".into());
+    }
+
+    let target = PathBuf::from(path.trim());
+    if !target.is_absolute() {
+        return Err(format!("{} is not an absolute path", target.display()));
+    }
+    let current = current_data_dir(&app, &http).await;
+    if let Some(current) = &current {
+        if same_path(current, &target) {
+            return Err(format!("{} is already the data folder", target.display()));
+        }
+        if target.starts_with(current) || current.starts_with(&target) {
+            return Err(
+                "The new data folder can't be inside the current one, or contain it".into(),
+            );
+        }
+    }
+    let source = match (move_data, current) {
+        (false, _) => None,
+        (true, Some(current)) => Some(current),
+        (true, None) => {
+            return Err(
+                "Could not tell where the current data folder is — start the \
+                        engine first, or set it without moving data"
+                    .into(),
+            )
+        }
+    };
+    prepare_target(&target)?;
+
+    // An engine adopted from an earlier session is stopped too, so it must
+    // be started again the same way.
+    let was_running =
+        engine.child.lock_recover().is_some() || engine.adopted.lock_recover().is_some();
+    if !crate::stop_sidecar_process(&app, crate::ENGINE_SIDECAR).await? {
+        return Err("Engine did not stop — nothing was moved".to_string());
+    }
+
+    let moved = apply_move(&app, source, target.clone()).await;
+    if let Err(e) = moved {
+        if was_running {
+            if let Err(e) = crate::start_sidecar(app.clone(), None, None).await {
+                log::error!("[data_dir] Engine restart after failed move: {}", e);
+            }
+        }
+        return Err(e);
+    }
+
+    if was_running {
+        crate::start_sidecar(app.clone(), None, None)
+            .await
+            .map_err(|e| format!("Data moved, but the engine failed to start: {}", e))?;
+    }
+    Ok(target.display().to_string())
+}
+
+/// Copy `source` (if moving data) into `target`, point the setting at
+/// `target`, then clear `source`. On any error the copy is removed and the
+/// setting is back where it was.
+async fn apply_move(
+    app: &AppHandle,
+    source: Option<PathBuf>,
+    target: PathBuf,
+) -> Result<(), String> {
+    // A folder with nothing written yet has nothing to move.
+    let source = source.filter(|source| source.exists());
+    if let Some(source) = &source {
+        let (app_handle, from, to) = (app.clone(), source.clone(), target.clone());
+        tauri::async_runtime::spawn_blocking(move || copy_contents(&app_handle, &from, &to))
+            .await
+            .map_err(|e| format!("Failed to move data: {}", e))
+            .and_then(|r| r)?;
+    }
+
+    let previous = settings::sidecar_config(app);
+    let mut config = previous.clone();
+    config
+        .env
+        .insert(DATA_DIR_ENV.to_string(), target.display().to_string());
+    if let Err(e) = save_sidecar_config(app, &config) {
+        clear_dir(&target);
+        return Err(e);
+    }
+    log::info!("[data_dir] Data folder is now {}", target.display());
+
+    let Some(source) = source else {
+        return Ok(());
+    };
+    if let Err(e) = check_can_clear(&source, &target) {
+        if let Err(e) = save_sidecar_config(app, &previous) {
+            log::error!(
+                "[data_dir] Could not restore the data folder setting: {}",
+                e
+            );
+        }
+        clear_dir(&target);
+        return Err(format!("{} — the data was left in {}", e, source.display()));
+    }
+    clear_dir(&source);
+    Ok(())
+}
+
+fn save_sidecar_config(app: &AppHandle, config: &settings::SidecarConfig) -> Result<(), String> {
+    let value = serde_json::to_value(config)
+        .map_err(|e| format!("Failed to serialize sidecar config: {}", e))?;
+    settings::set(app, settings::KEY_SIDECAR_CONFIG, value)
+}
+
+/// Before the originals go: the copy must hold as many bytes and files as
+/// they do, and the old folder must still be writable (so clearing it can't
+/// stop halfway for lack of permission).
+fn check_can_clear(source: &Path, target: &Path) -> Result<(), String> {
+    let (expected, found) = (measure(source), measure(target));
+    if found != expected {
+        return Err(format!(
+            "The copy in {} is incomplete ({} of {} file(s))",
+            target.display(),
+            found.1,
+            expected.1
+        ));
+    }
+    probe_writable(source)
+}
+
+#[derive(serde::Deserialize)]
+struct SystemPaths {
+    resolved: ResolvedPaths,
+}
+
+#[derive(serde::Deserialize)]
+struct ResolvedPaths {
+    data: PathBuf,
+}
+
+/// The MATRX_DATA_DIR the engine is configured with, if one was set.
+pub fn configured(app: &AppHandle) -> Option<PathBuf> {
+    settings::sidecar_config(app)
+        .env
+        .get(DATA_DIR_ENV)
+        .map(PathBuf::from)
+}
+
+/// The folder the engine is using: what it reports in `/system/paths` if it
+/// is up, else the configured MATRX_DATA_DIR. `None` for an engine that is
+/// down and has never been configured (its default depends on the build).
+pub async fn current_data_dir(app: &AppHandle, http: &EngineHttp) -> Option<PathBuf> {
+    let reported = match http.request(app, reqwest::Method::GET, &["system", "paths"]) {
+        Ok(request) => match engine_http::send_retrying(request)
+            .await
+            .and_then(|r| r.error_for_status())
+        {
+            Ok(response) => response.json::<SystemPaths>().await.ok(),
+            Err(_) => None,
+        },
+        Err(_) => None,
+    };
+    reported
+        .map(|paths| paths.resolved.data)
+        .or_else(|| configured(app))
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Create `target` if needed and check that it is empty and writable.
+fn prepare_target(target: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(target)
+        .map_err(|e| format!("Could not create {}: {}", target.display(), e))?;
+    let mut entries = std::fs::read_dir(target)
+        .map_err(|e| format!("Could not read {}: {}", target.display(), e))?;
+    if entries.next().is_some() {
+        return Err(format!(
+            "{} is not empty — choose an empty folder",
+            target.display()
+        ));
+    }
+    probe_writable(target)
+}
+
+/// Check that files can be created in `dir` by writing and removing one.
+pub fn probe_writable(dir: &Path) -> Result<(), String> {
+    let probe = dir.join(".matrx-write-test");
+    std::fs::write(&probe, b"ok")
+        .and_then(|_| std::fs::remove_file(&probe))
+        .map_err(|e| format!("{} is not writable: {}", dir.display(), e))
+}
+
+struct Progress<'a> {
+    app: &'a AppHandle,
+    state: DataDirProgress,
+    last_emit: Option<Instant>,
+}
+
+impl Progress<'_> {
+    fn emit(&mut self, force: bool) {
+        if !force
+            && self
+                .last_emit
+                .is_some_and(|t| t.elapsed() < PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        self.last_emit = Some(Instant::now());
+        let _ = self.app.emit("data-dir-progress", &self.state);
+    }
+}
+
+/// Copy everything in `from` into `to`, leaving the originals for
+/// apply_move to delete. On a copy error the partial copy is removed.
+fn copy_contents(app: &AppHandle, from: &Path, to: &Path) -> Result<(), String> {
+    let (total_bytes, files_total) = measure(from);
+    let mut progress = Progress {
+        app,
+        state: DataDirProgress {
+            copied_bytes: 0,
+            total_bytes,
+            files_done: 0,
+            files_total,
+        },
+        last_emit: None,
+    };
+    progress.emit(true);
+    if let Err(e) = copy_dir(from, to, &mut progress) {
+        clear_dir(to);
+        return Err(format!("Failed to copy data to {}: {}", to.display(), e));
+    }
+    progress.emit(true);
+    log::info!(
+        "[data_dir] Copied {} file(s), {} bytes from {}",
+        progress.state.files_done,
+        progress.state.copied_bytes,
+        from.display()
+    );
+    Ok(())
+}
+
+/// Total size and file count under `dir`. Unreadable entries are skipped
+/// here; the copy reports them.
+pub fn measure(dir: &Path) -> (u64, u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (0, 0);
+    };
+    let mut totals = (0, 0);
+    for entry in entries.flatten() {
+        let Ok(kind) = entry.file_type() else {
+            continue;
+        };
+        if kind.is_dir() {
+            let (bytes, files) = measure(&entry.path());
+            totals.0 += bytes;
+            totals.1 += files;
+        } else {
+            totals.0 += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            totals.1 += 1;
+        }
+    }
+    totals
+}
+
+/// Recursive copy. Symlinks are recreated on Unix rather than followed;
+/// elsewhere the file they point to is copied.
+fn copy_dir(from: &Path, to: &Path, progress: &mut Progress) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let kind = entry.file_type()?;
+        let dest = to.join(entry.file_name());
+        if kind.is_dir() {
+            copy_dir(&entry.path(), &dest, progress)?;
+            continue;
+        }
+        #[cfg(unix)]
+        if kind.is_symlink() {
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &dest)?;
+            progress.state.files_done += 1;
+            progress.emit(false);
+            continue;
+        }
+        progress.state.copied_bytes += std::fs::copy(entry.path(), &dest)?;
+        progress.state.files_done += 1;
+        progress.emit(false);
+    }
+    Ok(())
+}
+
+/// Best-effort removal of everything inside `dir`, keeping `dir` itself.
+fn clear_dir(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        let result = if is_dir {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        if let Err(e) = result {
+            log::error!("[data_dir] Could not remove {}: {}", path.display(), e);
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! diagnostics — one-file support bundle for bug reports.
+//!
+//! `export_diagnostics` writes `{downloads}/matrx-diagnostics-{unix_secs}.zip`
+//! containing:
+//!
+//! - `summary.json` — app and engine versions, OS info, and the engine's
+//!   sidecar_status
+//! - `settings.json` — every settings-store entry, with secrets redacted
+//! - `engine.log` — the tail of the on-disk engine log
+//! - `sidecar-buffer.log` — the in-memory output buffer (get_sidecar_logs)
+//! - `crash.log` — Rust panics recorded by panic_hook, if there were any
+//! - `dumps/engine-dump-*.txt` — the latest stack dumps taken with
+//!   dump_engine_state, if any
+//!
+//! Redaction is by key name: any object key that looks like it holds a
+//! credential has its value replaced, at any depth (so `sidecar-config` env
+//! vars such as `HF_TOKEN` are covered), as are values passed to
+//! secret-looking CLI flags in `sidecar-config` args.
+
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, State};
+use zip::write::SimpleFileOptions;
+
+use crate::engine_http::EngineHttp;
+use crate::{engine_log, panic_hook, settings, LockRecover, SidecarError, SidecarState};
+
+/// engine.log lines included in the bundle.
+const LOG_TAIL_LINES: usize = 2000;
+
+/// Engine stack dumps included in the bundle, newest first.
+const BUNDLED_DUMPS: usize = 3;
+
+/// File name prefix of dump_engine_state's files in the log directory.
+const DUMP_PREFIX: &str = "engine-dump-";
+
+/// A hung engine can take a long time to answer at all.
+const DUMP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Key fragments (lowercased) that mark a value as secret.
+const SECRET_MARKERS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "passwd",
+    "key",
+    "auth",
+    "credential",
+];
+
+pub const REDACTED: &str = "[redacted]";
+
+/// Collect status, logs, versions, OS info and redacted settings into a zip
+/// in the Downloads folder. Returns the path of the written file.
+#[tauri::command]
+pub async fn export_diagnostics(app: AppHandle) -> Result<String, String> {
+    let created_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let status = crate::sidecar_status(app.clone(), app.state(), None).await;
+    let engine_version = crate::sidecar_version(app.state(), app.state()).await;
+    let summary = json!({
+        "created_at": created_at,
+        "app_version": app.package_info().version.to_string(),
+        "engine_version": match &engine_version {
+            Ok(version) => json!(version),
+            Err(e) => json!({ "error": e.to_string() }),
+        },
+        "os": os_info(),
+        "sidecar_status": match &status {
+            Ok(status) => json!(status),
+            Err(e) => json!({ "error": e.to_string() }),
+        },
+    });
+
+    let settings_json = settings::store(&app)
+        .map(|store| {
+            let mut entries: serde_json::Map<String, Value> = store.entries().into_iter().collect();
+            for (key, value) in entries.iter_mut() {
+                redact(key, value);
+            }
+            Value::Object(entries)
+        })
+        .unwrap_or_else(|e| json!({ "error": e }));
+
+    let log = app.try_state::<engine_log::EngineLogState>();
+    let engine_log = match &log {
+        Some(log) => match log.tail(LOG_TAIL_LINES) {
+            Ok(lines) => lines.join("\n"),
+            Err(e) => format!("Failed to read {}: {}", log.path().display(), e),
+        },
+        None => String::new(),
+    };
+    let crash_log = log
+        .as_ref()
+        .and_then(|log| std::fs::read_to_string(panic_hook::crash_log_path(log.dir())).ok());
+    let dumps = log.map(|log| recent_dumps(log.dir())).unwrap_or_default();
+    let buffer = app
+        .state::<SidecarState>()
+        .engine()
+        .logs
+        .lock_recover()
+        .join("\n");
+
+    let dir = app
+        .path()
+        .download_dir()
+        .map_err(|e| format!("Could not resolve the Downloads folder: {}", e))?;
+    let path = dir.join(format!("matrx-diagnostics-{}.zip", created_at));
+    let mut files = vec![
+        ("summary.json", pretty(&summary)),
+        ("settings.json", pretty(&settings_json)),
+        ("engine.log", engine_log),
+        ("sidecar-buffer.log", buffer),
+    ];
+    files.extend(crash_log.map(|contents| ("crash.log", contents)));
+    files.extend(
+        dumps
+            .iter()
+            .map(|(name, contents)| (name.as_str(), contents.clone())),
+    );
+    write_zip(&path, &files).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    log::info!("[diagnostics] Wrote {}", path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Ask the engine for the stacks of all its threads and asyncio tasks
+/// (`/admin/dump`) and save them as `{app_log_dir}/engine-dump-{unix_secs}.txt`.
+/// Returns the file's path.
+///
+/// Hangs are when this is needed, so it doesn't wait for `/health` like the
+/// other engine commands — any running engine is asked, with a long
+/// timeout. `NotRunning` without one. export_diagnostics bundles the latest
+/// few dumps.
+#[tauri::command]
+pub async fn dump_engine_state(
+    app: AppHandle,
+    http: State<'_, EngineHttp>,
+) -> Result<String, SidecarError> {
+    let request = {
+        let sidecars = app.state::<SidecarState>();
+        let engine = sidecars.engine();
+        let external = crate::external_engine_url(&app, engine).is_some();
+        if !external && engine.child.lock_recover().is_none() {
+            return Err(SidecarError::NotRunning);
+        }
+        http.request_to(engine, reqwest::Method::POST, &["admin", "dump"])?
+    };
+    let dump = request
+        .timeout(DUMP_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("The engine did not return a dump: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read the engine dump: {}", e))?;
+
+    let log = app
+        .try_state::<engine_log::EngineLogState>()
+        .ok_or_else(|| "Engine log not initialized".to_string())?;
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = log.ensure_dir()?.join(format!("{}{}.txt", DUMP_PREFIX, ts));
+    std::fs::write(&path, dump)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    log::info!("[diagnostics] Wrote {}", path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// The newest BUNDLED_DUMPS dumps in `dir`, as (zip entry name, contents).
+fn recent_dumps(dir: &Path) -> Vec<(String, String)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.starts_with(DUMP_PREFIX) && name.ends_with(".txt"))
+        .collect();
+    // Same-length timestamps, so the names sort by age.
+    names.sort_unstable_by(|a, b| b.cmp(a));
+    names
+        .into_iter()
+        .take(BUNDLED_DUMPS)
+        .filter_map(|name| {
+            let contents = std::fs::read_to_string(dir.join(&name)).ok()?;
+            Some((format!("dumps/{}", name), contents))
+        })
+        .collect()
+}
+
+fn write_zip(path: &Path, files: &[(&str, String)]) -> zip::result::ZipResult<()> {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, contents) in files {
+        zip.start_file(*name, options)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+fn os_info() -> Value {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_memory();
+    json!({
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "name": sysinfo::System::long_os_version(),
+        "kernel": sysinfo::System::kernel_version(),
+        "cpus": std::thread::available_parallelism().map(|n| n.get()).ok(),
+        "total_memory_bytes": sys.total_memory(),
+    })
+}
+
+fn is_secret(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_MARKERS.iter().any(|m| name.contains(m))
+}
+
+/// Replace secret-looking values under `key`, recursing into objects and
+/// arrays. Also used by export_settings.
+pub fn redact(key: &str, value: &mut Value) {
+    if is_secret(key) && !value.is_null() {
+        *value = Value::String(REDACTED.to_string());
+        return;
+    }
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                redact(k, v);
+            }
+        }
+        Value::Array(items) => {
+            redact_args(items);
+            for item in items.iter_mut() {
+                redact("", item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Redact CLI-style `--api-key value` / `--api-key=value` pairs.
+fn redact_args(items: &mut [Value]) {
+    let mut redact_next = false;
+    for item in items.iter_mut() {
+        let Value::String(arg) = item else {
+            redact_next = false;
+            continue;
+        };
+        if redact_next {
+            *arg = REDACTED.to_string();
+            redact_next = false;
+            continue;
+        }
+        let Some(flag) = arg.strip_prefix("--") else {
+            continue;
+        };
+        match flag.split_once('=') {
+            Some((name, _)) if is_secret(name) => {
+                let redacted = format!("--{}={}", name, REDACTED);
+                *arg = redacted;
+            }
+            Some(_) => {}
+            None => redact_next = is_secret(flag),
+        }
+    }
+}
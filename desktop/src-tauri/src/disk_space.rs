@@ -0,0 +1,208 @@
+//! disk_space — warn before the data folder's disk fills up.
+//!
+//! The engine fails in confusing ways once its disk is full. data_dir_space
+//! reports the disk's size and free space for the engine's data folder (the
+//! one data_dir.rs manages) along with how much of it the folder itself
+//! takes. A background check runs shortly after launch and then every
+//! CHECK_INTERVAL; when free space is under `low-disk-threshold-mb` it
+//! emits `low-disk-space` and notifies once, and again only after space has
+//! recovered and run low a second time.
+//!
+//! Free space comes from statvfs on Unix and PowerShell's Get-PSDrive on
+//! Windows, where the app has no direct binding for it.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::engine_http::EngineHttp;
+use crate::{data_dir, settings};
+
+/// Wait this long after launch, so a spawning engine can report its folder.
+const STARTUP_DELAY: Duration = Duration::from_secs(30);
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Set while free space is below the threshold, so the warning isn't
+/// repeated every check.
+static WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Result of data_dir_space.
+#[derive(Debug, Clone, Serialize)]
+pub struct DataDirSpace {
+    pub path: String,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub used_by_matrx_bytes: u64,
+}
+
+/// Payload of `low-disk-space`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LowDiskSpace {
+    pub path: String,
+    pub free_bytes: u64,
+    pub threshold_bytes: u64,
+}
+
+/// Disk space for the engine's data folder. Walking the folder makes this
+/// slow for a large one. Fails when the folder isn't known — the engine is
+/// down and no folder was ever configured — or its disk can't be queried.
+#[tauri::command]
+pub async fn data_dir_space(
+    app: AppHandle,
+    http: State<'_, EngineHttp>,
+) -> Result<DataDirSpace, String> {
+    let dir = data_dir::current_data_dir(&app, &http)
+        .await
+        .ok_or_else(|| "The data folder isn't known until the engine has started".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let (total_bytes, free_bytes) = disk_space(&dir)?;
+        let (used_by_matrx_bytes, _) = data_dir::measure(&dir);
+        Ok(DataDirSpace {
+            path: dir.to_string_lossy().to_string(),
+            total_bytes,
+            free_bytes,
+            used_by_matrx_bytes,
+        })
+    })
+    .await
+    .map_err(|e| format!("Disk space check failed: {}", e))?
+}
+
+/// Start the periodic check. Called once from setup.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        loop {
+            check(&app).await;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+async fn check(app: &AppHandle) {
+    let Some(threshold_bytes) = settings::low_disk_threshold_bytes(app) else {
+        return;
+    };
+    let http = app.state::<EngineHttp>();
+    let Some(dir) = data_dir::current_data_dir(app, &http).await else {
+        return;
+    };
+    let path = dir.clone();
+    let free_bytes = match tauri::async_runtime::spawn_blocking(move || disk_space(&path)).await {
+        Ok(Ok((_, free))) => free,
+        Ok(Err(e)) => {
+            log::warn!("[disk] {}", e);
+            return;
+        }
+        Err(_) => return,
+    };
+
+    if free_bytes >= threshold_bytes {
+        WARNED.store(false, Ordering::SeqCst);
+        return;
+    }
+    if WARNED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let path = dir.to_string_lossy().to_string();
+    log::warn!(
+        "[disk] Only {} MiB free on the disk holding {}",
+        free_bytes / (1024 * 1024),
+        path
+    );
+    crate::notifications::notify(
+        app,
+        "AI Matrx is running out of disk space",
+        &format!(
+            "Only {} MiB is free on the disk holding the engine's data. Free up space to \
+             keep it working.",
+            free_bytes / (1024 * 1024)
+        ),
+    );
+    let _ = app.emit(
+        "low-disk-space",
+        LowDiskSpace {
+            path,
+            free_bytes,
+            threshold_bytes,
+        },
+    );
+}
+
+/// `(total, free)` bytes of the disk `dir` is on; free as available to
+/// this user.
+#[cfg(unix)]
+fn disk_space(dir: &Path) -> Result<(u64, u64), String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(dir.as_os_str().as_bytes())
+        .map_err(|_| format!("{} contains a NUL byte", dir.display()))?;
+    // SAFETY: statvfs only writes into the zeroed struct we pass it.
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(format!(
+            "Could not read disk space for {}: {}",
+            dir.display(),
+            std::io::Error::last_os_error()
+        ));
+    }
+    let block = stat.f_frsize as u64;
+    Ok((stat.f_blocks as u64 * block, stat.f_bavail as u64 * block))
+}
+
+/// `(total, free)` bytes of the drive `dir` is on.
+#[cfg(windows)]
+fn disk_space(dir: &Path) -> Result<(u64, u64), String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let drive =
+        drive_letter(dir).ok_or_else(|| format!("{} is not on a lettered drive", dir.display()))?;
+    let script = format!(
+        "$d = Get-PSDrive -Name '{}'; \"$($d.Used) $($d.Free)\"",
+        drive
+    );
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Could not run PowerShell: {}", e))?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut numbers = text.split_whitespace().map(|n| n.parse::<u64>());
+    match (numbers.next(), numbers.next()) {
+        (Some(Ok(used)), Some(Ok(free))) => Ok((used + free, free)),
+        _ => Err(format!(
+            "Unexpected Get-PSDrive output for {}: {}",
+            drive,
+            text.trim()
+        )),
+    }
+}
+
+#[cfg(windows)]
+fn drive_letter(dir: &Path) -> Option<char> {
+    use std::path::{Component, Prefix};
+
+    match dir.components().next()? {
+        Component::Prefix(prefix) => match prefix.kind() {
+            Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => {
+                Some((letter as char).to_ascii_uppercase())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn disk_space(dir: &Path) -> Result<(u64, u64), String> {
+    Err(format!(
+        "Disk space isn't available on this platform ({})",
+        dir.display()
+    ))
+}
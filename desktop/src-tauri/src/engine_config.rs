@@ -0,0 +1,180 @@
+//! engine_config — view and edit the engine's effective configuration.
+//!
+//! Proxies over the engine's `GET /config` and `PATCH /config` for the
+//! in-app settings viewer. Both go through EngineHttp against the live
+//! engine, so they fail with `NotRunning` / `HealthCheckFailed` until it is
+//! up. Keys the engine only reads at startup come back in
+//! `restart_required`, and a patch that changed any emits
+//! `restart-required` like set_feature_flags does.
+//!
+//! Power users can also point the engine at a config file of their own with
+//! `set_engine_config_file`. It is passed as `--config PATH` on every spawn,
+//! and its keys override the stored settings for that run.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::engine_http::EngineHttp;
+use crate::features::RestartRequired;
+use crate::{settings, LockRecover, SidecarError, SidecarState};
+
+/// Config file the running engine was started with; `None` before the first
+/// spawn.
+static APPLIED_FILE: Mutex<Option<Option<String>>> = Mutex::new(None);
+
+/// Result of patch_engine_config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineConfigPatch {
+    /// The whole configuration after the change.
+    pub config: serde_json::Value,
+    /// Keys whose value actually changed.
+    pub updated: Vec<String>,
+    /// The subset of `updated` that only takes effect after a restart.
+    pub restart_required: Vec<String>,
+}
+
+/// The engine's configuration as a JSON object, defaults filled in.
+#[tauri::command]
+pub async fn get_engine_config(
+    app: AppHandle,
+    http: State<'_, EngineHttp>,
+) -> Result<serde_json::Value, SidecarError> {
+    crate::idle::resume_if_suspended(&app).await?;
+    let config = http
+        .request(&app, reqwest::Method::GET, &["config"])?
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to read engine config: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected /config response: {}", e))?;
+    Ok(config)
+}
+
+/// Change some of the engine's settings. `partial` is an object of
+/// key/value pairs; the engine rejects unknown keys and wrong types, and
+/// that message is returned as the error.
+#[tauri::command]
+pub async fn patch_engine_config(
+    app: AppHandle,
+    http: State<'_, EngineHttp>,
+    partial: serde_json::Value,
+) -> Result<EngineConfigPatch, SidecarError> {
+    if !partial.is_object() {
+        return Err("Config changes must be a JSON object".to_string().into());
+    }
+    crate::idle::resume_if_suspended(&app).await?;
+    let response = http
+        .request(&app, reqwest::Method::PATCH, &["config"])?
+        .json(&partial)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update engine config: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let detail = response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|body| {
+                body.get("detail")
+                    .and_then(|d| d.as_str())
+                    .map(String::from)
+            })
+            .unwrap_or_else(|| status.to_string());
+        return Err(format!("Engine rejected the config change: {}", detail).into());
+    }
+    let patch: EngineConfigPatch = response
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected /config response: {}", e))?;
+
+    if !patch.updated.is_empty() {
+        log::info!("[engine_config] Updated {}", patch.updated.join(", "));
+    }
+    if !patch.restart_required.is_empty() {
+        let _ = app.emit(
+            "restart-required",
+            RestartRequired {
+                reason: "engine-config",
+                required: true,
+            },
+        );
+    }
+    Ok(patch)
+}
+
+/// `--config PATH` for the saved config file, if any. Called by
+/// spawn_sidecar for the engine, which also records it as the running
+/// engine's file. Fails with `ConfigFileMissing` when the file has gone
+/// away, so the start fails with a clear error instead of the engine
+/// quietly running without it.
+pub fn spawn_args(app: &AppHandle) -> Result<Vec<String>, SidecarError> {
+    let path = settings::engine_config_file(app);
+    if let Some(path) = &path {
+        check_readable(path).map_err(|e| {
+            log::warn!("[engine_config] {}", e);
+            SidecarError::ConfigFileMissing { path: path.clone() }
+        })?;
+    }
+    let args = match &path {
+        Some(path) => vec!["--config".to_string(), path.clone()],
+        None => Vec::new(),
+    };
+    *APPLIED_FILE.lock_recover() = Some(path);
+    Ok(args)
+}
+
+fn check_readable(path: &str) -> Result<(), String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("{}: {}", path, e))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", path));
+    }
+    std::fs::File::open(path)
+        .map(|_| ())
+        .map_err(|e| format!("{} is not readable: {}", path, e))
+}
+
+/// Launch the engine against the config file at `path` from now on; `None`
+/// (or "") goes back to none. The file must exist and be readable. Returns
+/// the saved path. If the engine is running, emits `restart-required`
+/// (`required: false` when it already runs with this file).
+#[tauri::command]
+pub fn set_engine_config_file(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<Option<String>, String> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    match &path {
+        Some(p) => {
+            check_readable(p)?;
+            settings::set(&app, settings::KEY_ENGINE_CONFIG_FILE, serde_json::json!(p))?;
+            log::info!("[engine_config] Engine config file: {}", p);
+        }
+        None => {
+            settings::set(
+                &app,
+                settings::KEY_ENGINE_CONFIG_FILE,
+                serde_json::Value::Null,
+            )?;
+            log::info!("[engine_config] Engine config file cleared");
+        }
+    }
+
+    let sidecars = app.state::<SidecarState>();
+    let running = sidecars.engine().child.lock_recover().is_some();
+    let applied = APPLIED_FILE.lock_recover().clone();
+    if let (true, Some(applied)) = (running, applied) {
+        let _ = app.emit(
+            "restart-required",
+            RestartRequired {
+                reason: "engine-config-file",
+                required: applied != path,
+            },
+        );
+    }
+    Ok(path)
+}
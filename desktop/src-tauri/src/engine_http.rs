@@ -0,0 +1,189 @@
+//! engine_http — the shared HTTP client for calls into the engine.
+//!
+//! One `reqwest::Client` is managed for the app lifetime so engine calls
+//! reuse connections and share one set of timeouts. It never uses a proxy:
+//! the engine is on this machine, and a system proxy would only get in the
+//! way. Calls that need a tighter bound (health pings, the quit check)
+//! override the default per request.
+//!
+//! URLs are resolved against the live SidecarProcess — its current port, or
+//! the address it reported binding — and carry its per-spawn engine token,
+//! so callers never build `http://127.0.0.1:{port}` by hand. The exceptions
+//! are request_admin_shutdown, which must work from the synchronous shutdown
+//! path, and the port-scanning commands that probe ports nobody owns yet.
+//!
+//! Health and admin calls go out through `send_retrying`, which retries a
+//! failed send a couple of times after a short jittered pause: the engine
+//! occasionally stalls for a few ms (GC), and one dropped `/health` must not
+//! look like a crash. The proxy commands don't use it — their callers decide
+//! whether to retry.
+//!
+//! Server-Sent Event streams get a second client without the per-request
+//! timeout, which would otherwise cut a long stream off after ten seconds.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::{LockRecover, SidecarError, SidecarProcess, SidecarState};
+
+/// Fail fast when nothing is listening.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// Per-request default. Callers that need longer (or shorter) override it
+/// with `RequestBuilder::timeout`.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Sends per `send_retrying` call, including the first.
+const RETRY_ATTEMPTS: u32 = 3;
+/// Pause before a retry: RETRY_DELAY_MIN plus up to RETRY_JITTER_MS.
+const RETRY_DELAY_MIN: Duration = Duration::from_millis(50);
+const RETRY_JITTER_MS: u64 = 100;
+
+pub struct EngineHttp {
+    client: reqwest::Client,
+    /// No overall timeout, for `stream`.
+    streaming: reqwest::Client,
+}
+
+impl Default for EngineHttp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EngineHttp {
+    pub fn new() -> Self {
+        let build = |builder: reqwest::ClientBuilder| {
+            builder
+                .no_proxy()
+                .connect_timeout(CONNECT_TIMEOUT)
+                .build()
+                .unwrap_or_else(|e| {
+                    log::warn!("[engine_http] Falling back to a default client: {}", e);
+                    reqwest::Client::new()
+                })
+        };
+        Self {
+            client: build(reqwest::Client::builder().timeout(REQUEST_TIMEOUT)),
+            streaming: build(reqwest::Client::builder()),
+        }
+    }
+
+    /// The client itself, for the few calls that target an explicit URL
+    /// rather than a managed process.
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// Start a request to `process` whatever its state, with its token
+    /// attached. `segments` are the path segments, percent-encoded as
+    /// needed. For health probes, which must run before `ready` is set.
+    pub fn request_to(
+        &self,
+        process: &SidecarProcess,
+        method: reqwest::Method,
+        segments: &[&str],
+    ) -> Result<reqwest::RequestBuilder, String> {
+        let mut url = process
+            .base_url()
+            .parse::<tauri::Url>()
+            .map_err(|e| format!("Invalid engine URL: {}", e))?;
+        url.path_segments_mut()
+            .map_err(|_| "Engine URL cannot take a path".to_string())?
+            .extend(segments);
+        Ok(with_token(process, self.client.request(method, url)))
+    }
+
+    /// Start a request to the running, ready engine (see `request_to`).
+    ///
+    /// `NotRunning` if there is no engine; `HealthCheckFailed` if it hasn't
+    /// answered `/health` yet.
+    pub fn request(
+        &self,
+        app: &AppHandle,
+        method: reqwest::Method,
+        segments: &[&str],
+    ) -> Result<reqwest::RequestBuilder, SidecarError> {
+        Ok(self.request_to(ready_engine(app)?, method, segments)?)
+    }
+
+    /// Start a `GET` of the Server-Sent Event stream at `path` (starting
+    /// with `/`, query string allowed) on the running, ready engine. Uses
+    /// the streaming client, so the request has no overall timeout.
+    pub fn stream(
+        &self,
+        app: &AppHandle,
+        path: &str,
+    ) -> Result<reqwest::RequestBuilder, SidecarError> {
+        let state = ready_engine(app)?;
+        let url = format!("{}{}", state.base_url(), path)
+            .parse::<tauri::Url>()
+            .map_err(|e| format!("Invalid engine stream path '{}': {}", path, e))?;
+        let request = self
+            .streaming
+            .get(url)
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+        Ok(with_token(state, request))
+    }
+}
+
+/// The engine, if it is running (or external) and has answered `/health`.
+fn ready_engine(app: &AppHandle) -> Result<&SidecarProcess, SidecarError> {
+    let state = app.state::<SidecarState>().inner().engine();
+    let external = crate::external_engine_url(app, state).is_some();
+    if !external && state.child.lock_recover().is_none() {
+        return Err(SidecarError::NotRunning);
+    }
+    if !state.ready.load(Ordering::SeqCst) {
+        return Err(SidecarError::HealthCheckFailed);
+    }
+    Ok(state)
+}
+
+fn with_token(
+    process: &SidecarProcess,
+    request: reqwest::RequestBuilder,
+) -> reqwest::RequestBuilder {
+    match process.token.lock_recover().clone() {
+        Some(token) => request.header("X-Engine-Token", token),
+        None => request,
+    }
+}
+
+/// Send `request`, retrying transport failures (refused, reset, timed out)
+/// up to RETRY_ATTEMPTS times in total, 50–150ms apart. An HTTP error
+/// status is an answer, so it's returned as is. Requests with a streaming
+/// body can't be cloned and are sent once.
+pub async fn send_retrying(request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+    let mut attempt = 1;
+    loop {
+        let Some(retry) = (attempt < RETRY_ATTEMPTS)
+            .then(|| request.try_clone())
+            .flatten()
+        else {
+            return request.send().await;
+        };
+        match retry.send().await {
+            Err(e) if !e.is_builder() => {
+                log::error!(
+                    "[engine_http] Attempt {}/{} failed, retrying: {}",
+                    attempt,
+                    RETRY_ATTEMPTS,
+                    e
+                );
+                tokio::time::sleep(retry_delay()).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// RETRY_DELAY_MIN plus a jitter so concurrent callers don't retry in step.
+fn retry_delay() -> Duration {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    RETRY_DELAY_MIN + Duration::from_millis(nanos % (RETRY_JITTER_MS + 1))
+}
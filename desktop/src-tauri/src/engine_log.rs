@@ -0,0 +1,379 @@
+//! engine_log — on-disk sink for engine sidecar output.
+//!
+//! Every stdout/stderr line the engine prints is appended to
+//! `{app_log_dir}/engine.log` so bug reports can include logs from before the
+//! window was opened (or from a packaged app where the console is invisible).
+//!
+//! Rotation is size-based: once `engine.log` would exceed MAX_LOG_BYTES it is
+//! renamed to `engine.log.1`, an existing `.1` shifts to `.2`, and anything
+//! older is dropped — at most MAX_LOG_FILES files exist at any time.
+//! `rotate_engine_log` runs the same rotation on demand, for a clean log
+//! before reproducing a bug.
+//!
+//! One `EngineLog` is managed for the whole app lifetime (not per spawn), so
+//! rotation state stays consistent across sidecar restarts.
+//!
+//! `query_engine_log` filters the recent tail by level and text. It knows
+//! the engine's `LEVEL - message` / `time - LEVEL - message` lines and
+//! uvicorn's `LEVEL:  message`; a line in neither shape (a traceback, a
+//! wrapped message) belongs to the entry above it.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::LockRecover;
+
+const LOG_FILE_NAME: &str = "engine.log";
+
+/// Rotate once the active file would grow past this size.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024; // 5 MB
+
+/// Active file + rotated archives (engine.log, engine.log.1, engine.log.2).
+const MAX_LOG_FILES: usize = 3;
+
+/// How many of the newest lines query_engine_log searches.
+const QUERY_SCAN_LINES: usize = 5000;
+
+/// query_engine_log's default `limit`.
+const QUERY_DEFAULT_LIMIT: usize = 200;
+
+/// Level names as the engine prints them, least severe first.
+const LEVELS: [&str; 5] = ["DEBUG", "INFO", "WARNING", "ERROR", "CRITICAL"];
+
+/// tail() reads backwards from the end of the file in blocks of this size.
+const TAIL_CHUNK_BYTES: u64 = 16 * 1024;
+
+/// Shared state type managed by Tauri.
+pub type EngineLogState = Arc<EngineLog>;
+
+struct OpenLog {
+    file: File,
+    size: u64,
+}
+
+pub struct EngineLog {
+    dir: PathBuf,
+    /// Lazily opened on the first append so a missing/unwritable log dir
+    /// never blocks startup.
+    file: Mutex<Option<OpenLog>>,
+}
+
+impl EngineLog {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Create the log directory if needed and return it. Used by callers
+    /// that hand the directory to the user, so it exists even on a fresh
+    /// install before the engine has printed anything.
+    pub fn ensure_dir(&self) -> Result<&Path, String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create log dir {}: {}", self.dir.display(), e))?;
+        Ok(&self.dir)
+    }
+
+    /// The log directory (not created — see ensure_dir()).
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Drop the open handle so the directory can be deleted. The next
+    /// append() reopens (and recreates) the file.
+    pub fn close(&self) {
+        *self.file.lock_recover() = None;
+    }
+
+    /// Path of the active log file.
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(LOG_FILE_NAME)
+    }
+
+    /// Append one line (a trailing newline is added). Write failures are
+    /// logged to the console and otherwise ignored — losing a log line must
+    /// never take down the output-forwarding task.
+    pub fn append(&self, line: &str) {
+        let mut guard = self.file.lock_recover();
+        let bytes = line.len() as u64 + 1;
+
+        if let Some(open) = guard.as_ref() {
+            if open.size + bytes > MAX_LOG_BYTES {
+                // Close the handle before renaming — Windows refuses to
+                // rename a file that is still open.
+                *guard = None;
+                if let Err(e) = self.rotate_files() {
+                    log::error!("[engine-log] Rotation failed: {}", e);
+                }
+            }
+        }
+
+        if guard.is_none() {
+            match self.open() {
+                Ok(open) => *guard = Some(open),
+                Err(e) => {
+                    log::error!("[engine-log] Cannot open {}: {}", self.path().display(), e);
+                    return;
+                }
+            }
+        }
+
+        if let Some(open) = guard.as_mut() {
+            if open.file.write_all(line.as_bytes()).is_ok() && open.file.write_all(b"\n").is_ok() {
+                open.size += bytes;
+            }
+        }
+    }
+
+    /// Rotate now regardless of size and return the path the current file
+    /// was archived to. Holds the writer lock throughout, so a line being
+    /// appended lands wholly in the old file or the new one.
+    pub fn rotate(&self) -> Result<PathBuf, String> {
+        let mut guard = self.file.lock_recover();
+        if let Some(open) = guard.as_mut() {
+            let _ = open.file.flush();
+        }
+        // Closed before the rename, as in append().
+        *guard = None;
+        if !self.path().exists() {
+            return Err(format!(
+                "Nothing to rotate — {} doesn't exist yet",
+                LOG_FILE_NAME
+            ));
+        }
+        self.rotate_files()
+            .map_err(|e| format!("Failed to rotate {}: {}", self.path().display(), e))?;
+        // The next append() reopens a fresh engine.log.
+        Ok(self.dir.join(format!("{}.1", LOG_FILE_NAME)))
+    }
+
+    /// The last `n` lines of the active log file, oldest first.
+    ///
+    /// Reads backwards from the end in TAIL_CHUNK_BYTES blocks until it has
+    /// enough lines, so cost scales with `n`, not the file size. A missing
+    /// file (nothing logged yet this install) yields an empty vec.
+    pub fn tail(&self, n: usize) -> std::io::Result<Vec<String>> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        let mut file = match File::open(self.path()) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut pos = file.metadata()?.len();
+        let mut buf: Vec<u8> = Vec::new();
+        // n lines need n+1 newlines before the first one is known complete
+        // (the file normally ends with one).
+        while pos > 0 && buf.iter().filter(|&&b| b == b'\n').count() <= n {
+            let step = TAIL_CHUNK_BYTES.min(pos);
+            pos -= step;
+            file.seek(SeekFrom::Start(pos))?;
+            let mut chunk = vec![0u8; step as usize];
+            file.read_exact(&mut chunk)?;
+            chunk.extend_from_slice(&buf);
+            buf = chunk;
+        }
+
+        // If we stopped mid-file, the first line may be partial — taking the
+        // last n always drops it, since the loop read more than n.
+        let text = String::from_utf8_lossy(&buf);
+        let lines: Vec<&str> = text.lines().collect();
+        let skip = lines.len().saturating_sub(n);
+        Ok(lines[skip..].iter().map(|line| line.to_string()).collect())
+    }
+
+    fn open(&self) -> std::io::Result<OpenLog> {
+        std::fs::create_dir_all(&self.dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path())?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(OpenLog { file, size })
+    }
+
+    /// Shift engine.log → .1 → .2, dropping the oldest archive.
+    fn rotate_files(&self) -> std::io::Result<()> {
+        let archive = |n: usize| self.dir.join(format!("{}.{}", LOG_FILE_NAME, n));
+
+        let _ = std::fs::remove_file(archive(MAX_LOG_FILES - 1));
+        for n in (1..MAX_LOG_FILES - 1).rev() {
+            let from = archive(n);
+            if from.exists() {
+                std::fs::rename(&from, archive(n + 1))?;
+            }
+        }
+        std::fs::rename(self.path(), archive(1))
+    }
+}
+
+/// Open the log directory in the OS file manager (Finder / Explorer / xdg-open).
+pub fn open_log_dir(app: &AppHandle) -> Result<(), String> {
+    let log = app
+        .try_state::<EngineLogState>()
+        .ok_or_else(|| "Engine log not initialized".to_string())?;
+    let dir = log.ensure_dir()?;
+    crate::open_in_file_manager(dir)
+}
+
+/// The last `lines` lines of `engine.log` — for looking at what happened
+/// before a crash without a live stream. Empty if nothing was logged yet.
+#[tauri::command]
+pub async fn tail_engine_log(
+    state: State<'_, EngineLogState>,
+    lines: usize,
+) -> Result<Vec<String>, String> {
+    state
+        .tail(lines)
+        .map_err(|e| format!("Failed to read {}: {}", state.path().display(), e))
+}
+
+/// Archive the current `engine.log` to `engine.log.1` straight away, as if it
+/// had hit the size limit, and return the archive's path. Safe while the
+/// engine is logging; its next line starts the new file.
+#[tauri::command]
+pub fn rotate_engine_log(state: State<'_, EngineLogState>) -> Result<String, String> {
+    let archived = state.rotate()?;
+    log::info!("[engine-log] Rotated to {}", archived.display());
+    Ok(archived.to_string_lossy().to_string())
+}
+
+/// Return the resolved log directory, creating it if it doesn't exist yet.
+#[tauri::command]
+pub fn get_log_path(state: State<'_, EngineLogState>) -> Result<String, String> {
+    Ok(state.ensure_dir()?.to_string_lossy().to_string())
+}
+
+/// Filter for query_engine_log; every field is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EngineLogQuery {
+    /// Least severe level to include, e.g. "warning" for warnings, errors
+    /// and critical. Entries without a level only show without this.
+    pub level: Option<String>,
+    /// Case-insensitive text the entry must contain.
+    pub contains: Option<String>,
+    /// Most entries to return, newest kept (default 200).
+    pub limit: Option<usize>,
+}
+
+/// One log entry: a leveled line plus any continuation lines after it.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineLogEntry {
+    /// Lowercase level ("debug" … "critical"); `None` for lines before the
+    /// first leveled one.
+    pub level: Option<String>,
+    /// The entry's lines, newline-separated, with the `[stdout]` /
+    /// `[stderr]` tag and colour codes stripped.
+    pub text: String,
+}
+
+/// The newest entries of `engine.log` matching `query`, oldest first. Only
+/// the last 5000 lines are searched.
+#[tauri::command]
+pub async fn query_engine_log(
+    state: State<'_, EngineLogState>,
+    query: EngineLogQuery,
+) -> Result<Vec<EngineLogEntry>, String> {
+    let min_rank = match &query.level {
+        Some(level) => Some(level_rank(level).ok_or_else(|| {
+            format!(
+                "Unknown log level '{}' — expected one of {}",
+                level,
+                LEVELS.join(", ").to_lowercase()
+            )
+        })?),
+        None => None,
+    };
+    let needle = query.contains.as_deref().map(str::to_lowercase);
+    let lines = state
+        .tail(QUERY_SCAN_LINES)
+        .map_err(|e| format!("Failed to read {}: {}", state.path().display(), e))?;
+
+    let mut entries: Vec<(Option<usize>, String)> = Vec::new();
+    for line in lines {
+        let line = clean_line(&line);
+        match (line_level(&line), entries.last_mut()) {
+            (None, Some((_, text))) => {
+                text.push('\n');
+                text.push_str(&line);
+            }
+            (rank, _) => entries.push((rank, line)),
+        }
+    }
+
+    let mut matching: Vec<EngineLogEntry> = entries
+        .into_iter()
+        .filter(|(rank, _)| match min_rank {
+            Some(min) => rank.is_some_and(|r| r >= min),
+            None => true,
+        })
+        .filter(|(_, text)| match &needle {
+            Some(needle) => text.to_lowercase().contains(needle.as_str()),
+            None => true,
+        })
+        .map(|(rank, text)| EngineLogEntry {
+            level: rank.map(|r| LEVELS[r].to_lowercase()),
+            text,
+        })
+        .collect();
+    let limit = query.limit.unwrap_or(QUERY_DEFAULT_LIMIT);
+    let skip = matching.len().saturating_sub(limit);
+    Ok(matching.split_off(skip))
+}
+
+/// Index of `level` in LEVELS, case-insensitively; "warn" is "warning".
+fn level_rank(level: &str) -> Option<usize> {
+    let level = level.trim().to_ascii_uppercase();
+    let level = if level == "WARN" { "WARNING" } else { &level };
+    LEVELS.iter().position(|l| *l == level)
+}
+
+/// The level of a line that starts an entry, or `None` for a continuation.
+/// Matches `LEVEL - …`, `<asctime> - LEVEL - …` and uvicorn's `LEVEL: …`.
+fn line_level(line: &str) -> Option<usize> {
+    let mut fields = line.splitn(3, " - ");
+    let first = fields.next().unwrap_or("");
+    if let Some((prefix, _)) = first.split_once(':') {
+        if let Some(rank) = level_rank(prefix).filter(|_| !prefix.contains(' ')) {
+            return Some(rank);
+        }
+    }
+    let second = fields.next();
+    level_rank(first)
+        .filter(|_| second.is_some())
+        .or_else(|| second.and_then(level_rank))
+}
+
+/// Strip the stream tag engine.log adds and any ANSI colour sequences.
+fn clean_line(line: &str) -> String {
+    let line = line
+        .strip_prefix("[stdout] ")
+        .or_else(|| line.strip_prefix("[stderr] "))
+        .unwrap_or(line);
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // CSI: ESC [ params… final byte in @..~
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
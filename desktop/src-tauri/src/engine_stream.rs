@@ -0,0 +1,330 @@
+//! engine_stream — relay the engine's Server-Sent Event streams to the UI.
+//!
+//! `subscribe_engine_stream(path)` opens `GET {engine}{path}` as an SSE
+//! stream and re-emits every event as an `engine-sse` event tagged with the
+//! subscription id it returns. The stream is read in Rust for the same reason
+//! the other engine calls are: WebView2's loopback isolation on Windows.
+//!
+//! A connection that drops mid-stream is reopened (with `Last-Event-ID`) as
+//! long as the engine is still up; RECONNECT_MAX_FAILURES failed reconnects
+//! in a row end the subscription. A stream the engine closes cleanly is
+//! finished, not reconnected — for model output that means the answer is
+//! complete, and reopening would start a new one. Either way the frontend
+//! gets one `engine-sse-closed` with the reason.
+//!
+//! Subscriptions are tracked in EngineStreams and all cancelled when the
+//! engine is stopped and when the app exits.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::engine_http::EngineHttp;
+use crate::{LockRecover, SidecarError, SidecarState};
+
+/// Wait before reopening a dropped stream, unless the engine sent `retry:`.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+/// Consecutive failed reconnects before a subscription gives up.
+const RECONNECT_MAX_FAILURES: u32 = 5;
+
+/// Live subscriptions by id.
+#[derive(Default)]
+pub struct EngineStreams {
+    next_id: AtomicU64,
+    active: Mutex<HashMap<u64, JoinHandle<()>>>,
+}
+
+/// Payload of `engine-sse`: one event from the engine's stream.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineSseEvent {
+    /// Subscription id from subscribe_engine_stream.
+    pub id: u64,
+    /// The SSE `event:` field; "message" when the engine didn't send one.
+    pub event: String,
+    pub data: String,
+    /// The SSE `id:` field, if any.
+    pub event_id: Option<String>,
+}
+
+/// Payload of `engine-sse-closed`.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineSseClosed {
+    pub id: u64,
+    /// "ended" (the engine closed the stream), "engine-stopped",
+    /// "gave-up" (reconnects kept failing) or "error" (the engine refused
+    /// a reconnect, e.g. with a 404).
+    pub reason: &'static str,
+    pub detail: Option<String>,
+}
+
+/// Open the engine's SSE endpoint at `path` (e.g. "/chat/stream?id=42") and
+/// relay its events as `engine-sse`. Returns the subscription id. Fails
+/// straight away if the engine isn't ready or refuses the request.
+#[tauri::command]
+pub async fn subscribe_engine_stream(
+    app: AppHandle,
+    http: State<'_, EngineHttp>,
+    path: String,
+) -> Result<u64, SidecarError> {
+    if !path.starts_with('/') {
+        return Err(format!("Stream path must start with '/': {}", path).into());
+    }
+    crate::idle::resume_if_suspended(&app).await?;
+    let response = stream_request(&app, &http, &path, None)?
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to open engine stream {}: {}", path, e))?;
+
+    let streams = app.state::<EngineStreams>();
+    let id = streams.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+    // Hold the map while spawning so a stream that ends at once can't try
+    // to remove itself before it has been inserted.
+    let mut active = streams.active.lock_recover();
+    let task = tauri::async_runtime::spawn(relay(app.clone(), id, path.clone(), response));
+    active.insert(id, task);
+    log::info!("[engine_stream] Subscribed #{} to {}", id, path);
+    Ok(id)
+}
+
+/// Stop a subscription. Returns false if it had already ended.
+#[tauri::command]
+pub fn unsubscribe_engine_stream(app: AppHandle, id: u64) -> bool {
+    let task = app
+        .state::<EngineStreams>()
+        .active
+        .lock_recover()
+        .remove(&id);
+    match task {
+        Some(task) => {
+            task.abort();
+            log::info!("[engine_stream] Unsubscribed #{}", id);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Cancel every subscription, emitting `engine-sse-closed` for each. Called
+/// when the engine is stopped and on app exit.
+pub fn cancel_all(app: &AppHandle) {
+    let Some(streams) = app.try_state::<EngineStreams>() else {
+        return;
+    };
+    let tasks: Vec<(u64, JoinHandle<()>)> = streams.active.lock_recover().drain().collect();
+    for (id, task) in tasks {
+        task.abort();
+        emit_closed(app, id, "engine-stopped", None);
+    }
+}
+
+fn stream_request(
+    app: &AppHandle,
+    http: &EngineHttp,
+    path: &str,
+    last_event_id: Option<&str>,
+) -> Result<reqwest::RequestBuilder, SidecarError> {
+    let request = http.stream(app, path)?;
+    Ok(match last_event_id {
+        Some(last) => request.header("Last-Event-ID", last),
+        None => request,
+    })
+}
+
+/// Outcome of one reconnect attempt.
+enum Reconnect {
+    Open(reqwest::Response),
+    /// Worth another try after a pause, while the engine is up.
+    Retry(String),
+    /// End the subscription with this reason.
+    Stop(&'static str, Option<String>),
+}
+
+async fn reconnect(app: &AppHandle, path: &str, last_event_id: Option<&str>) -> Reconnect {
+    let http = app.state::<EngineHttp>();
+    let request = match stream_request(app, &http, path, last_event_id) {
+        Ok(request) => request,
+        // Alive but not answering /health yet, e.g. right after a restart.
+        Err(SidecarError::HealthCheckFailed) if engine_up(app) => {
+            return Reconnect::Retry("Engine is not answering".to_string())
+        }
+        Err(SidecarError::NotRunning | SidecarError::HealthCheckFailed) => {
+            return Reconnect::Stop("engine-stopped", None)
+        }
+        Err(e) => return Reconnect::Stop("error", Some(e.to_string())),
+    };
+    match request.send().await {
+        Ok(response) if response.status().is_success() => Reconnect::Open(response),
+        // The engine answered and said no; asking again won't change that.
+        Ok(response) => Reconnect::Stop(
+            "error",
+            Some(format!(
+                "Engine refused to reopen {}: {}",
+                path,
+                response.status()
+            )),
+        ),
+        Err(e) => Reconnect::Retry(e.to_string()),
+    }
+}
+
+/// Why one connection's read loop stopped.
+enum StreamEnd {
+    /// The engine closed the stream.
+    Finished,
+    /// The connection broke mid-stream.
+    Dropped(String),
+}
+
+/// Read `response` and every reconnect after it until the subscription ends,
+/// then deregister and emit `engine-sse-closed`.
+async fn relay(app: AppHandle, id: u64, path: String, response: reqwest::Response) {
+    let mut parser = SseParser::default();
+    let mut response = Some(response);
+    let mut failures = 0u32;
+    let (reason, detail) = loop {
+        let current = match response.take() {
+            Some(current) => current,
+            None => match reconnect(&app, &path, parser.last_event_id.as_deref()).await {
+                Reconnect::Open(current) => {
+                    failures = 0;
+                    current
+                }
+                Reconnect::Stop(reason, detail) => break (reason, detail),
+                Reconnect::Retry(e) => {
+                    failures += 1;
+                    if failures >= RECONNECT_MAX_FAILURES {
+                        break ("gave-up", Some(e));
+                    }
+                    tokio::time::sleep(parser.retry.unwrap_or(RECONNECT_DELAY)).await;
+                    continue;
+                }
+            },
+        };
+        match read_events(&app, id, current, &mut parser).await {
+            StreamEnd::Finished => break ("ended", None),
+            StreamEnd::Dropped(e) => {
+                if !engine_up(&app) {
+                    break ("engine-stopped", None);
+                }
+                log::warn!("[engine_stream] #{} dropped, reconnecting: {}", id, e);
+                // Half an event from the old connection must not merge with
+                // the first one from the new.
+                parser.event.clear();
+                parser.data.clear();
+                tokio::time::sleep(parser.retry.unwrap_or(RECONNECT_DELAY)).await;
+            }
+        }
+    };
+    app.state::<EngineStreams>()
+        .active
+        .lock_recover()
+        .remove(&id);
+    emit_closed(&app, id, reason, detail);
+}
+
+/// Feed `response` through `parser`, emitting each complete event.
+async fn read_events(
+    app: &AppHandle,
+    id: u64,
+    mut response: reqwest::Response,
+    parser: &mut SseParser,
+) -> StreamEnd {
+    let mut buffer: Vec<u8> = Vec::new();
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => return StreamEnd::Finished,
+            Err(e) => return StreamEnd::Dropped(e.to_string()),
+        };
+        buffer.extend_from_slice(&chunk);
+        // Split on whole lines only, so a chunk boundary inside a multi-byte
+        // character can't corrupt it.
+        while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if let Some((event, data)) = parser.line(line) {
+                let _ = app.emit(
+                    "engine-sse",
+                    EngineSseEvent {
+                        id,
+                        event,
+                        data,
+                        event_id: parser.last_event_id.clone(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// True while the engine process is alive (or an external engine is in use),
+/// whether or not it is currently answering.
+fn engine_up(app: &AppHandle) -> bool {
+    let sidecars = app.state::<SidecarState>();
+    let engine = sidecars.engine();
+    crate::external_engine_url(app, engine).is_some() || engine.child.lock_recover().is_some()
+}
+
+fn emit_closed(app: &AppHandle, id: u64, reason: &'static str, detail: Option<String>) {
+    log::info!("[engine_stream] #{} closed: {}", id, reason);
+    let _ = app.emit("engine-sse-closed", EngineSseClosed { id, reason, detail });
+}
+
+/// Line-by-line SSE parser, per the WHATWG event-stream format.
+#[derive(Default)]
+struct SseParser {
+    event: String,
+    data: String,
+    /// Kept across events and reconnects, and sent back as `Last-Event-ID`.
+    last_event_id: Option<String>,
+    /// Reconnect delay the engine asked for with `retry:`.
+    retry: Option<Duration>,
+}
+
+impl SseParser {
+    /// Take one line (without its terminator). Returns `(event, data)` when a
+    /// blank line completes an event that carried data.
+    fn line(&mut self, line: &str) -> Option<(String, String)> {
+        if line.is_empty() {
+            let event = std::mem::take(&mut self.event);
+            let mut data = std::mem::take(&mut self.data);
+            if data.is_empty() {
+                return None;
+            }
+            data.pop(); // the trailing '\n' added below
+            let event = if event.is_empty() {
+                "message".to_string()
+            } else {
+                event
+            };
+            return Some((event, data));
+        }
+        if line.starts_with(':') {
+            return None; // comment / keep-alive
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = Some(value.to_string()),
+            "retry" => {
+                if let Ok(ms) = value.parse::<u64>() {
+                    self.retry = Some(Duration::from_millis(ms));
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
@@ -0,0 +1,128 @@
+//! ephemeral — run the engine against a throwaway data dir.
+//!
+//! For tests and demos: `start_sidecar_ephemeral` creates a fresh directory
+//! under the OS temp dir, points the engine's `MATRX_DATA_DIR` at it and
+//! starts the engine as start_sidecar would (crash restarts included, with
+//! the same dir). The next stop_sidecar ends the mode and deletes the
+//! directory; sidecar_status reports `ephemeral: true` until then.
+//!
+//! The path is also kept in the settings store, so a directory left behind
+//! by a crash or a quit is removed on the next launch (see clean_stale).
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+
+use crate::{settings, LockRecover, SidecarError, SidecarProcess, SidecarState};
+
+/// Name prefix of the temp dirs; only such dirs are ever deleted.
+const DIR_PREFIX: &str = "matrx-ephemeral-";
+
+/// Start the engine against a new, empty temp data dir. Fails with
+/// `AlreadyRunning` if an engine is up — stop it first — and otherwise like
+/// start_sidecar, in which case the dir is removed again.
+#[tauri::command]
+pub async fn start_sidecar_ephemeral(
+    app: AppHandle,
+    ready_timeout_ms: Option<u64>,
+) -> Result<(), SidecarError> {
+    let sidecars = app.state::<SidecarState>();
+    let engine = sidecars.engine();
+    if settings::dev_engine_url(&app).is_some() {
+        let msg = "An external dev engine is in use — it keeps its own data dir";
+        return Err(msg.to_string().into());
+    }
+    if engine.child.lock_recover().is_some() || engine.adopted.lock_recover().is_some() {
+        return Err(SidecarError::AlreadyRunning);
+    }
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("{}{}-{}", DIR_PREFIX, std::process::id(), nanos));
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.to_string_lossy().to_string();
+    settings::set(
+        &app,
+        settings::KEY_EPHEMERAL_DATA_DIR,
+        serde_json::json!(path),
+    )?;
+    *engine.ephemeral_dir.lock_recover() = Some(dir);
+    log::info!("[ephemeral] Starting the engine with data dir {}", path);
+
+    let result = crate::start_sidecar(app.clone(), None, ready_timeout_ms).await;
+    if result.is_err() {
+        // A failed start leaves no engine behind (start_sidecar kills a
+        // half-started one), so nothing is using the dir.
+        finish(&app, engine);
+    }
+    result
+}
+
+/// The `MATRX_DATA_DIR` override for the next engine spawn, if ephemeral.
+/// Applied by spawn_sidecar after the user's sidecar config, so it wins.
+pub fn spawn_env(state: &SidecarProcess) -> Option<(&'static str, String)> {
+    let dir = state.ephemeral_dir.lock_recover();
+    dir.as_ref()
+        .map(|dir| ("MATRX_DATA_DIR", dir.to_string_lossy().to_string()))
+}
+
+/// End ephemeral mode and delete its dir. Called by stop_sidecar once the
+/// engine is gone; a no-op unless `state` is an ephemeral engine.
+pub fn finish(app: &AppHandle, state: &SidecarProcess) {
+    let Some(dir) = state.ephemeral_dir.lock_recover().take() else {
+        return;
+    };
+    remove(&dir);
+    if let Err(e) = settings::set(
+        app,
+        settings::KEY_EPHEMERAL_DATA_DIR,
+        serde_json::Value::Null,
+    ) {
+        log::warn!("[ephemeral] {}", e);
+    }
+}
+
+/// Remove the dir of an ephemeral engine from a previous session. Called
+/// from setup, before anything can spawn the engine.
+pub fn clean_stale(app: &AppHandle) {
+    let Some(path) = settings::ephemeral_data_dir(app) else {
+        return;
+    };
+    log::info!(
+        "[ephemeral] Removing data dir left by a previous session: {}",
+        path
+    );
+    remove(Path::new(&path));
+    if let Err(e) = settings::set(
+        app,
+        settings::KEY_EPHEMERAL_DATA_DIR,
+        serde_json::Value::Null,
+    ) {
+        log::warn!("[ephemeral] {}", e);
+    }
+}
+
+/// Delete `dir` — but only one of ours, so a hand-edited settings file
+/// can't point this at real data.
+fn remove(dir: &Path) {
+    let ours = dir.starts_with(std::env::temp_dir())
+        && dir
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with(DIR_PREFIX));
+    if !ours {
+        log::warn!(
+            "[ephemeral] Not removing {}: not an ephemeral data dir",
+            dir.display()
+        );
+        return;
+    }
+    match std::fs::remove_dir_all(dir) {
+        Ok(()) => log::info!("[ephemeral] Removed {}", dir.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log::error!("[ephemeral] Failed to remove {}: {}", dir.display(), e),
+    }
+}
@@ -0,0 +1,97 @@
+//! features — optional engine features, persisted and passed on every spawn.
+//!
+//! The selection lives in the `feature-flags` setting and is applied as
+//! `--enable NAME` arguments each time the engine starts; run.py turns each
+//! one into the env var that gates the feature. Flags are only read at
+//! startup, so `set_feature_flags` on a running engine emits
+//! `restart-required` for the UI to offer a restart instead of the change
+//! silently waiting for the next launch.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{settings, LockRecover, SidecarState};
+
+/// Every feature the engine understands. Keep in sync with
+/// FEATURE_ENV_VARS in run.py.
+pub const KNOWN_FEATURES: &[&str] = &["bridge-broadcast", "local-scheduler"];
+
+/// Flags the running engine was started with; `None` before the first spawn.
+static APPLIED: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+/// Payload of `restart-required`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RestartRequired {
+    /// What changed: "feature-flags", "engine-config",
+    /// "engine-config-file", "resource-limits" or "settings-import".
+    pub reason: &'static str,
+    /// False when the setting was changed back to what the engine is already
+    /// running with, so an earlier prompt can be dismissed.
+    pub required: bool,
+}
+
+/// The saved flags, minus any this build doesn't know, in KNOWN_FEATURES order.
+fn saved(app: &AppHandle) -> Vec<String> {
+    normalize(&settings::feature_flags(app))
+}
+
+fn normalize(flags: &[String]) -> Vec<String> {
+    KNOWN_FEATURES
+        .iter()
+        .filter(|known| flags.iter().any(|f| f == *known))
+        .map(|known| known.to_string())
+        .collect()
+}
+
+/// `--enable NAME` for each saved flag. Called by spawn_sidecar for the
+/// engine, which also records them as the running engine's flags.
+pub fn spawn_args(app: &AppHandle) -> Vec<String> {
+    let flags = saved(app);
+    let args = flags
+        .iter()
+        .flat_map(|flag| ["--enable".to_string(), flag.clone()])
+        .collect();
+    *APPLIED.lock_recover() = Some(flags);
+    args
+}
+
+/// The engine features switched on for the next launch.
+#[tauri::command]
+pub fn get_feature_flags(app: AppHandle) -> Vec<String> {
+    saved(&app)
+}
+
+/// Replace the enabled features with `flags` and save them. Every name must
+/// be in KNOWN_FEATURES. Returns the saved set. If the engine is running,
+/// emits `restart-required` (`required: false` when it already runs with
+/// exactly these flags) — the change takes effect on the next start.
+#[tauri::command]
+pub fn set_feature_flags(app: AppHandle, flags: Vec<String>) -> Result<Vec<String>, String> {
+    if let Some(unknown) = flags.iter().find(|f| !KNOWN_FEATURES.contains(&f.as_str())) {
+        return Err(format!(
+            "Unknown feature '{}' — expected one of {}",
+            unknown,
+            KNOWN_FEATURES.join(", ")
+        ));
+    }
+    let flags = normalize(&flags);
+    settings::set(&app, settings::KEY_FEATURE_FLAGS, serde_json::json!(flags))?;
+    log::info!("[features] Enabled features: {:?}", flags);
+
+    let sidecars = app.state::<SidecarState>();
+    let engine = sidecars.engine();
+    let running = engine.child.lock_recover().is_some();
+    let applied = APPLIED.lock_recover().clone();
+    if let (true, Some(applied)) = (running, applied) {
+        let _ = app.emit(
+            "restart-required",
+            RestartRequired {
+                reason: "feature-flags",
+                required: applied != flags,
+            },
+        );
+    }
+    Ok(flags)
+}
@@ -0,0 +1,128 @@
+//! idle — suspend the engine after a stretch of doing nothing.
+//!
+//! With `idle-suspend-minutes` above 0, a background task asks the engine's
+//! `/admin/status` every IDLE_CHECK_INTERVAL when it last ran a tool call.
+//! Once that is longer ago than the setting and nothing is in flight, the
+//! engine is paused through the same path as pause_sidecar and marked
+//! suspended: the tray says so, `sidecar-suspended` is emitted and
+//! sidecar_status reports `suspended`.
+//!
+//! Unlike a pause the user asked for, a suspension lifts itself: the proxy
+//! commands (jobs, engine config, SSE streams) call resume_if_suspended
+//! before talking to the engine. Only a process the app spawned is
+//! suspended, never an external dev engine.
+
+use std::sync::atomic::Ordering;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::engine_http::EngineHttp;
+use crate::{settings, LockRecover, SidecarError, SidecarState};
+
+/// How often the idle timer looks at the engine.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The parts of `/admin/status` the idle timer reads.
+#[derive(Deserialize)]
+struct AdminStatus {
+    #[serde(default)]
+    active_jobs: u64,
+    /// Epoch seconds of the engine's last tool call.
+    last_activity: Option<f64>,
+}
+
+/// Payload of `sidecar-suspended`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SidecarSuspended {
+    /// How long the engine had been idle.
+    pub idle_secs: u64,
+}
+
+/// Start the idle timer. Called once from setup; a setting of 0 makes each
+/// check a no-op, so the feature can be switched on and off at runtime.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(IDLE_CHECK_INTERVAL).await;
+            check(&app).await;
+        }
+    });
+}
+
+async fn check(app: &AppHandle) {
+    let minutes = settings::idle_suspend_minutes(app);
+    if minutes == 0 {
+        return;
+    }
+    let sidecars = app.state::<SidecarState>();
+    let engine = sidecars.engine();
+    if crate::external_engine_url(app, engine).is_some()
+        || engine.child.lock_recover().is_none()
+        || !engine.ready.load(Ordering::SeqCst)
+        || engine.paused.load(Ordering::SeqCst)
+    {
+        return;
+    }
+
+    let http = app.state::<EngineHttp>();
+    let Ok(request) = http.request(app, reqwest::Method::GET, &["admin", "status"]) else {
+        return;
+    };
+    let status = match request.send().await.and_then(|r| r.error_for_status()) {
+        Ok(response) => response.json::<AdminStatus>().await,
+        Err(e) => Err(e),
+    };
+    let status = match status {
+        Ok(status) => status,
+        // Not answering is the watchdog's business, not ours.
+        Err(e) => {
+            log::error!("[idle] Could not read engine status: {}", e);
+            return;
+        }
+    };
+    // An engine too old to report last_activity is never suspended.
+    let Some(last_activity) = status.last_activity else {
+        return;
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+    let idle_secs = (now - last_activity).max(0.0) as u64;
+    if status.active_jobs > 0 || idle_secs < minutes * 60 {
+        return;
+    }
+
+    if let Err(e) = crate::set_engine_paused(app, true).await {
+        log::error!("[idle] Could not suspend the idle engine: {}", e);
+        return;
+    }
+    engine.suspended.store(true, Ordering::SeqCst);
+    crate::set_tray_status(
+        app,
+        &format!(
+            "Status: Suspended (idle, port {})",
+            engine.port.load(Ordering::SeqCst)
+        ),
+    );
+    log::info!("[idle] Engine idle for {}s — suspended", idle_secs);
+    let _ = app.emit("sidecar-suspended", SidecarSuspended { idle_secs });
+}
+
+/// Resume the engine if the idle timer suspended it. A user's own pause is
+/// left alone. Called by the proxy commands before each engine request.
+pub async fn resume_if_suspended(app: &AppHandle) -> Result<(), SidecarError> {
+    let suspended = app
+        .state::<SidecarState>()
+        .engine()
+        .suspended
+        .load(Ordering::SeqCst);
+    if !suspended {
+        return Ok(());
+    }
+    log::info!("[idle] Request for a suspended engine — resuming it");
+    crate::set_engine_paused(app, false).await
+}
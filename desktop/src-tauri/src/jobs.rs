@@ -0,0 +1,86 @@
+//! jobs — list and cancel the engine's in-flight tool calls.
+//!
+//! Thin proxies over the engine's `GET /jobs` and `POST /jobs/{id}/cancel`,
+//! for the UI's "stop generation" button. Both go through EngineHttp, so
+//! they fail with `NotRunning` when there is no engine.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::engine_http::EngineHttp;
+use crate::SidecarError;
+
+/// One running tool call, as reported by the engine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineJob {
+    /// Pass to cancel_job. Unique across the engine's connections.
+    pub id: String,
+    /// The id the client chose for the call.
+    pub request_id: String,
+    pub tool: String,
+    /// Seconds since the Unix epoch.
+    pub started_at: f64,
+    pub elapsed_secs: f64,
+}
+
+#[derive(Deserialize)]
+struct JobList {
+    jobs: Vec<EngineJob>,
+}
+
+/// Result of cancel_job. `cancelled` is false when the job had already
+/// finished (or never existed).
+#[derive(Debug, Clone, Serialize)]
+pub struct JobCancelResult {
+    pub id: String,
+    pub cancelled: bool,
+}
+
+/// Tool calls the engine is currently running, oldest first.
+#[tauri::command]
+pub async fn list_active_jobs(
+    app: AppHandle,
+    http: State<'_, EngineHttp>,
+) -> Result<Vec<EngineJob>, SidecarError> {
+    crate::idle::resume_if_suspended(&app).await?;
+    let list: JobList = http
+        .request(&app, reqwest::Method::GET, &["jobs"])?
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to list engine jobs: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected /jobs response: {}", e))?;
+    Ok(list.jobs)
+}
+
+/// Cancel one job from list_active_jobs. The client that started it gets the
+/// engine's usual "was cancelled" error for the call.
+#[tauri::command]
+pub async fn cancel_job(
+    app: AppHandle,
+    http: State<'_, EngineHttp>,
+    id: String,
+) -> Result<JobCancelResult, SidecarError> {
+    crate::idle::resume_if_suspended(&app).await?;
+    let response = http
+        .request(&app, reqwest::Method::POST, &["jobs", &id, "cancel"])?
+        .send()
+        .await
+        .map_err(|e| format!("Failed to cancel job {}: {}", id, e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(JobCancelResult {
+            id,
+            cancelled: false,
+        });
+    }
+    response
+        .error_for_status()
+        .map_err(|e| format!("Failed to cancel job {}: {}", id, e))?;
+    log::info!("[jobs] Cancelled engine job {}", id);
+    Ok(JobCancelResult {
+        id,
+        cancelled: true,
+    })
+}
@@ -0,0 +1,172 @@
+//! lan_access — let other devices on the network reach the engine.
+//!
+//! The engine normally binds 127.0.0.1. With the `lan-access` setting on it
+//! is spawned with `MATRX_HOST=0.0.0.0` so a phone on the same network can
+//! use it at the machine's LAN address, authenticating with the engine
+//! token as the shell does. The shell itself keeps talking over loopback
+//! (see SidecarProcess::base_url).
+//!
+//! The engine demands the token from every client that isn't on this
+//! machine, on every route — the admin and bootstrap routes that are public
+//! over loopback included (app/api/auth.py). set_lan_access checks that the
+//! engine says so (`lan_auth` in /version) once it has restarted onto the
+//! network, and puts it back on loopback if it doesn't.
+//!
+//! Opening the engine to the network is never a side effect: set_lan_access
+//! only turns it on with `confirm: true`, and a notification says so every
+//! time it does.
+
+use std::net::{IpAddr, UdpSocket};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::engine_http::{self, EngineHttp};
+use crate::{settings, LockRecover, SidecarError, SidecarState, ENGINE_SIDECAR};
+
+const LOOPBACK_HOST: &str = "127.0.0.1";
+const WILDCARD_HOST: &str = "0.0.0.0";
+
+/// What set_lan_access returns once LAN access is on.
+#[derive(Debug, Clone, Serialize)]
+pub struct LanAccessInfo {
+    /// The engine's address on the LAN, e.g. `http://192.168.1.20:22140`.
+    pub url: String,
+    /// The engine token to send as `X-Engine-Token` (or `?engine_token=`);
+    /// the engine refuses LAN clients without it. Changes on every restart.
+    pub token: Option<String>,
+}
+
+/// The host the next engine spawn binds, for its `MATRX_HOST`.
+pub fn bind_host(app: &AppHandle) -> &'static str {
+    if settings::lan_access(app) {
+        WILDCARD_HOST
+    } else {
+        LOOPBACK_HOST
+    }
+}
+
+/// Turn LAN access on or off and restart a running engine onto the new
+/// bind address. Turning it on requires `confirm: true` — the engine is
+/// then reachable by anything on the network — and a running engine, which
+/// must report that it enforces the token for LAN clients; it returns the
+/// LAN URL and token to show. Turning it off returns `None`, and a stopped
+/// engine picks that up on its next start. Not available with an external
+/// dev engine, which binds wherever it was started.
+#[tauri::command]
+pub async fn set_lan_access(
+    app: AppHandle,
+    enabled: bool,
+    confirm: Option<bool>,
+) -> Result<Option<LanAccessInfo>, SidecarError> {
+    if enabled && confirm != Some(true) {
+        let msg = "Turning on LAN access needs confirm: true — it opens the engine to the network";
+        return Err(msg.to_string().into());
+    }
+    let sidecars = app.state::<SidecarState>();
+    let engine = sidecars.engine();
+    // An adopted engine is ours to restart; only a dev engine is off limits.
+    if settings::dev_engine_url(&app).is_some() {
+        return Err("LAN access can't be changed for an external dev engine"
+            .to_string()
+            .into());
+    }
+
+    let running = engine.child.lock_recover().is_some() || engine.adopted.lock_recover().is_some();
+    if enabled && !running {
+        let msg = "Start the engine before turning on LAN access — it must first confirm that \
+                   it requires the engine token from other devices";
+        return Err(msg.to_string().into());
+    }
+
+    settings::set(
+        &app,
+        settings::KEY_LAN_ACCESS,
+        serde_json::Value::Bool(enabled),
+    )?;
+    let port = if running {
+        crate::restart_sidecar_process(&app, ENGINE_SIDECAR).await?
+    } else {
+        engine.port.load(Ordering::SeqCst)
+    };
+    if enabled && !enforces_lan_auth(&app).await {
+        log::warn!("[lan] Engine doesn't enforce the token for LAN clients — back to loopback");
+        let off = serde_json::Value::Bool(false);
+        settings::set(&app, settings::KEY_LAN_ACCESS, off)?;
+        crate::restart_sidecar_process(&app, ENGINE_SIDECAR).await?;
+        let msg = "This engine doesn't require its token from other devices, so LAN access \
+                   stays off";
+        return Err(msg.to_string().into());
+    }
+    log::info!(
+        "[lan] LAN access {} (engine binds {})",
+        if enabled { "on" } else { "off" },
+        bind_host(&app)
+    );
+    if !enabled {
+        return Ok(None);
+    }
+
+    let host = lan_ip().map_or_else(|| "<this computer's IP>".to_string(), |ip| ip.to_string());
+    let url = format!("http://{}:{}", host, port);
+    crate::notifications::notify(
+        &app,
+        "AI Matrx engine is open to your network",
+        &format!(
+            "Devices on this network can reach the engine at {}. Turn LAN access off when \
+             you no longer need it.",
+            url
+        ),
+    );
+    let token = engine.token.lock_recover().clone();
+    Ok(Some(LanAccessInfo { url, token }))
+}
+
+/// Whether the running engine reports (`lan_auth` in /version) that it
+/// refuses LAN clients without the engine token. False if it can't be asked.
+async fn enforces_lan_auth(app: &AppHandle) -> bool {
+    let http = app.state::<EngineHttp>();
+    let Ok(request) = http.request(app, reqwest::Method::GET, &["version"]) else {
+        return false;
+    };
+    let request = request.timeout(Duration::from_secs(3));
+    match engine_http::send_retrying(request)
+        .await
+        .and_then(|r| r.error_for_status())
+    {
+        Ok(response) => response
+            .json::<serde_json::Value>()
+            .await
+            .is_ok_and(|body| body["lan_auth"] == true),
+        Err(_) => false,
+    }
+}
+
+/// The host a sidecar is bound to: the address it reported binding, else
+/// the one it was told to bind. `None` for an external or adopted engine,
+/// whose bind address we don't control.
+pub fn effective_bind_host(app: &AppHandle, state: &crate::SidecarProcess) -> Option<String> {
+    if crate::external_engine_url(app, state).is_some() || state.adopted.lock_recover().is_some() {
+        return None;
+    }
+    if let Some((host, _)) = state.bound_addr.lock_recover().clone() {
+        return Some(host);
+    }
+    let host = if state.is_engine() {
+        bind_host(app)
+    } else {
+        LOOPBACK_HOST
+    };
+    Some(host.to_string())
+}
+
+/// This machine's address on the LAN: the local end of a route to a public
+/// address. Connecting a UDP socket sends nothing.
+fn lan_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((WILDCARD_HOST, 0)).ok()?;
+    socket.connect(("8.8.8.8", 80)).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
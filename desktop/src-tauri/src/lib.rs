@@ -1,6 +1,6 @@
-use serde::Serialize;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use tauri::{Emitter, Manager};
 #[cfg(unix)]
 use libc;
@@ -8,8 +8,38 @@ use libc;
 /// Global flag: set to true once graceful_shutdown_sync has run.
 /// Prevents the cleanup from running twice (tray quit → ExitRequested both fire).
 static SHUTDOWN_DONE: AtomicBool = AtomicBool::new(false);
+
+/// Headless mode for CI and automation, switched on by `MATRX_HEADLESS=1`
+/// (or `true` / `yes`) in the environment. The app then stays out of the
+/// way of whatever is driving it:
+///
+/// - no tray icon, and the main window is never shown (show_main_window is a
+///   no-op, so deep links, second launches and the self-check can't raise it);
+/// - closing the window quits instead of hiding to the tray, so the process
+///   exits normally on a close or a termination signal;
+/// - the global show/hide and quit hotkeys aren't registered;
+/// - desktop notifications are only logged.
+///
+/// Everything else — the sidecar commands, events and settings — works as
+/// usual, so integration tests can drive the engine through Tauri.
+fn headless() -> bool {
+    static HEADLESS: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *HEADLESS.get_or_init(|| {
+        std::env::var("MATRX_HEADLESS").is_ok_and(|v| {
+            matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes")
+        })
+    })
+}
+
+/// Set once graceful_shutdown_sync has *finished* (SHUTDOWN_DONE only says it
+/// started). Quit paths that find cleanup already in flight wait on this
+/// before exiting so the parent never dies ahead of the sidecar.
+static SHUTDOWN_COMPLETE: AtomicBool = AtomicBool::new(false);
+
+/// Set while a `confirm-quit` prompt is waiting on confirm_quit().
+static QUIT_PENDING: AtomicBool = AtomicBool::new(false);
 use tauri::{
-    menu::{MenuBuilder, MenuItemBuilder},
+    menu::{CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
 };
 use tauri_plugin_autostart::MacosLauncher;
@@ -30,6 +60,123 @@ use downloads::commands::*;
 mod floating_overlay;
 use floating_overlay::*;
 
+mod window_state;
+
+mod engine_log;
+use engine_log::{get_log_path, query_engine_log, rotate_engine_log, tail_engine_log};
+
+mod settings;
+use settings::{get_setting, set_setting};
+
+mod settings_transfer;
+use settings_transfer::{export_settings, import_settings};
+
+mod shortcuts;
+use shortcuts::{set_global_shortcut, set_quit_shortcut};
+
+mod app_menu;
+
+mod autostart;
+use autostart::{is_autostart_enabled, set_autostart};
+
+mod reset;
+use reset::reset_app_data;
+
+mod diagnostics;
+use diagnostics::{dump_engine_state, export_diagnostics};
+
+mod sidecar_error;
+use sidecar_error::SidecarError;
+
+mod panic_hook;
+
+mod engine_http;
+use engine_http::EngineHttp;
+
+mod engine_stream;
+use engine_stream::{subscribe_engine_stream, unsubscribe_engine_stream, EngineStreams};
+
+mod jobs;
+use jobs::{cancel_job, list_active_jobs};
+
+mod engine_config;
+use engine_config::{get_engine_config, patch_engine_config, set_engine_config_file};
+
+mod notifications;
+use notifications::request_notification_permission;
+
+mod data_dir;
+use data_dir::set_data_dir;
+
+mod features;
+use features::{get_feature_flags, set_feature_flags};
+
+mod power;
+
+mod idle;
+
+mod version_compat;
+use version_compat::check_version_compat;
+
+mod adopt;
+use adopt::{check_port, probe_existing_sidecar};
+
+mod signals;
+
+mod openapi;
+use openapi::get_openapi_schema;
+
+mod ephemeral;
+use ephemeral::start_sidecar_ephemeral;
+
+mod resource_limits;
+use resource_limits::{get_resource_limits, set_resource_limits};
+
+mod splash;
+
+mod stray_engines;
+use stray_engines::kill_stray_engines;
+
+mod lifecycle_log;
+use lifecycle_log::get_lifecycle_history;
+
+mod app_log;
+use app_log::set_app_log_level;
+
+mod scheduled_restart;
+use scheduled_restart::{cancel_scheduled_restart, schedule_restart};
+
+mod startup_timing;
+
+mod lan_access;
+use lan_access::set_lan_access;
+
+mod watchdog_pause;
+use watchdog_pause::{pause_watchdog, resume_watchdog};
+
+mod disk_space;
+use disk_space::data_dir_space;
+
+mod self_check;
+use self_check::run_self_check;
+
+mod spawn_gate;
+use spawn_gate::{SpawnGate, SpawnTurn};
+
+/// `Mutex::lock` that recovers from poisoning. A panic while a guard was held
+/// (see panic_hook) must not wedge sidecar control for the rest of the
+/// session; the protected state here is always left consistent between
+/// statements, so the inner guard is safe to keep using.
+trait LockRecover<T> {
+    fn lock_recover(&self) -> MutexGuard<'_, T>;
+}
+
+impl<T> LockRecover<T> for Mutex<T> {
+    fn lock_recover(&self) -> MutexGuard<'_, T> {
+        self.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
 // ── proxy_fetch types ────────────────────────────────────────────────────────
 #[derive(Serialize)]
 struct FetchResponse {
@@ -39,11 +186,11 @@ struct FetchResponse {
     final_url: String,
 }
 
-/// Kill orphaned engine sidecar processes from a previous session.
+/// Kill an engine a previous session left running on `port`.
 ///
 /// **Ownership note (see ARCHITECTURE.md → Lifecycle):**
-/// This function ONLY targets the engine sidecar binaries. It does NOT
-/// touch cloudflared, llama-server, or any other service the engine owns.
+/// This function ONLY targets the engine sidecar. It does NOT touch
+/// cloudflared, llama-server, or any other service the engine owns.
 /// Each level of the ownership tree only kills its own children:
 ///
 ///   Rust (this file) → engine sidecar
@@ -55,106 +202,34 @@ struct FetchResponse {
 /// behind by a previous crashed engine — that's the engine reclaiming its
 /// own children, not Rust reaching across.
 ///
-/// Targets here are only the engine sidecar binaries:
-///   macOS / Linux: pkill -f matches the full command line; one regex
-///     covers all known engine names (current and legacy).
-///       • "Matrx Engine"          (macOS Helper-app binary, with space)
-///       • "matrx-engine"          (Linux flat binary; also legacy macOS
-///                                  builds where it lived in Contents/MacOS/)
-///       • "aimatrx-engine"        (legacy installs that pre-date the
-///                                  Helper-app rename — sweep them so
-///                                  upgrade paths don't leave orphans)
-///   Windows: taskkill /IM matches by exact image (process) name, so we
-///     have to issue one call per image variant.
+/// The target is found through the discovery file, not by process name: a
+/// second copy of the app runs its own engine on another port, and a
+/// name-based pkill / taskkill would take that one down too. Only an engine
+/// the discovery file places on `port` — ours, since callers only pass the
+/// port our managed child used — is terminated. One that isn't recorded
+/// there is left alone and the spawn falls back to another port.
 ///
 /// Discovery file safety: only deletes local.json if the PID it contains
 /// is no longer alive. This prevents a race where Rust cleanup runs AFTER
 /// the new engine has already written its own PID — we would otherwise
 /// delete the new engine's discovery record on startup.
-fn kill_orphaned_sidecars() {
-    #[cfg(unix)]
-    {
-        // Single regex covers the macOS Helper-app process name ("Matrx Engine"),
-        // the Linux flat binary ("matrx-engine"), and the legacy "aimatrx-engine"
-        // name from older installs. pkill -f matches against the full command
-        // line, so a substring of the executable path is enough.
-        const ENGINE_PATTERN: &str = "Matrx Engine|matrx-engine|aimatrx-engine";
-
-        let _ = std::process::Command::new("pkill")
-            .args(["-TERM", "-f", ENGINE_PATTERN])
-            .output();
-        std::thread::sleep(std::time::Duration::from_millis(500));
-        let _ = std::process::Command::new("pkill")
-            .args(["-KILL", "-f", ENGINE_PATTERN])
-            .output();
-        // NOTE: cloudflared is intentionally NOT killed here. It is the
-        // engine's child, and the engine's preflight reclaims it on startup.
-        // Killing it from Rust during a normal shutdown races against the
-        // engine's tunnel.stop() and produces "ended unexpectedly" errors.
-    }
-    #[cfg(windows)]
-    {
-        // /F = force, /T = kill entire process tree (children: uvicorn workers,
-        // Playwright Chromium, etc.) — without /T those children survive and
-        // continue to hold port 22140 and file system locks.
-        //
-        // We issue one taskkill per known image name. The current build target
-        // is "matrx-engine-x86_64-pc-windows-msvc.exe"; "aimatrx-engine-*.exe"
-        // is the legacy name that may still be running on machines that just
-        // upgraded to a build that uses the new name — sweep both so the
-        // upgrade is clean. /T cascades to the engine's process tree
-        // (cloudflared, etc.) without us having to name them explicitly —
-        // that is the engine signaling its children via process-tree
-        // termination, not Rust reaching across.
-        for image in [
-            "matrx-engine-x86_64-pc-windows-msvc.exe",
-            "matrx-engine.exe",
-            "aimatrx-engine-x86_64-pc-windows-msvc.exe",
-            "aimatrx-engine.exe",
-        ] {
-            let _ = std::process::Command::new("taskkill")
-                .args(["/F", "/T", "/IM", image])
-                .output();
+fn kill_orphaned_sidecars(port: u16) {
+    if let Some(pid) = orphaned_engine_on_port(port) {
+        log::info!(
+            "[sidecar] Stopping orphaned engine pid={} on port {}",
+            pid, port
+        );
+        if !terminate_orphaned_engine(pid, ORPHAN_TERM_TIMEOUT) {
+            log::warn!("[sidecar] Orphaned engine pid={} did not exit", pid);
         }
-        // NOTE: cloudflared.exe is intentionally NOT killed here. /T above
-        // already cascades to it as a child of the engine process tree. A
-        // standalone taskkill /IM cloudflared.exe would also kill cloudflared
-        // instances spawned by other applications on the same machine.
     }
 
     // Remove the discovery file only if its recorded PID is no longer alive.
-    let discovery_path = {
-        #[cfg(unix)]
-        { std::env::var("HOME").ok().map(|h| std::path::PathBuf::from(h).join(".matrx").join("local.json")) }
-        #[cfg(windows)]
-        { std::env::var("USERPROFILE").ok().map(|h| std::path::PathBuf::from(h).join(".matrx").join("local.json")) }
+    let Some(path) = discovery_file_path() else {
+        return;
     };
-
-    if let Some(path) = discovery_path {
-        if path.exists() {
-            // Parse the "pid" value out of local.json without a JSON dependency.
-            let recorded_pid: Option<u32> = std::fs::read_to_string(&path).ok().and_then(|s| {
-                // Scan for: "pid": 12345
-                s.split('"')
-                    .skip_while(|tok| *tok != "pid")
-                    .nth(2)
-                    .and_then(|tok| {
-                        let digits: String = tok.chars().skip_while(|c| !c.is_ascii_digit()).take_while(|c| c.is_ascii_digit()).collect();
-                        digits.parse().ok()
-                    })
-            });
-
-            let pid_is_alive = recorded_pid.map(|pid| {
-                #[cfg(unix)]
-                { let rc = unsafe { libc::kill(pid as libc::pid_t, 0) }; rc == 0 }
-                #[cfg(not(unix))]
-                { let _ = pid; false } // Windows: after taskkill above, assume dead
-            }).unwrap_or(false);
-
-            if !pid_is_alive {
-                let _ = std::fs::remove_file(&path);
-            }
-        }
+    if path.exists() && !read_engine_pid_from_discovery().is_some_and(process_is_alive) {
+        let _ = std::fs::remove_file(&path);
     }
 }
 
@@ -176,29 +251,554 @@ fn kill_orphaned_llama_server() {
     }
 }
 
-/// Holds the sidecar child process handle for lifecycle management.
-struct SidecarState {
+/// Preferred engine port. Used on first spawn and whenever the previously
+/// chosen port is still free; otherwise we fall back to an ephemeral port.
+const DEFAULT_ENGINE_PORT: u16 = 22140;
+
+/// Crash-restart supervisor tuning (see schedule_sidecar_restart). The
+/// circuit breaker's budget is in settings (`restart-circuit-*`).
+const RESTART_BACKOFF_INITIAL_MS: u64 = 500;
+const RESTART_BACKOFF_MAX_MS: u64 = 30_000;
+
+/// How long shutdown_sidecar waits for a graceful exit before force-killing.
+/// Interactive stop/restart use the short budget; quit gets the full Python
+/// lifespan teardown budget (~25s total: wake-word 3s + scheduler 3s + proxy
+/// 4s + tunnel 5s + scraper 5s + browsers 3s + margin) so most phases finish.
+const SIDECAR_STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+const SIDECAR_QUIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// How long to wait for the OS to reap the engine after a force-kill.
+const SIDECAR_KILL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// How long kill_orphaned_sidecars gives an orphan to exit on SIGTERM before
+/// it is killed outright — the same grace the old pkill sweep gave.
+const ORPHAN_TERM_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Upper bound a quit path waits for an already-running graceful_shutdown_sync
+/// (started by another handler) to finish before exiting anyway.
+const SHUTDOWN_WAIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How long quit waits for the engine to say whether jobs are running. An
+/// engine that doesn't answer in time is treated as idle.
+const QUIT_STATUS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// reload_sidecar: how often it re-reads `active_jobs` while draining, and
+/// its default `drain_timeout_ms`.
+const DRAIN_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+const DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Readiness polling after spawn (see spawn_readiness_poll). READY_TIMEOUT is
+/// also start_sidecar's default `ready_timeout_ms`.
+const READY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+const READY_PING_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(1000);
+const READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Hang detection once the engine is up (see spawn_health_watchdog). The
+/// interval is the `health-interval-ms` setting.
+const WATCHDOG_PING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+const WATCHDOG_MAX_FAILURES: u32 = 3;
+
+/// Bound on one ping_sidecar round trip.
+const PING_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(1500);
+
+/// Name of the main engine sidecar. Every sidecar command defaults to it, so
+/// frontend calls that don't pass a `name` keep targeting the engine.
+const ENGINE_SIDECAR: &str = "aimatrx-engine";
+
+/// Whether the supervisor respawns a sidecar that exited on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum RestartPolicy {
+    /// After any exit, clean or not.
+    Always,
+    /// Only after a crash — a non-zero exit code or a signal.
+    OnFailure,
+    /// Never, e.g. for a one-shot migration.
+    Never,
+}
+
+/// A Python service bundled as a Tauri sidecar (`externalBin`).
+struct SidecarSpec {
+    /// Key in SidecarState and the `name` argument of the sidecar commands.
+    name: &'static str,
+    /// externalBin name resolved by `shell().sidecar()`.
+    binary: &'static str,
+    default_port: u16,
+    /// Starting restart policy; set_restart_policy can change it per session.
+    restart_policy: RestartPolicy,
+    /// Most supervisor respawns per session, or `None` for no cap beyond the
+    /// circuit breaker (see schedule_sidecar_restart).
+    max_restarts: Option<u32>,
+}
+
+/// Every sidecar the app manages. A new service needs an entry here plus a
+/// matching `externalBin` in tauri.conf.json. Engine-only behaviour (macOS
+/// Helper bundle, discovery file, orphan sweep, tray status, engine.log,
+/// `sidecar-config` env/args) stays keyed to ENGINE_SIDECAR.
+const SIDECARS: &[SidecarSpec] = &[SidecarSpec {
+    name: ENGINE_SIDECAR,
+    binary: "matrx-engine",
+    default_port: DEFAULT_ENGINE_PORT,
+    restart_policy: RestartPolicy::Always,
+    max_restarts: None,
+}];
+
+/// Every `externalBin` in tauri.conf.json, managed here or not (cloudflared
+/// and llama-server have their own modules). list_sidecars reports on these.
+const BUNDLED_BINARIES: &[&str] = &["matrx-engine", "cloudflared", "llama-server"];
+
+/// Lifecycle state for one sidecar process.
+///
+/// `port` is the port handed to the most recent spawn. It starts at the
+/// spec's default port and is kept across stop/start within the session so a
+/// restart lands on the same port whenever it is still free.
+///
+/// `crash_history` holds the timestamps of recent unexpected exits; the
+/// supervisor uses it for backoff and to decide when to give up.
+///
+/// `ready` flips to true once the current process's `/health` answers 200
+/// and back to false whenever that process stops or is replaced.
+struct SidecarProcess {
+    spec: &'static SidecarSpec,
     child: Mutex<Option<tauri_plugin_shell::process::CommandChild>>,
+    port: AtomicU16,
+    crash_history: Mutex<std::collections::VecDeque<std::time::Instant>>,
+    ready: AtomicBool,
+    /// When the current child was spawned (uptime in sidecar_metrics and
+    /// sidecar_status).
+    started_at: Mutex<Option<std::time::Instant>>,
+    /// Supervisor respawns (crash restarts and hung-watchdog restarts) this
+    /// session. Never reset, so a flapping sidecar shows a climbing count.
+    restart_count: AtomicU32,
+    /// Current restart policy and `max_restarts`, seeded from the spec.
+    restart_policy: Mutex<(RestartPolicy, Option<u32>)>,
+    /// sidecar_version result, keyed by the PID it was fetched from so a
+    /// restart invalidates it.
+    version_cache: Mutex<Option<(u32, SidecarVersion)>>,
+    /// get_openapi_schema result, keyed by PID like `version_cache`.
+    openapi_cache: Mutex<Option<(u32, serde_json::Value)>>,
+    /// Shared secret handed to the current process as MATRX_ENGINE_TOKEN.
+    /// Regenerated on every spawn; see get_engine_token.
+    token: Mutex<Option<String>>,
+    /// Host and port the current process says it bound, parsed from its
+    /// startup log (see parse_bound_address). `None` until that line shows up.
+    bound_addr: Mutex<Option<(String, u16)>>,
+    /// Ring buffer of recent stdout/stderr lines for frontend diagnostics.
+    logs: Arc<Mutex<Vec<String>>>,
+    /// Set when a crash notification went out, so the next successful
+    /// readiness poll announces the recovery.
+    recovering: AtomicBool,
+    /// How the last process ended; kept across respawns until the next exit.
+    last_exit: Mutex<Option<ExitInfo>>,
+    /// The current process was paused with pause_sidecar. Cleared on every
+    /// lifecycle change — a new process always starts unpaused.
+    paused: AtomicBool,
+    /// The pause came from the idle timer, not the user, so the proxy
+    /// commands may lift it (see idle.rs). Cleared with `paused`.
+    suspended: AtomicBool,
+    /// The watchdog's last ping failed and it hasn't answered since. Only
+    /// changed through set_sidecar_unresponsive, which emits the edges.
+    unresponsive: AtomicBool,
+    /// Held for the whole of spawn_sidecar, so the liveness check and the
+    /// spawn are one step: a second caller waits, then finds the first
+    /// caller's child and returns its port instead of spawning another.
+    spawning: SpawnGate,
+    /// An engine left running by a previous session that is in use instead
+    /// of a spawned one. Engine only; see adopt.rs.
+    adopted: Mutex<Option<adopt::AdoptedEngine>>,
+    /// Temp data dir the engine runs against until the next stop_sidecar.
+    /// Engine only; see ephemeral.rs.
+    ephemeral_dir: Mutex<Option<std::path::PathBuf>>,
+    /// The supervisor's circuit breaker tripped: too many crashes in its
+    /// window, so no automatic restarts until a start closes it again
+    /// (start_sidecar, reset_circuit_and_start).
+    circuit_open: AtomicBool,
+    /// The args of the last spawn, from build_sidecar_args.
+    launch_args: Mutex<Option<Vec<String>>>,
+    /// Spawn-to-ready time of the last start. Engine only; see
+    /// startup_timing.rs.
+    last_startup_ms: Mutex<Option<u64>>,
+}
+
+impl SidecarProcess {
+    fn new(spec: &'static SidecarSpec) -> Self {
+        Self {
+            spec,
+            child: Mutex::new(None),
+            port: AtomicU16::new(spec.default_port),
+            crash_history: Mutex::new(std::collections::VecDeque::new()),
+            ready: AtomicBool::new(false),
+            started_at: Mutex::new(None),
+            restart_count: AtomicU32::new(0),
+            restart_policy: Mutex::new((spec.restart_policy, spec.max_restarts)),
+            version_cache: Mutex::new(None),
+            openapi_cache: Mutex::new(None),
+            token: Mutex::new(None),
+            bound_addr: Mutex::new(None),
+            logs: Arc::new(Mutex::new(Vec::new())),
+            recovering: AtomicBool::new(false),
+            last_exit: Mutex::new(None),
+            paused: AtomicBool::new(false),
+            suspended: AtomicBool::new(false),
+            unresponsive: AtomicBool::new(false),
+            spawning: SpawnGate::default(),
+            adopted: Mutex::new(None),
+            ephemeral_dir: Mutex::new(None),
+            circuit_open: AtomicBool::new(false),
+            launch_args: Mutex::new(None),
+            last_startup_ms: Mutex::new(None),
+        }
+    }
+
+    fn is_engine(&self) -> bool {
+        self.spec.name == ENGINE_SIDECAR
+    }
+
+    /// Why the supervisor must not respawn this sidecar after an exit on its
+    /// own (`crashed` if it was a failure), or `None` if it may.
+    fn restart_refusal(&self, crashed: bool) -> Option<&'static str> {
+        let (policy, max_restarts) = *self.restart_policy.lock_recover();
+        match policy {
+            RestartPolicy::Never => return Some("policy-never"),
+            RestartPolicy::OnFailure if !crashed => return Some("exited-cleanly"),
+            _ => {}
+        }
+        let restarts = self.restart_count.load(Ordering::SeqCst);
+        max_restarts
+            .is_some_and(|max| restarts >= max)
+            .then_some("max-restarts")
+    }
+
+    /// URL clients should use to reach the process: the address it reported
+    /// binding, or `127.0.0.1:{port}` until it has. A wildcard bind (0.0.0.0
+    /// for LAN access) is reached over loopback, since a wildcard isn't a
+    /// valid destination on every OS.
+    fn base_url(&self) -> String {
+        let bound = self.bound_addr.lock_recover().clone();
+        let (host, port) = bound.unwrap_or_else(|| {
+            ("127.0.0.1".to_string(), self.port.load(Ordering::SeqCst))
+        });
+        let host = match host.as_str() {
+            "0.0.0.0" => "127.0.0.1",
+            "::" | "[::]" => "[::1]",
+            host => host,
+        };
+        format!("http://{}:{}", host, port)
+    }
+}
+
+/// Pull `(host, port)` out of a server's startup line, e.g. uvicorn's
+/// "Uvicorn running on http://0.0.0.0:22140 (Press CTRL+C to quit)".
+fn parse_bound_address(line: &str) -> Option<(String, u16)> {
+    let (_, rest) = line.split_once("running on ")?;
+    let addr = rest
+        .trim_start_matches("http://")
+        .trim_start_matches("https://")
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .next()?;
+    let (host, port) = addr.rsplit_once(':')?;
+    let port = port.parse().ok()?;
+    (!host.is_empty()).then(|| (host.to_string(), port))
+}
+
+/// All managed sidecars, keyed by SidecarSpec::name. The set is fixed at
+/// startup (see SIDECARS), so the map itself needs no lock.
+struct SidecarState(std::collections::HashMap<String, SidecarProcess>);
+
+impl SidecarState {
+    fn new() -> Self {
+        Self(
+            SIDECARS
+                .iter()
+                .map(|spec| (spec.name.to_string(), SidecarProcess::new(spec)))
+                .collect(),
+        )
+    }
+
+    fn get(&self, name: &str) -> Result<&SidecarProcess, String> {
+        self.0
+            .get(name)
+            .ok_or_else(|| format!("Unknown sidecar '{}'", name))
+    }
+
+    /// The main engine — always registered.
+    fn engine(&self) -> &SidecarProcess {
+        &self.0[ENGINE_SIDECAR]
+    }
+}
+
+/// Look up a managed sidecar by name.
+fn sidecar_process<'a>(
+    app: &'a tauri::AppHandle,
+    name: &str,
+) -> Result<&'a SidecarProcess, String> {
+    app.state::<SidecarState>().inner().get(name)
 }
 
-/// Ring buffer of recent sidecar stdout/stderr lines for frontend diagnostics.
-#[derive(Clone)]
-struct SidecarLogs {
-    lines: Arc<Mutex<Vec<String>>>,
+/// sysinfo needs the same System across refreshes to compute a process's
+/// CPU usage, so sidecar_metrics keeps one alive between calls.
+struct SidecarMetricsSampler(Mutex<sysinfo::System>);
+
+/// Handle to the tray menu's status line so sidecar lifecycle code can keep
+/// it in sync via set_tray_status(). Managed in setup_tray().
+struct TrayStatusItem(tauri::menu::MenuItem<tauri::Wry>);
+
+/// The tray icon itself, so lifecycle code can swap its image via
+/// set_tray_health(). Managed in setup_tray().
+struct TrayHandle(tauri::tray::TrayIcon<tauri::Wry>);
+
+/// Engine health as shown by the tray icon.
+#[derive(Clone, Copy)]
+enum TrayHealth {
+    /// Grey: stopped or still starting.
+    Stopped,
+    /// Green: serving and answering /health.
+    Running,
+    /// Red: crashed, hung, or failed to start.
+    Error,
 }
 
-/// Controls whether window close hides to tray or quits the app.
-struct CloseToTray(AtomicBool);
+/// Every "Restart Engine" menu item (tray + application menu) so they can all
+/// be disabled while a restart is in flight. Each menu builder registers its
+/// own item.
+struct RestartEngineItems(Mutex<Vec<tauri::menu::MenuItem<tauri::Wry>>>);
+
+/// Every "Open API Docs" menu item, enabled only while the engine is running
+/// and ready (see emit_sidecar_state).
+struct ApiDocsItems(Mutex<Vec<tauri::menu::MenuItem<tauri::Wry>>>);
+
+/// The tray's "Pause Engine" toggle: checked while paused, enabled only
+/// while the engine is running and ready. Managed in setup_tray().
+struct PauseEngineItem(tauri::menu::CheckMenuItem<tauri::Wry>);
 
 /// Holds a pending OAuth deep-link URL that arrived before the frontend
 /// mounted its listener. The frontend polls this via get_pending_oauth_url
 /// and clears it after consuming.
 struct PendingOAuthUrl(Mutex<Option<String>>);
 
+/// Payload of the `deep-link` event for an aimatrx:// URL. `path` folds the
+/// URL's host into the route, so aimatrx://notes/123?x=1 becomes "/notes/123".
+#[derive(Clone, Serialize)]
+struct DeepLinkPayload {
+    url: String,
+    path: String,
+    query: std::collections::HashMap<String, String>,
+}
+
+/// Most recent deep link, kept until the frontend claims it via
+/// get_pending_deep_link — covers links that launch the app, which arrive
+/// before any listener is mounted. Same pattern as PendingOAuthUrl.
+struct PendingDeepLink(Mutex<Option<DeepLinkPayload>>);
+
+/// Payload of the `second-instance` event: the argv (minus the binary path)
+/// and working directory of a launch that single-instance redirected to us.
+#[derive(Clone, Serialize)]
+struct SecondInstancePayload {
+    args: Vec<String>,
+    cwd: String,
+}
+
+/// Engine lifecycle states reported by the `sidecar-state-changed` event.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum SidecarLifecycle {
+    Starting,
+    Running,
+    Stopping,
+    Stopped,
+    Crashed,
+    /// Crashed too often; the supervisor's circuit breaker is open.
+    Failed,
+}
+
+/// Phases of the `sidecar-startup-progress` event, in the order a start goes
+/// through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum StartupPhase {
+    /// About to launch the process.
+    Spawning,
+    /// The process exists; nothing is listening on its port yet.
+    ProcessUp,
+    /// The port accepts TCP connections but the health endpoint isn't
+    /// answering 200 yet (the app is still importing, say).
+    PortOpen,
+    /// Serving. The last event of a successful start.
+    HealthPassing,
+}
+
+fn emit_startup_progress(
+    app: &tauri::AppHandle,
+    name: &str,
+    pid: Option<u32>,
+    phase: StartupPhase,
+    attempt: u32,
+    elapsed: std::time::Duration,
+) {
+    let _ = app.emit(
+        "sidecar-startup-progress",
+        serde_json::json!({
+            "name": name,
+            "pid": pid,
+            "phase": phase,
+            "attempt": attempt,
+            "elapsed_ms": elapsed.as_millis() as u64,
+        }),
+    );
+}
+
+/// Whether anything accepts a TCP connection on the sidecar's port. Tells
+/// "listening but not serving" apart from "not listening" while starting.
+async fn sidecar_port_open(port: u16) -> bool {
+    let connect = tokio::net::TcpStream::connect(("127.0.0.1", port));
+    matches!(
+        tokio::time::timeout(READY_PING_TIMEOUT, connect).await,
+        Ok(Ok(_))
+    )
+}
+
+/// How a sidecar process ended (see ExitInfo).
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ExitKind {
+    /// Exit code 0.
+    Clean,
+    /// A non-zero exit code.
+    Error,
+    /// Killed by a signal (Unix).
+    Signal,
+    /// The OS reported neither a code nor a signal.
+    Unknown,
+}
+
+/// The most recent exit of a sidecar process, as reported by `Terminated`.
+#[derive(Debug, Clone, Serialize)]
+struct ExitInfo {
+    pid: u32,
+    kind: ExitKind,
+    code: Option<i32>,
+    signal: Option<i32>,
+    /// Milliseconds since the Unix epoch, when the exit was seen.
+    ts: u64,
+}
+
+impl ExitInfo {
+    fn new(pid: u32, code: Option<i32>, signal: Option<i32>) -> Self {
+        let kind = match (code, signal) {
+            (_, Some(_)) => ExitKind::Signal,
+            (Some(0), None) => ExitKind::Clean,
+            (Some(_), None) => ExitKind::Error,
+            (None, None) => ExitKind::Unknown,
+        };
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self {
+            pid,
+            kind,
+            code,
+            signal,
+            ts,
+        }
+    }
+}
+
+/// Payload of `sidecar-state-changed`. `pid` is the sidecar process the
+/// transition refers to — the new one for starting/running, the old one for
+/// stopping/stopped/crashed — or `None` when no process was involved.
+///
+/// `exit` is how that process ended, on stopped/crashed transitions whose
+/// exit has already been seen. A crash always carries it; an intentional
+/// stop usually reports Stopped before the exit arrives, so there it may be
+/// `None` — sidecar_status's `last_exit` catches up.
+#[derive(Clone, Serialize)]
+struct SidecarStateEvent {
+    name: &'static str,
+    state: SidecarLifecycle,
+    port: u16,
+    pid: Option<u32>,
+    exit: Option<ExitInfo>,
+}
+
+#[derive(Clone, Serialize)]
+struct SidecarVersion {
+    engine_version: String,
+    python_version: Option<String>,
+    /// None for dev-mode engines, which aren't a packaged build.
+    build_date: Option<String>,
+}
+
+/// Result of ping_sidecar. On failure `latency_ms` is how long the attempt
+/// took — up to PING_TIMEOUT.
+#[derive(Clone, Serialize)]
+struct SidecarPing {
+    latency_ms: u64,
+    ok: bool,
+}
+
+/// Body of the engine's `GET /version`. The extra fields are missing on
+/// engines older than the build-info change, hence the Options.
+#[derive(serde::Deserialize)]
+struct EngineVersionResponse {
+    version: String,
+    python_version: Option<String>,
+    build_date: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SidecarMetrics {
+    pid: u32,
+    /// Summed across cores: 200.0 means two cores fully busy.
+    cpu_percent: f32,
+    memory_bytes: u64,
+    uptime_secs: u64,
+}
+
 #[derive(Serialize)]
 struct SidecarStatus {
+    name: &'static str,
     running: bool,
+    pid: Option<u32>,
+    /// Port of the current (or last) spawn — the effective port after any
+    /// fallback from the preferred one.
     port: u16,
+    /// True once the engine's `/health` endpoint has answered 200.
+    ready: bool,
+    /// Seconds since the current process was spawned; 0 when not running.
+    uptime_secs: u64,
+    /// Times the supervisor has respawned it since the app launched.
+    restart_count: u32,
+    /// Paused with pause_sidecar — see there.
+    paused: bool,
+    /// Paused by the idle timer rather than the user — see idle.rs.
+    suspended: bool,
+    /// Running against a throwaway data dir — see start_sidecar_ephemeral.
+    ephemeral: bool,
+    /// The supervisor gave up after too many crashes; see
+    /// reset_circuit_and_start.
+    circuit_open: bool,
+    /// Alive but failing the watchdog's pings (see `engine-unresponsive`).
+    unresponsive: bool,
+    /// How long the last start took from spawn to ready — see
+    /// startup_timing.rs.
+    last_startup_duration_ms: Option<u64>,
+    /// The address the process listens on, e.g. "0.0.0.0" with LAN access
+    /// (see lan_access.rs). `None` for an external or adopted engine.
+    bind_host: Option<String>,
+    /// How the last process ended, if one has since the app launched.
+    last_exit: Option<ExitInfo>,
+    /// Current policy and cap — see set_restart_policy.
+    restart_policy: RestartPolicy,
+    max_restarts: Option<u32>,
+    /// Where to send requests — see SidecarProcess::base_url. The frontend
+    /// builds engine URLs from this rather than assuming localhost.
+    base_url: String,
+    /// "managed" for a process we spawned, "external" for a dev engine the
+    /// developer runs by hand (see connect_external_engine), "adopted" for
+    /// one a previous session left running (see adopt.rs). For the latter
+    /// two `running`/`ready` reflect the last health check.
+    mode: &'static str,
 }
 
 /// Resolve the path to the Helper-app engine binary on macOS production builds.
@@ -232,10 +832,148 @@ fn macos_helper_engine_path() -> Option<std::path::PathBuf> {
     }
 }
 
+/// Fail with `BinaryMissing` if `binary` isn't where sidecar() will look for
+/// it: next to our own executable, where externalBin entries are installed.
+/// Turns a broken install into an actionable error instead of whatever
+/// spawn() would have reported.
+fn check_sidecar_binary(binary: &str) -> Result<(), SidecarError> {
+    let Some(path) = bundled_binary_path(binary) else {
+        // Can't tell — let spawn() report whatever is wrong.
+        return Ok(());
+    };
+    if path.exists() {
+        return Ok(());
+    }
+    log::warn!("[sidecar] Sidecar binary not found at {}", path.display());
+    Err(SidecarError::BinaryMissing {
+        expected_path: path.display().to_string(),
+    })
+}
+
+/// Where the externalBin `binary` is installed: next to our own executable,
+/// with the platform's executable suffix. `None` if current_exe() fails.
+fn bundled_binary_path(binary: &str) -> Option<std::path::PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    Some(dir.join(format!("{}{}", binary, std::env::consts::EXE_SUFFIX)))
+}
+
+/// Show a sidecar's binary (the engine unless `name` says otherwise) selected
+/// in the OS file manager — for checking its code signature, or whether
+/// antivirus quarantined it. Resolves it the way spawn_sidecar does: the
+/// Helper app's binary for the engine on macOS production builds, else the
+/// externalBin next to our executable. Returns the path; fails with
+/// `BinaryMissing` if the binary isn't there.
+#[tauri::command]
+fn reveal_sidecar_binary(
+    app: tauri::AppHandle,
+    name: Option<String>,
+) -> Result<String, SidecarError> {
+    let state = sidecar_process(&app, name.as_deref().unwrap_or(ENGINE_SIDECAR))?;
+    #[cfg(target_os = "macos")]
+    let helper = macos_helper_engine_path().filter(|_| state.is_engine());
+    #[cfg(not(target_os = "macos"))]
+    let helper: Option<std::path::PathBuf> = None;
+    let path = helper
+        .or_else(|| bundled_binary_path(state.spec.binary))
+        .ok_or_else(|| "Could not resolve the app's install directory".to_string())?;
+    if !path.exists() {
+        return Err(SidecarError::BinaryMissing {
+            expected_path: path.display().to_string(),
+        });
+    }
+    reveal_in_file_manager(&path)?;
+    Ok(path.display().to_string())
+}
+
+/// 32 random bytes from the OS, hex-encoded — a fresh MATRX_ENGINE_TOKEN.
+fn generate_engine_token() -> Result<String, SidecarError> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(|e| {
+        SidecarError::SpawnFailed(format!("Failed to generate engine token: {}", e))
+    })?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Pick the port the next sidecar spawn should bind to.
+///
+/// Tries `preferred` first (22140 on first launch, the last chosen port on
+/// later restarts). If something else holds it, asks the OS for an ephemeral
+/// port by binding 127.0.0.1:0, reads the assigned number, and releases the
+/// listener so the engine can bind it. There is a tiny window between the
+/// release and the engine's bind where another process could grab the port;
+/// in practice the ephemeral range makes that collision vanishingly rare.
+fn pick_sidecar_port(preferred: u16) -> Result<u16, String> {
+    if std::net::TcpListener::bind(("127.0.0.1", preferred)).is_ok() {
+        return Ok(preferred);
+    }
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+        .map_err(|e| format!("Failed to reserve an ephemeral port: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read ephemeral port: {}", e))?
+        .port();
+    drop(listener);
+
+    log::info!(
+        "[sidecar] Preferred port {} is taken — falling back to ephemeral port {}",
+        preferred, port
+    );
+    Ok(port)
+}
+
+// Engine flags for the OS this build targets, added after the base `--port`
+// args by build_sidecar_args. The one place for platform quirks in how the
+// engine is launched — keep each entry commented with why that OS needs it.
+#[cfg(target_os = "windows")]
+const PLATFORM_SIDECAR_ARGS: &[&str] = &[];
+#[cfg(target_os = "macos")]
+const PLATFORM_SIDECAR_ARGS: &[&str] = &[];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PLATFORM_SIDECAR_ARGS: &[&str] = &[];
+
+/// The complete argument list for spawning `state` on `port`, in order:
+/// `--port`, the platform overrides (engine only), then the engine's
+/// feature flags, its config file and `extra` — the `sidecar-config`
+/// setting's args — so a per-install arg can override any of them. The
+/// result is remembered for get_effective_sidecar_args.
+fn build_sidecar_args(
+    app: &tauri::AppHandle,
+    state: &SidecarProcess,
+    port: u16,
+    extra: Vec<String>,
+) -> Result<Vec<String>, SidecarError> {
+    let mut args = vec!["--port".to_string(), port.to_string()];
+    if state.is_engine() {
+        args.extend(PLATFORM_SIDECAR_ARGS.iter().map(|a| a.to_string()));
+        args.extend(features::spawn_args(app));
+        args.extend(engine_config::spawn_args(app)?);
+    }
+    args.extend(extra);
+    *state.launch_args.lock_recover() = Some(args.clone());
+    Ok(args)
+}
+
+/// The args a sidecar (the engine unless `name` says otherwise) was last
+/// spawned with, as built by build_sidecar_args — for debugging what the
+/// engine was actually told. `None` until it has been spawned this session.
+#[tauri::command]
+async fn get_effective_sidecar_args(
+    state: tauri::State<'_, SidecarState>,
+    name: Option<String>,
+) -> Result<Option<Vec<String>>, SidecarError> {
+    let process = state.get(name.as_deref().unwrap_or(ENGINE_SIDECAR))?;
+    let args = process.launch_args.lock_recover().clone();
+    Ok(args)
+}
+
 /// Start the Python/FastAPI engine sidecar.
 ///
 /// In production, this spawns the bundled PyInstaller binary.
-/// The sidecar listens on the configured port (default 22140).
+/// The port is chosen by pick_sidecar_port() right before the spawn and
+/// handed to the engine via both `--port` and `MATRX_PORT` (run.py reads the
+/// env var; the arg keeps the command line self-describing in `ps` output).
 /// We set TAURI_SIDECAR=1 so that run.py skips the pystray tray icon —
 /// Tauri already owns the single system-tray icon for the whole app.
 ///
@@ -251,206 +989,1821 @@ fn macos_helper_engine_path() -> Option<std::path::PathBuf> {
 ///   Windows / Linux:  Tauri's externalBin places the renamed `matrx-engine`
 ///     binary at the expected location; sidecar() resolves it correctly.
 ///
-/// Before spawning, kills any orphaned engine processes left over from a
-/// previous crash or unclean shutdown.  This prevents the new sidecar from
-/// failing with "address already in use" on port 22140.
+/// Before spawning, kills an orphaned engine left on our port by a previous
+/// crash or unclean shutdown (see kill_orphaned_sidecars).  This prevents
+/// the new sidecar from failing with "address already in use".
 ///
 /// Self-healing: if we hold a child handle but the process has already
 /// exited (e.g. killed by macOS watchdog or SIGKILL), we clear the stale
 /// handle so we can respawn cleanly rather than returning early.
+///
+/// Other sidecars (see SIDECARS) go through the same path minus the
+/// engine-only steps: they always resolve via sidecar(), get no
+/// `sidecar-config` env/args, and skip the orphan sweep.
+///
+/// Concurrent calls for the same sidecar are serialized (see
+/// SidecarProcess::spawning), so racing callers — a double-clicked Start,
+/// the setup warm start, the supervisor — never spawn two processes.
+///
+/// Shared by the `start_sidecar` command and the crash supervisor. Returns the
+/// port the sidecar was told to bind (or is already bound to). `ready_timeout`
+/// bounds the background readiness poll (READY_TIMEOUT unless the caller
+/// asked for something else).
+async fn spawn_sidecar(
+    app: &tauri::AppHandle,
+    name: &str,
+    ready_timeout: std::time::Duration,
+) -> Result<u16, SidecarError> {
+    let state = sidecar_process(app, name)?;
+    let turn = state.spawning.enter().await;
+    spawn_sidecar_in_turn(app, state.spec.name, ready_timeout, &turn).await
+}
+
+/// spawn_sidecar for a caller that already holds the sidecar's spawn gate.
+/// The spawn itself sleeps and shells out, so it runs on the blocking pool.
+async fn spawn_sidecar_in_turn(
+    app: &tauri::AppHandle,
+    name: &'static str,
+    ready_timeout: std::time::Duration,
+    turn: &SpawnTurn<'_>,
+) -> Result<u16, SidecarError> {
+    let app = app.clone();
+    turn.run(move || spawn_sidecar_blocking(&app, name, ready_timeout))
+        .await
+        .unwrap_or_else(|e| Err(SidecarError::SpawnFailed(e)))
+}
+
+fn spawn_sidecar_blocking(
+    app: &tauri::AppHandle,
+    name: &'static str,
+    ready_timeout: std::time::Duration,
+) -> Result<u16, SidecarError> {
+    let state = sidecar_process(app, name)?;
+    // A child whose process exited without going through stop_sidecar() is
+    // a stale handle: clear it and respawn.
+    spawn_gate::reuse_or_spawn(
+        &state.child,
+        |child| {
+            // On Unix we can probe liveness with kill(pid, 0): if it returns
+            // ESRCH the process is gone and the handle is stale.
+            #[cfg(unix)]
+            {
+                let pid = child.pid();
+                let alive = unsafe { libc::kill(pid as libc::pid_t, 0) } == 0;
+                if !alive {
+                    log::warn!(
+                        "[sidecar] Stale child handle (pid={}) detected — process is gone. Clearing.",
+                        pid
+                    );
+                }
+                alive
+            }
+            // On Windows we have no cheap liveness check via the plugin API;
+            // treat a held handle as running. The watchdog / restart path
+            // always calls stop_sidecar() first on Windows, so this is fine.
+            #[cfg(not(unix))]
+            {
+                let _ = child;
+                true
+            }
+        },
+        || Ok(state.port.load(Ordering::SeqCst)),
+        || spawn_new_sidecar(app, name, ready_timeout),
+    )
+}
+
+/// The spawn half of spawn_sidecar_blocking, once no live child is held.
+fn spawn_new_sidecar(
+    app: &tauri::AppHandle,
+    name: &'static str,
+    ready_timeout: std::time::Duration,
+) -> Result<u16, SidecarError> {
+    let state = sidecar_process(app, name)?;
+    let is_engine = state.is_engine();
+
+    // Kill an orphaned engine from a previous session before spawning a new
+    // one.  Without this, our port may still be held by a zombie from a
+    // crash/force-quit, and the new sidecar will fail.
+    let preferred = state.port.load(Ordering::SeqCst);
+    if is_engine {
+        kill_orphaned_sidecars(preferred);
+    }
+
+    // Pick the port AFTER the orphan sweep so a port freed by the sweep is
+    // reused instead of drifting to an ephemeral one.
+    let port = pick_sidecar_port(preferred)?;
+    if port != preferred {
+        // Tell the UI who's squatting on the preferred port. If it's one of
+        // our engines that survived the sweep above (it ignored SIGTERM and
+        // SIGKILL alike), the UI can offer reclaim_engine_port.
+        let engine_pid = if is_engine {
+            orphaned_engine_on_port(preferred)
+        } else {
+            None
+        };
+        log::warn!(
+            "[sidecar] Port {} for {} is in use{}",
+            preferred,
+            name,
+            engine_pid
+                .map(|pid| format!(" by an orphaned engine (pid={})", pid))
+                .unwrap_or_default()
+        );
+        let _ = app.emit(
+            "sidecar-port-in-use",
+            serde_json::json!({
+                "name": name,
+                "port": preferred,
+                "fallback": port,
+                "engine_pid": engine_pid,
+            }),
+        );
+    }
+    state.port.store(port, Ordering::SeqCst);
+    state.ready.store(false, Ordering::SeqCst);
+    log::info!("[sidecar] Spawning {} on port {}", name, port);
+
+    // Build the engine command. On macOS production the engine lives inside
+    // a Helper .app sub-bundle; everywhere else Tauri's externalBin places
+    // it where the sidecar() API expects it.
+    let sidecar_command = {
+        #[cfg(target_os = "macos")]
+        {
+            if let Some(helper_path) = macos_helper_engine_path().filter(|_| is_engine) {
+                app.shell().command(helper_path)
+            } else {
+                // Dev mode (no .app bundle) — fall through to sidecar() so the
+                // user sees a clear "binary not found" if they actually invoke
+                // start_sidecar from `pnpm tauri:dev`.
+                check_sidecar_binary(state.spec.binary)?;
+                app.shell()
+                    .sidecar(state.spec.binary)
+                    .map_err(|e| {
+                        let msg = format!("Failed to create sidecar command: {}", e);
+                        SidecarError::SpawnFailed(msg)
+                    })?
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            check_sidecar_binary(state.spec.binary)?;
+            app.shell()
+                .sidecar(state.spec.binary)
+                .map_err(|e| {
+                    SidecarError::SpawnFailed(format!("Failed to create sidecar command: {}", e))
+                })?
+        }
+    };
+
+    // Per-install env from the settings store. Applied first so the
+    // variables below — which this process depends on — always win.
+    let config = if is_engine {
+        settings::sidecar_config(app)
+    } else {
+        settings::SidecarConfig::default()
+    };
+    let args = build_sidecar_args(app, state, port, config.args)?;
+
+    let port_arg = port.to_string();
+    let token = generate_engine_token()?;
+    let sidecar = sidecar_command
+        .envs(config.env)
+        .envs(ephemeral::spawn_env(state))
+        // Applied by the engine itself at startup — see resource_limits.rs.
+        .envs(is_engine.then(|| resource_limits::spawn_env(app)).unwrap_or_default())
+        // 0.0.0.0 with LAN access on — see lan_access.rs.
+        .envs(is_engine.then(|| ("MATRX_HOST", lan_access::bind_host(app))))
+        // Token-less clients the engine lets in — see app/api/auth.py.
+        .envs(is_engine.then(|| {
+            let clients = settings::external_clients(app).join(",");
+            ("MATRX_EXTERNAL_CLIENTS", clients)
+        }))
+        // Signal to run.py that it is running inside Tauri — suppress pystray tray icon.
+        .env("TAURI_SIDECAR", "1")
+        // Pass the Tauri app's own PID so the Python watchdog can watch the
+        // correct process on Windows.  On Windows, Tauri spawns the sidecar
+        // via an intermediate shim/pipe helper whose PPID is NOT the Tauri
+        // process itself — so os.getppid() in Python points to a short-lived
+        // launcher that exits immediately, causing a false "parent gone" kill.
+        .env("TAURI_APP_PID", std::process::id().to_string())
+        .env("MATRX_PORT", &port_arg)
+        .env("MATRX_ENGINE_TOKEN", &token)
+        .args(&args);
+
+    emit_startup_progress(
+        app,
+        name,
+        None,
+        StartupPhase::Spawning,
+        0,
+        std::time::Duration::ZERO,
+    );
+    let (mut rx, child) = sidecar
+        .spawn()
+        .map_err(|e| SidecarError::SpawnFailed(format!("Failed to spawn sidecar: {}", e)))?;
+
+    let pid = child.pid();
+    log::debug!("[sidecar] Spawned {} (pid={})", name, pid);
+    *state.child.lock_recover() = Some(child);
+    *state.started_at.lock_recover() = Some(std::time::Instant::now());
+    *state.version_cache.lock_recover() = None;
+    *state.openapi_cache.lock_recover() = None;
+    *state.token.lock_recover() = Some(token);
+    *state.bound_addr.lock_recover() = None;
+    set_sidecar_tray_status(app, name, &format!("Status: Starting (port {})...", port));
+    emit_sidecar_state(app, name, SidecarLifecycle::Starting, Some(pid));
+
+    spawn_readiness_poll(app.clone(), name, pid, port, ready_timeout);
+
+    // Forward sidecar output to Tauri logs AND to the frontend via events.
+    // The per-sidecar ring buffer stores the last 200 lines so the frontend
+    // can retrieve them on demand (e.g. when the recovery modal opens).
+    let app_handle = app.clone();
+    let log_lines = state.logs.clone();
+    let tag = if is_engine { "engine" } else { name };
+    tauri::async_runtime::spawn(async move {
+        use tauri_plugin_shell::process::CommandEvent;
+        let mut batch = SidecarLogBatch::new(name);
+        // When the pending batch goes out; None while nothing is pending.
+        let mut flush_at: Option<tokio::time::Instant> = None;
+        loop {
+            let deadline = flush_at.unwrap_or_else(tokio::time::Instant::now);
+            let event = tokio::select! {
+                event = rx.recv() => event,
+                _ = tokio::time::sleep_until(deadline), if flush_at.is_some() => {
+                    batch.flush(&app_handle);
+                    flush_at = None;
+                    continue;
+                }
+            };
+            let Some(event) = event else {
+                break;
+            };
+            match event {
+                CommandEvent::Stdout(line) => {
+                    let text = String::from_utf8_lossy(&line).to_string();
+                    forward_output_line(
+                        &app_handle,
+                        &mut batch,
+                        &log_lines,
+                        pid,
+                        tag,
+                        "stdout",
+                        text,
+                    );
+                }
+                CommandEvent::Stderr(line) => {
+                    let text = String::from_utf8_lossy(&line).to_string();
+                    forward_output_line(
+                        &app_handle,
+                        &mut batch,
+                        &log_lines,
+                        pid,
+                        tag,
+                        "stderr",
+                        text,
+                    );
+                }
+                CommandEvent::Terminated(status) => {
+                    // stdout and stderr are read on their own threads, so
+                    // their last lines — often a crashing engine's traceback
+                    // — can still be queued behind Terminated. Take them
+                    // until the channel closes (bounded) before reporting.
+                    let drain = async {
+                        while let Some(event) = rx.recv().await {
+                            let (stream, line) = match event {
+                                CommandEvent::Stdout(line) => ("stdout", line),
+                                CommandEvent::Stderr(line) => ("stderr", line),
+                                _ => continue,
+                            };
+                            let text = String::from_utf8_lossy(&line).to_string();
+                            forward_output_line(
+                                &app_handle,
+                                &mut batch,
+                                &log_lines,
+                                pid,
+                                tag,
+                                stream,
+                                text,
+                            );
+                        }
+                    };
+                    if tokio::time::timeout(TERMINATED_DRAIN_TIMEOUT, drain).await.is_err() {
+                        log::warn!("[{}] Output still open after exit — not waiting", tag);
+                    }
+                    let msg = format!("[terminated] Process exited: {:?}", status);
+                    log::warn!("[{}] {}", tag, msg);
+                    forward_sidecar_line(&app_handle, &mut batch, &log_lines, "system", msg);
+                    // The last lines must reach the frontend before the exit
+                    // state change they explain.
+                    batch.flush(&app_handle);
+                    handle_sidecar_exit(&app_handle, name, pid, status.code, status.signal);
+                    break;
+                }
+                _ => {}
+            }
+            if flush_at.is_none() && !batch.is_empty() {
+                flush_at = Some(tokio::time::Instant::now() + LOG_BATCH_WINDOW);
+            }
+        }
+        batch.flush(&app_handle);
+    });
+
+    Ok(port)
+}
+
+/// Remember the address `pid` reports binding, if `line` is its startup
+/// line. Only the first such line counts, and only while `pid` is current.
+fn record_bound_address(app: &tauri::AppHandle, name: &str, pid: u32, line: &str) {
+    let Some(addr) = parse_bound_address(line) else {
+        return;
+    };
+    let Ok(state) = sidecar_process(app, name) else {
+        return;
+    };
+    if state.child.lock_recover().as_ref().map(|c| c.pid()) != Some(pid) {
+        return;
+    }
+    let mut bound = state.bound_addr.lock_recover();
+    if bound.is_none() {
+        log::info!("[sidecar] {} bound {}:{}", name, addr.0, addr.1);
+        *bound = Some(addr);
+    }
+}
+
+/// How long output lines are collected before going out as one
+/// `sidecar-log-batch` event.
+const LOG_BATCH_WINDOW: std::time::Duration = std::time::Duration::from_millis(100);
+/// Most lines one batch carries. Lines past this within a window are
+/// counted in `dropped` instead of sent, so a log storm can't swamp the
+/// webview. They still reach the ring buffer and engine.log.
+const LOG_BATCH_MAX: usize = 500;
+/// How long the forwarding task keeps reading output after `Terminated`.
+/// The channel normally closes well within this; a grandchild that inherited
+/// the pipes can hold it open, though.
+const TERMINATED_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// One line of sidecar output, as carried in a `sidecar-log-batch`.
+///
+/// `stream` is "stdout" or "stderr" for sidecar output, or "system" for lines
+/// the supervisor itself injects (e.g. the `[terminated]` notice). `ts` is
+/// milliseconds since the Unix epoch, stamped when Rust received the line.
+#[derive(Clone, Serialize)]
+struct SidecarLogEvent {
+    name: &'static str,
+    stream: &'static str,
+    line: String,
+    ts: u64,
+}
+
+/// Payload of the `sidecar-log-batch` event — one sidecar's output lines
+/// from the last LOG_BATCH_WINDOW, in the order they arrived across stdout
+/// and stderr. `dropped` counts lines past LOG_BATCH_MAX that were left out.
+#[derive(Clone, Serialize)]
+struct SidecarLogBatch {
+    name: &'static str,
+    entries: Vec<SidecarLogEvent>,
+    dropped: u64,
+}
+
+impl SidecarLogBatch {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            entries: Vec::new(),
+            dropped: 0,
+        }
+    }
+
+    fn push(&mut self, stream: &'static str, line: String) {
+        if self.entries.len() >= LOG_BATCH_MAX {
+            self.dropped += 1;
+            return;
+        }
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        self.entries.push(SidecarLogEvent {
+            name: self.name,
+            stream,
+            line,
+            ts,
+        });
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.dropped == 0
+    }
+
+    /// Emit whatever is pending and start a new batch.
+    fn flush(&mut self, app: &tauri::AppHandle) {
+        if self.is_empty() {
+            return;
+        }
+        if self.dropped > 0 {
+            log::warn!(
+                "[sidecar] {} log burst: {} line(s) not forwarded to the UI",
+                self.name, self.dropped
+            );
+        }
+        let batch = std::mem::replace(self, Self::new(self.name));
+        let _ = app.emit("sidecar-log-batch", batch);
+    }
+}
+
+/// Echo one stdout/stderr line of process `pid` to our own output, check it
+/// for the bound address, and forward it (see forward_sidecar_line).
+fn forward_output_line(
+    app: &tauri::AppHandle,
+    batch: &mut SidecarLogBatch,
+    log_lines: &Mutex<Vec<String>>,
+    pid: u32,
+    tag: &str,
+    stream: &'static str,
+    text: String,
+) {
+    // The engine's own output, so it goes to engine.log rather than the
+    // app log; this is only the terminal echo.
+    if stream == "stderr" {
+        eprintln!("[{}] {}", tag, text);
+    } else {
+        println!("[{}] {}", tag, text);
+    }
+    record_bound_address(app, batch.name, pid, &text);
+    forward_sidecar_line(app, batch, log_lines, stream, text);
+}
+
+/// Push one output line into the sidecar's ring buffer (last 200 lines, as
+/// `[stream] line`), append it to engine.log on disk (engine only), and queue
+/// it in `batch` for the frontend.
+fn forward_sidecar_line(
+    app: &tauri::AppHandle,
+    batch: &mut SidecarLogBatch,
+    log_lines: &Mutex<Vec<String>>,
+    stream: &'static str,
+    line: String,
+) {
+    let entry = if stream == "system" {
+        line.clone()
+    } else {
+        format!("[{}] {}", stream, line)
+    };
+    if batch.name == ENGINE_SIDECAR {
+        if let Some(log) = app.try_state::<engine_log::EngineLogState>() {
+            log.append(&entry);
+        }
+    }
+    {
+        let mut lines = log_lines.lock_recover();
+        lines.push(entry);
+        let excess = lines.len().saturating_sub(200);
+        if excess > 0 {
+            lines.drain(..excess);
+        }
+    }
+    batch.push(stream, line);
+}
+
+/// True if the child currently held for sidecar `name` has this PID.
+///
+/// Background tasks tied to one spawn (readiness poll, supervisor) use this
+/// to notice that their process was stopped or replaced and bail out.
+fn sidecar_pid_is_current(app: &tauri::AppHandle, name: &str, pid: u32) -> bool {
+    let Ok(process) = sidecar_process(app, name) else {
+        return false;
+    };
+    let current = process.child.lock_recover().as_ref().map(|c| c.pid());
+    current == Some(pid)
+}
+
+/// Poll the health endpoint (`health-endpoint`, `/health` by default) on a
+/// freshly spawned sidecar until it answers 200.
+///
+/// The process existing is not the same as the engine serving — PyInstaller
+/// cold boot takes 5-30s before uvicorn binds. Polls every 250ms for up to
+/// `timeout`, emitting `sidecar-starting` (with the attempt count) each time
+/// so the UI can show "still starting..."; on success sets
+/// SidecarProcess.ready and emits `sidecar-ready` so the UI can drop its
+/// spinner. Stops early if this process is stopped or replaced.
+///
+/// Each poll also emits `sidecar-startup-progress` with the phase reached
+/// (see StartupPhase): until the port accepts a TCP connection the phase is
+/// `process-up`, then `port-open` until the health check passes.
+fn spawn_readiness_poll(
+    app: tauri::AppHandle,
+    name: &'static str,
+    pid: u32,
+    port: u16,
+    timeout: std::time::Duration,
+) {
+    tauri::async_runtime::spawn(async move {
+        let started = std::time::Instant::now();
+        let mut attempt = 0u32;
+        let mut phase = StartupPhase::ProcessUp;
+        loop {
+            if !sidecar_pid_is_current(&app, name, pid) {
+                return;
+            }
+            attempt += 1;
+            if phase < StartupPhase::PortOpen && sidecar_port_open(port).await {
+                phase = StartupPhase::PortOpen;
+            }
+            emit_startup_progress(&app, name, Some(pid), phase, attempt, started.elapsed());
+            let _ = app.emit(
+                "sidecar-starting",
+                serde_json::json!({
+                    "name": name,
+                    "pid": pid,
+                    "port": port,
+                    "attempt": attempt,
+                    "elapsed_ms": started.elapsed().as_millis() as u64,
+                }),
+            );
+            let healthy = ping_sidecar_health(&app, name, READY_PING_TIMEOUT, false).await;
+            log::debug!(
+                "[sidecar] Readiness check {} for {} (pid={}, phase {:?}): {}",
+                attempt,
+                name,
+                pid,
+                phase,
+                if healthy { "passed" } else { "failed" }
+            );
+            if healthy {
+                emit_startup_progress(
+                    &app,
+                    name,
+                    Some(pid),
+                    StartupPhase::HealthPassing,
+                    attempt,
+                    started.elapsed(),
+                );
+                if let Ok(process) = sidecar_process(&app, name) {
+                    process.ready.store(true, Ordering::SeqCst);
+                    if process.recovering.swap(false, Ordering::SeqCst) {
+                        notify_engine_health(
+                            &app,
+                            "Engine recovered",
+                            "AI Matrx is running again.",
+                        );
+                    }
+                }
+                if name == ENGINE_SIDECAR {
+                    // Next launch tries this port first (see
+                    // settings::startup_engine_port).
+                    if let Err(e) = settings::remember_engine_port(&app, port) {
+                        log::warn!("[sidecar] {}", e);
+                    }
+                    // A partial update can pair this UI with the wrong engine.
+                    version_compat::check_in_background(&app);
+                    startup_timing::record(&app, started.elapsed().as_millis() as u64);
+                }
+                let status = format!("Status: Running (port {})", port);
+                set_sidecar_tray_status(&app, name, &status);
+                emit_sidecar_state(&app, name, SidecarLifecycle::Running, Some(pid));
+                log::info!(
+                    "[sidecar] {} ready on port {} after {}ms",
+                    name,
+                    port,
+                    started.elapsed().as_millis()
+                );
+                let _ = app.emit(
+                    "sidecar-ready",
+                    serde_json::json!({ "name": name, "port": port, "pid": pid }),
+                );
+                spawn_health_watchdog(app, name, pid, port);
+                return;
+            }
+            if started.elapsed() >= timeout {
+                log::warn!(
+                    "[sidecar] {} (pid={}) did not answer {} within {}s",
+                    name,
+                    pid,
+                    settings::health_endpoint(&app),
+                    timeout.as_secs()
+                );
+                // Still alive but not serving — let the watchdog decide
+                // whether it's just slow or genuinely wedged.
+                spawn_health_watchdog(app, name, pid, port);
+                return;
+            }
+            tokio::time::sleep(READY_POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// One health ping of sidecar `name` through EngineHttp, at the
+/// `health-endpoint` setting (`/health` by default). False on any error,
+/// non-200, or no answer within `timeout`. With `retry`, a failed send is
+/// retried (engine_http::send_retrying) before giving up.
+async fn ping_sidecar_health(
+    app: &tauri::AppHandle,
+    name: &str,
+    timeout: std::time::Duration,
+    retry: bool,
+) -> bool {
+    let Ok(process) = sidecar_process(app, name) else {
+        return false;
+    };
+    let endpoint = settings::health_endpoint(app);
+    let segments: Vec<&str> = endpoint.split('/').filter(|s| !s.is_empty()).collect();
+    let http = app.state::<EngineHttp>();
+    let Ok(request) = http.request_to(process, reqwest::Method::GET, &segments) else {
+        return false;
+    };
+    let request = request.timeout(timeout);
+    let response = if retry {
+        engine_http::send_retrying(request).await
+    } else {
+        request.send().await
+    };
+    response.is_ok_and(|resp| resp.status().is_success())
+}
+
+/// Watch a running sidecar for hangs (process alive, event loop deadlocked).
+///
+/// Pings the health endpoint every `health-interval-ms` (re-read each tick,
+/// and a change cuts the current wait short). After WATCHDOG_MAX_FAILURES
+/// consecutive failures (timeout or non-200) it emits `sidecar-hung` and
+/// restarts the sidecar through restart_sidecar_process() — the same
+/// graceful-then-force path as the Restart Engine tray item.
+///
+/// Scoped to one PID: an intentional stop or restart takes the child out of
+/// state, which ends this task on its next tick. A crash is left to the
+/// supervisor (handle_sidecar_exit), which clears the handle the same way.
+fn spawn_health_watchdog(app: tauri::AppHandle, name: &'static str, pid: u32, port: u16) {
+    tauri::async_runtime::spawn(async move {
+        let mut failures = 0u32;
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(settings::health_interval(&app)) => {}
+                // Start over with the new interval.
+                _ = settings::HEALTH_INTERVAL_CHANGED.notified() => continue,
+            }
+            if SHUTDOWN_DONE.load(Ordering::SeqCst) || !sidecar_pid_is_current(&app, name, pid) {
+                return;
+            }
+            // A paused engine is quiet on purpose, and a paused watchdog is
+            // being kept out of the way; don't mistake either for a hang.
+            if watchdog_pause::is_paused()
+                || sidecar_process(&app, name).is_ok_and(|p| p.paused.load(Ordering::SeqCst))
+            {
+                failures = 0;
+                continue;
+            }
+
+            let healthy = ping_sidecar_health(&app, name, WATCHDOG_PING_TIMEOUT, true).await;
+            log::debug!(
+                "[sidecar] Watchdog check for {} (pid={}): {}",
+                name,
+                pid,
+                if healthy { "passed" } else { "failed" }
+            );
+            if healthy {
+                failures = 0;
+                set_sidecar_unresponsive(&app, name, false, Some(pid), "recovered");
+                continue;
+            }
+
+            // Just woke from sleep: sockets and timers are still settling.
+            if power::in_wake_grace() {
+                failures = 0;
+                continue;
+            }
+
+            failures += 1;
+            set_sidecar_unresponsive(&app, name, true, Some(pid), "ping-failed");
+            log::error!(
+                "[sidecar] Watchdog: {} failed for {} pid={} ({}/{})",
+                settings::health_endpoint(&app),
+                name,
+                pid,
+                failures,
+                WATCHDOG_MAX_FAILURES
+            );
+            if failures < WATCHDOG_MAX_FAILURES {
+                continue;
+            }
+
+            // The ping may have raced an intentional stop — re-check.
+            if !sidecar_pid_is_current(&app, name, pid) {
+                return;
+            }
+            log::warn!("[sidecar] {} (pid={}) is hung", name, pid);
+            let mut entry = lifecycle_log::LifecycleEntry::new("hung", name, Some(pid), port);
+            entry.detail = Some(format!("{} failed health checks", failures));
+            lifecycle_log::record(&app, entry);
+            let _ = app.emit(
+                "sidecar-hung",
+                serde_json::json!({
+                    "name": name,
+                    "pid": pid,
+                    "port": port,
+                    "failures": failures,
+                }),
+            );
+            // Left running when the policy rules out a restart: a hung
+            // process may still come back, and stopping it is the user's call.
+            let refusal = sidecar_process(&app, name)
+                .ok()
+                .and_then(|p| p.restart_refusal(true));
+            if let Some(reason) = refusal {
+                give_up_restarting(&app, name, reason);
+                return;
+            }
+            set_sidecar_tray_status(&app, name, "Status: Not responding — restarting");
+            if name == ENGINE_SIDECAR {
+                set_tray_health(&app, TrayHealth::Error);
+            }
+            match restart_sidecar_process(&app, name).await {
+                Ok(_) => {
+                    if let Ok(state) = sidecar_process(&app, name) {
+                        state.restart_count.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+                Err(e) => {
+                    log::error!("[sidecar] Hung {} restart failed: {}", name, e);
+                    set_sidecar_tray_status(&app, name, "Status: Restart failed");
+                }
+            }
+            // The new process gets its own watchdog from its readiness poll.
+            return;
+        }
+    });
+}
+
+/// Start a sidecar — the engine unless `name` says otherwise (see
+/// spawn_sidecar for details) — and wait for it to answer `/health`.
+///
+/// A user-initiated start resets the supervisor's crash budget and closes
+/// its circuit breaker, so a sidecar that previously gave up with
+/// `sidecar-circuit-open` gets a fresh set of retries.
+///
+/// Fails with `AlreadyRunning` if the process was already up. Calls made
+/// while another start is in progress wait for that one process to become
+/// ready instead of spawning their own. If it isn't serving within
+/// `ready_timeout_ms` (default 30s) the half-started process is killed and
+/// `Timeout` is returned, so a wedged boot never leaves the UI waiting
+/// forever.
+///
+/// With an external dev engine configured, nothing is spawned — see
+/// connect_external_engine.
+#[tauri::command]
+async fn start_sidecar(
+    app: tauri::AppHandle,
+    name: Option<String>,
+    ready_timeout_ms: Option<u64>,
+) -> Result<(), SidecarError> {
+    let timeout = ready_timeout_ms
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(READY_TIMEOUT);
+    let state = sidecar_process(&app, name.as_deref().unwrap_or(ENGINE_SIDECAR))?;
+    let name = state.spec.name;
+    if let Some(url) = external_engine_url(&app, state) {
+        return connect_external_engine(&app, &url).await.map(|_| ());
+    }
+    state.crash_history.lock_recover().clear();
+    state.circuit_open.store(false, Ordering::SeqCst);
+    let running = state.child.lock_recover().as_ref().map(|c| c.pid());
+    // An ephemeral start needs its own process, with its own data dir.
+    let ephemeral = state.ephemeral_dir.lock_recover().is_some();
+    if running.is_none() && state.is_engine() && !ephemeral && adopt::try_adopt(&app).await {
+        return Ok(());
+    }
+    let port = spawn_sidecar(&app, name, timeout).await.inspect_err(|e| {
+        if matches!(e, SidecarError::BinaryMissing { .. }) {
+            notify_binary_missing(&app);
+        }
+    })?;
+    let Some(pid) = state.child.lock_recover().as_ref().map(|c| c.pid()) else {
+        return Err(SidecarError::SpawnFailed(format!("{} exited during startup", name)));
+    };
+    // spawn_sidecar returns early, keeping the same child, when it's alive.
+    if running == Some(pid) {
+        return Err(SidecarError::AlreadyRunning);
+    }
+
+    match wait_until_ready(&app, name, pid, timeout).await {
+        ReadyWait::Ready => return Ok(()),
+        ReadyWait::Exited => {
+            return Err(SidecarError::SpawnFailed(format!("{} exited during startup", name)))
+        }
+        ReadyWait::TimedOut => {}
+    }
+
+    // Take the child only if it's still the one we started; a watchdog or
+    // manual restart may have replaced it in the meantime.
+    let child = {
+        let mut guard = state.child.lock_recover();
+        if guard.as_ref().map(|c| c.pid()) == Some(pid) {
+            state.ready.store(false, Ordering::SeqCst);
+            guard.take()
+        } else {
+            None
+        }
+    };
+    if let Some(c) = child {
+        log::warn!(
+            "[sidecar] {} (pid={}) not ready after {}ms — killing it",
+            name,
+            pid,
+            timeout.as_millis()
+        );
+        let _ = tauri::async_runtime::spawn_blocking(move || {
+            shutdown_sidecar(c, Some(port), false, SIDECAR_KILL_TIMEOUT)
+        })
+        .await;
+        set_sidecar_tray_status(&app, name, "Status: Failed to start");
+        emit_sidecar_state(&app, name, SidecarLifecycle::Stopped, Some(pid));
+        if name == ENGINE_SIDECAR {
+            set_tray_health(&app, TrayHealth::Error);
+        }
+    }
+    Err(SidecarError::Timeout)
+}
+
+/// The Retry for `sidecar-circuit-open`: close the supervisor's circuit
+/// breaker for a sidecar (the engine unless `name` says otherwise), forget
+/// its recent crashes and start it as start_sidecar does.
+#[tauri::command]
+async fn reset_circuit_and_start(
+    app: tauri::AppHandle,
+    name: Option<String>,
+    ready_timeout_ms: Option<u64>,
+) -> Result<(), SidecarError> {
+    let state = sidecar_process(&app, name.as_deref().unwrap_or(ENGINE_SIDECAR))?;
+    if state.circuit_open.swap(false, Ordering::SeqCst) {
+        log::info!("[sidecar] {} circuit closed — retrying", state.spec.name);
+    }
+    start_sidecar(app, name, ready_timeout_ms).await
+}
+
+/// The dev engine URL (settings::dev_engine_url) when `state` is the engine
+/// and that mode is on, else the URL of an engine adopted from a previous
+/// session — both are engines we reach without holding a child.
+fn external_engine_url(app: &tauri::AppHandle, state: &SidecarProcess) -> Option<String> {
+    if !state.is_engine() {
+        return None;
+    }
+    settings::dev_engine_url(app).or_else(|| {
+        state
+            .adopted
+            .lock_recover()
+            .map(|adopted| format!("http://127.0.0.1:{}", adopted.port))
+    })
+}
+
+/// Dev mode: instead of spawning the bundled sidecar, check that the engine
+/// the developer runs by hand answers `/health` and adopt its port. Only the
+/// port is kept — every other call still goes to http://127.0.0.1 — so a URL
+/// with another host or scheme, or a path, is refused rather than silently
+/// misrouted. Fails with `HealthCheckFailed` if the engine isn't up.
+async fn connect_external_engine(app: &tauri::AppHandle, url: &str) -> Result<u16, SidecarError> {
+    let base = url
+        .parse::<tauri::Url>()
+        .map_err(|e| format!("Invalid external engine URL '{}': {}", url, e))?;
+    let local = matches!(base.host_str(), Some("127.0.0.1" | "localhost"));
+    if base.scheme() != "http" || !local || base.path().trim_end_matches('/') != "" {
+        return Err(format!(
+            "External engine URL '{}' must be http://127.0.0.1:PORT or http://localhost:PORT \
+             — the engine has to run on this machine",
+            url
+        )
+        .into());
+    }
+    let port = base
+        .port_or_known_default()
+        .ok_or_else(|| format!("External engine URL '{}' has no port", url))?;
+    let health = format!("{}/health", url.trim_end_matches('/'));
+    let healthy = app
+        .state::<EngineHttp>()
+        .client()
+        .get(&health)
+        .timeout(std::time::Duration::from_secs(3))
+        .send()
+        .await
+        .is_ok_and(|r| r.status().is_success());
+
+    let state = app.state::<SidecarState>();
+    let state = state.engine();
+    state.port.store(port, Ordering::SeqCst);
+    state.ready.store(healthy, Ordering::SeqCst);
+    if !healthy {
+        log::warn!("[sidecar] External engine at {} is not answering /health", url);
+        // A dead adopted engine is forgotten so the next start spawns one.
+        state.adopted.lock_recover().take();
+        set_sidecar_tray_status(app, ENGINE_SIDECAR, "Status: External engine not running");
+        emit_sidecar_state(app, ENGINE_SIDECAR, SidecarLifecycle::Stopped, None);
+        return Err(SidecarError::HealthCheckFailed);
+    }
+    log::info!("[sidecar] Using external engine at {} (port {})", url, port);
+    set_sidecar_tray_status(app, ENGINE_SIDECAR, &format!("Status: External (port {})", port));
+    emit_sidecar_state(app, ENGINE_SIDECAR, SidecarLifecycle::Running, None);
+    Ok(port)
+}
+
+/// How a wait on a sidecar's readiness ended.
+enum ReadyWait {
+    Ready,
+    /// The process exited or was replaced before it became ready.
+    Exited,
+    TimedOut,
+}
+
+/// Wait for the readiness poll of process `pid` to flip
+/// SidecarProcess.ready. Watches the flag rather than polling `/health`
+/// again — spawn_readiness_poll is already doing that.
+async fn wait_until_ready(
+    app: &tauri::AppHandle,
+    name: &str,
+    pid: u32,
+    timeout: std::time::Duration,
+) -> ReadyWait {
+    let Ok(state) = sidecar_process(app, name) else {
+        return ReadyWait::Exited;
+    };
+    let started = std::time::Instant::now();
+    loop {
+        if !sidecar_pid_is_current(app, name, pid) {
+            return ReadyWait::Exited;
+        }
+        if state.ready.load(Ordering::SeqCst) {
+            return ReadyWait::Ready;
+        }
+        if started.elapsed() >= timeout {
+            return ReadyWait::TimedOut;
+        }
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
+/// Resolve once a sidecar (the engine unless `name` says otherwise) is
+/// serving, so the UI can gate its first requests on cold start. Starts it
+/// first if nothing is running. Unlike start_sidecar, a timeout leaves the
+/// process alone — it may just be slow — and fails with `Timeout`.
+///
+/// Returns the port it is serving on.
+#[tauri::command]
+async fn wait_for_ready(
+    app: tauri::AppHandle,
+    name: Option<String>,
+    timeout_ms: Option<u64>,
+) -> Result<u16, SidecarError> {
+    let timeout = timeout_ms
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(READY_TIMEOUT);
+    let state = sidecar_process(&app, name.as_deref().unwrap_or(ENGINE_SIDECAR))?;
+    let name = state.spec.name;
+    if let Some(url) = external_engine_url(&app, state) {
+        return connect_external_engine(&app, &url).await;
+    }
+
+    let running = state.child.lock_recover().as_ref().map(|c| c.pid());
+    let pid = match running {
+        Some(pid) => pid,
+        None => {
+            spawn_sidecar(&app, name, timeout).await?;
+            let spawned = state.child.lock_recover().as_ref().map(|c| c.pid());
+            spawned.ok_or_else(|| {
+                SidecarError::SpawnFailed(format!("{} exited during startup", name))
+            })?
+        }
+    };
+
+    match wait_until_ready(&app, name, pid, timeout).await {
+        ReadyWait::Ready => Ok(state.port.load(Ordering::SeqCst)),
+        ReadyWait::Exited => Err(SidecarError::SpawnFailed(format!(
+            "{} exited before it became ready",
+            name
+        ))),
+        ReadyWait::TimedOut => Err(SidecarError::Timeout),
+    }
+}
+
+/// Supervisor hook, called from the output-forwarding task once a sidecar
+/// process has terminated.
+///
+/// Intentional stops (stop_sidecar, graceful_shutdown_sync) always `take()`
+/// the child out of its SidecarProcess before killing it. So if the handle in state
+/// still carries this PID, nobody asked the process to die — it crashed or
+/// exited on its own. The sidecar's RestartPolicy then decides: a respawn is
+/// handed to schedule_sidecar_restart(), a refusal ends in `sidecar-gave-up`.
+fn handle_sidecar_exit(
+    app: &tauri::AppHandle,
+    name: &'static str,
+    pid: u32,
+    code: Option<i32>,
+    signal: Option<i32>,
+) {
+    if SHUTDOWN_DONE.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let Ok(state) = sidecar_process(app, name) else {
+        return;
+    };
+    let unexpected = {
+        let mut guard = state.child.lock_recover();
+        let current = guard.as_ref().map(|c| c.pid()) == Some(pid);
+        if current {
+            *guard = None;
+            state.ready.store(false, Ordering::SeqCst);
+        }
+        // An intentional stop's exit counts too, unless a newer process has
+        // already replaced this one (a restart).
+        if current || guard.is_none() {
+            *state.last_exit.lock_recover() = Some(ExitInfo::new(pid, code, signal));
+        }
+        current
+    };
+    if !unexpected {
+        return;
+    }
+
+    let crashed = code != Some(0) || signal.is_some();
+    if crashed {
+        log::warn!(
+            "[sidecar] {} (pid={}) terminated unexpectedly (code={:?}, signal={:?})",
+            name, pid, code, signal
+        );
+        emit_sidecar_state(app, name, SidecarLifecycle::Crashed, Some(pid));
+    } else {
+        log::info!("[sidecar] {} (pid={}) exited cleanly on its own", name, pid);
+        emit_sidecar_state(app, name, SidecarLifecycle::Stopped, Some(pid));
+    }
+
+    if watchdog_pause::is_paused() {
+        log::info!("[sidecar] Watchdog paused — not restarting {}", name);
+        let port = state.port.load(Ordering::SeqCst);
+        let mut entry =
+            lifecycle_log::LifecycleEntry::new("restart-suppressed", name, Some(pid), port);
+        entry.detail = Some("watchdog paused".to_string());
+        lifecycle_log::record(app, entry);
+        let status = if crashed { "Status: Crashed" } else { "Status: Stopped" };
+        set_sidecar_tray_status(app, name, status);
+        watchdog_pause::hold_restart(name, code, signal);
+        return;
+    }
+
+    restart_after_exit(app, name, code, signal);
+}
+
+/// The supervisor's answer to an unexpected exit: respawn the sidecar, or
+/// give up if its RestartPolicy says so.
+fn restart_after_exit(
+    app: &tauri::AppHandle,
+    name: &'static str,
+    code: Option<i32>,
+    signal: Option<i32>,
+) {
+    let Ok(state) = sidecar_process(app, name) else {
+        return;
+    };
+    let crashed = code != Some(0) || signal.is_some();
+    if let Some(reason) = state.restart_refusal(crashed) {
+        if crashed && state.is_engine() {
+            notify_engine_health(app, "AI Matrx engine crashed", &exit_detail(code, signal));
+        }
+        give_up_restarting(app, name, reason);
+        return;
+    }
+
+    if crashed && state.is_engine() {
+        let detail = exit_detail(code, signal);
+        notify_engine_health(app, "AI Matrx engine crashed — restarting", &detail);
+        state.recovering.store(true, Ordering::SeqCst);
+    }
+    schedule_sidecar_restart(app.clone(), name);
+}
+
+/// Called when a watchdog pause lifts, for a sidecar whose exit during the
+/// pause went unanswered. Hands it to restart_after_exit as if it had just
+/// exited, unless it has been started again (or an external engine
+/// connected) since, the circuit is open, or the app is shutting down.
+fn restart_held_sidecar(
+    app: &tauri::AppHandle,
+    name: &'static str,
+    code: Option<i32>,
+    signal: Option<i32>,
+) {
+    if SHUTDOWN_DONE.load(Ordering::SeqCst) {
+        return;
+    }
+    let Ok(state) = sidecar_process(app, name) else {
+        return;
+    };
+    if state.child.lock_recover().is_some()
+        || state.circuit_open.load(Ordering::SeqCst)
+        || external_engine_url(app, &state).is_some()
+    {
+        return;
+    }
+    log::info!("[sidecar] {} exited during the watchdog pause — restarting it now", name);
+    restart_after_exit(app, name, code, signal);
+}
+
+fn exit_detail(code: Option<i32>, signal: Option<i32>) -> String {
+    match (code, signal) {
+        (_, Some(signal)) => format!("Killed by signal {}.", signal),
+        (Some(code), None) => format!("Exit code {}.", code),
+        (None, None) => "Exit status unknown.".to_string(),
+    }
+}
+
+/// The restart policy ruled out a respawn: leave the sidecar down and tell
+/// the frontend with a terminal `sidecar-gave-up` event. `reason` is
+/// "policy-never", "exited-cleanly" (on-failure after a clean exit) or
+/// "max-restarts".
+fn give_up_restarting(app: &tauri::AppHandle, name: &'static str, reason: &str) {
+    let restarts = sidecar_process(app, name)
+        .map(|p| p.restart_count.load(Ordering::SeqCst))
+        .unwrap_or(0);
+    log::info!("[sidecar] Not restarting {} ({})", name, reason);
+    let port = sidecar_process(app, name)
+        .map(|p| p.port.load(Ordering::SeqCst))
+        .unwrap_or(0);
+    let mut entry = lifecycle_log::LifecycleEntry::new("gave-up", name, None, port);
+    entry.detail = Some(reason.to_string());
+    lifecycle_log::record(app, entry);
+    if reason == "exited-cleanly" {
+        set_sidecar_tray_status(app, name, "Status: Stopped");
+    } else {
+        set_sidecar_tray_status(app, name, "Status: Failed");
+        if name == ENGINE_SIDECAR {
+            set_tray_health(app, TrayHealth::Error);
+        }
+    }
+    let _ = app.emit(
+        "sidecar-gave-up",
+        serde_json::json!({
+            "name": name,
+            "reason": reason,
+            "restarts": restarts,
+        }),
+    );
+}
+
+/// Respawn a crashed sidecar after an exponential backoff delay.
+///
+/// Each call records one failure. Delays run 500ms → 1s → 2s → … capped at
+/// 30s. Once `restart-circuit-max-failures` failures land inside
+/// `restart-circuit-window-secs` the circuit breaker opens: the supervisor
+/// stops restarting, the sidecar goes to the `failed` state, and
+/// `sidecar-circuit-open` (plus the older `sidecar-failed`) is emitted so the
+/// frontend can offer a Retry (reset_circuit_and_start) instead of us
+/// looping forever.
+///
+/// A spawn error during the retry counts as another failure and re-enters
+/// this function, so a binary that can't even start burns through the budget
+/// just like one that crashes on boot.
+fn schedule_sidecar_restart(app: tauri::AppHandle, name: &'static str) {
+    let Ok(state) = sidecar_process(&app, name) else {
+        return;
+    };
+    if state.circuit_open.load(Ordering::SeqCst) {
+        log::debug!("[sidecar] Not restarting {}: the circuit is open", name);
+        return;
+    }
+    let max_failures = settings::restart_circuit_max_failures(&app);
+    let window = settings::restart_circuit_window(&app);
+    let failures = {
+        let mut history = state.crash_history.lock_recover();
+        let now = std::time::Instant::now();
+        history.push_back(now);
+        while history
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > window)
+        {
+            history.pop_front();
+        }
+        history.len()
+    };
+    log::debug!(
+        "[sidecar] {} crashed: {} failure(s) within {}s, circuit opens at {}",
+        name,
+        failures,
+        window.as_secs(),
+        max_failures
+    );
+
+    if failures >= max_failures {
+        log::warn!(
+            "[sidecar] {}: {} failures within {}s — circuit open, no more automatic restarts",
+            name,
+            failures,
+            window.as_secs()
+        );
+        state.circuit_open.store(true, Ordering::SeqCst);
+        state.recovering.store(false, Ordering::SeqCst);
+        set_sidecar_tray_status(&app, name, "Status: Failed");
+        emit_sidecar_state(&app, name, SidecarLifecycle::Failed, None);
+        let payload = serde_json::json!({
+            "name": name,
+            "failures": failures,
+            "window_secs": window.as_secs(),
+        });
+        let _ = app.emit("sidecar-failed", &payload);
+        let _ = app.emit("sidecar-circuit-open", &payload);
+        return;
+    }
+
+    let delay = restart_backoff(failures);
+    set_sidecar_tray_status(&app, name, "Status: Crashed — restarting");
+    log::info!(
+        "[sidecar] Restarting {} in {}ms (failure {} of {})",
+        name,
+        delay.as_millis(),
+        failures,
+        max_failures
+    );
+    let mut entry = lifecycle_log::LifecycleEntry::new(
+        "restart",
+        name,
+        None,
+        state.port.load(Ordering::SeqCst),
+    );
+    entry.detail = Some(format!(
+        "in {}ms (failure {} of {})",
+        delay.as_millis(),
+        failures,
+        max_failures
+    ));
+    lifecycle_log::record(&app, entry);
+    log::debug!("[sidecar] Restart of {} scheduled in {}ms", name, delay.as_millis());
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        if SHUTDOWN_DONE.load(Ordering::SeqCst) {
+            return;
+        }
+        // Someone (the UI, a manual restart) may have started it again
+        // while we were sleeping — don't spawn a second one on top of it.
+        if !sidecar_process(&app, name).is_ok_and(|p| p.child.lock_recover().is_none()) {
+            return;
+        }
+        match spawn_sidecar(&app, name, READY_TIMEOUT).await {
+            Ok(port) => {
+                if let Ok(state) = sidecar_process(&app, name) {
+                    state.restart_count.fetch_add(1, Ordering::SeqCst);
+                }
+                log::info!("[sidecar] {} respawned on port {}", name, port);
+            }
+            Err(e) => {
+                log::error!("[sidecar] Respawn of {} failed: {}", name, e);
+                schedule_sidecar_restart(app.clone(), name);
+            }
+        }
+    });
+}
+
+/// Change how the supervisor treats a sidecar (the engine unless `name` says
+/// otherwise) exiting on its own, for the rest of the session. `max_restarts`
+/// caps supervisor respawns, counting those already made (`None` = no cap).
+/// Takes effect at the next exit; a running process is left alone.
+#[tauri::command]
+fn set_restart_policy(
+    app: tauri::AppHandle,
+    name: Option<String>,
+    policy: RestartPolicy,
+    max_restarts: Option<u32>,
+) -> Result<(), SidecarError> {
+    let state = sidecar_process(&app, name.as_deref().unwrap_or(ENGINE_SIDECAR))?;
+    *state.restart_policy.lock_recover() = (policy, max_restarts);
+    log::info!(
+        "[sidecar] {} restart policy: {:?}, max_restarts={:?}",
+        state.spec.name, policy, max_restarts
+    );
+    Ok(())
+}
+
+/// Backoff delay for the Nth consecutive failure (1-based).
+fn restart_backoff(failures: usize) -> std::time::Duration {
+    let exp = failures.saturating_sub(1).min(16) as u32;
+    let ms = RESTART_BACKOFF_INITIAL_MS
+        .saturating_mul(1u64 << exp)
+        .min(RESTART_BACKOFF_MAX_MS);
+    std::time::Duration::from_millis(ms)
+}
+
+/// Stop a sidecar (the engine unless `name` says otherwise) gracefully.
+///
+/// Uses shutdown_sidecar() — /admin/shutdown + SIGTERM first, force-kill if
+/// the process hasn't exited after SIDECAR_STOP_TIMEOUT. Always clears the
+/// child handle regardless of whether a child was held — this ensures a
+/// subsequent start_sidecar() can always respawn cleanly. A no-op for an
+/// external dev engine. Stopping an ephemeral engine also deletes its data
+/// dir (see ephemeral.rs).
+#[tauri::command]
+async fn stop_sidecar(app: tauri::AppHandle, name: Option<String>) -> Result<(), SidecarError> {
+    let state = sidecar_process(&app, name.as_deref().unwrap_or(ENGINE_SIDECAR))?;
+    adopt::release(&app, state).await;
+    if external_engine_url(&app, state).is_some() {
+        // Not ours to stop — the developer runs it from their terminal.
+        log::info!("[sidecar] External engine in use — stop_sidecar is a no-op");
+        return Ok(());
+    }
+    let exited = stop_sidecar_process(&app, state.spec.name).await?;
+    if exited {
+        ephemeral::finish(&app, state);
+    }
+    Ok(())
+}
+
+/// Shared stop path for the `stop_sidecar` command and reset_app_data.
+/// Returns true once the process is confirmed gone (or none was running).
+async fn stop_sidecar_process(
+    app: &tauri::AppHandle,
+    name: &str,
+) -> Result<bool, SidecarError> {
+    let state = sidecar_process(app, name)?;
+    let name = state.spec.name;
+    let child = state.child.lock_recover().take();
+    state.ready.store(false, Ordering::SeqCst);
+    state.recovering.store(false, Ordering::SeqCst);
+    let pid = child.as_ref().map(|c| c.pid());
+    let mut exited = true;
+    if let Some(c) = child {
+        emit_sidecar_state(app, name, SidecarLifecycle::Stopping, pid);
+        let port = state.port.load(Ordering::SeqCst);
+        exited = tauri::async_runtime::spawn_blocking(move || {
+            shutdown_sidecar(c, Some(port), true, SIDECAR_STOP_TIMEOUT)
+        })
+        .await
+        .map_err(|e| format!("Failed to stop sidecar: {}", e))?;
+    }
+    // Also stop an orphan left on our port — covers the case where the
+    // process was SIGKILLed by the OS (e.g. macOS watchdog) before we could
+    // clear the handle, leaving the port still bound by a lingering child.
+    if state.is_engine() {
+        kill_orphaned_sidecars(state.port.load(Ordering::SeqCst));
+        engine_stream::cancel_all(app);
+    }
+    set_sidecar_tray_status(app, name, "Status: Stopped");
+    emit_sidecar_state(app, name, SidecarLifecycle::Stopped, pid);
+    Ok(exited)
+}
+
+/// Environment variables the app sets itself on every spawn; the user config
+/// can't override them.
+const RESERVED_SIDECAR_ENV: &[&str] = &[
+    "TAURI_SIDECAR",
+    "TAURI_APP_PID",
+    "MATRX_PORT",
+    "MATRX_ENGINE_TOKEN",
+];
+
+/// Set extra environment variables (e.g. `MATRX_DATA_DIR`) and, optionally,
+/// CLI args (e.g. `["--log-level", "debug"]`) for the engine sidecar.
+///
+/// Persisted to the settings store. Changes only apply on the next spawn —
+/// call restart_sidecar to pick them up on a running engine. Passing `None`
+/// for `args` leaves the previously configured args untouched.
+#[tauri::command]
+async fn configure_sidecar_env(
+    app: tauri::AppHandle,
+    env: std::collections::BTreeMap<String, String>,
+    args: Option<Vec<String>>,
+) -> Result<(), String> {
+    if let Some(key) = env.keys().find(|k| RESERVED_SIDECAR_ENV.contains(&k.as_str())) {
+        return Err(format!("{} is managed by the app and cannot be overridden", key));
+    }
+    if let Some(args) = &args {
+        if args.iter().any(|a| a == "--port" || a.starts_with("--port=")) {
+            return Err("--port is managed by the app; set the preferred-port setting instead".into());
+        }
+    }
+
+    let mut config = settings::sidecar_config(&app);
+    config.env = env;
+    if let Some(args) = args {
+        config.args = args;
+    }
+    let value = serde_json::to_value(&config)
+        .map_err(|e| format!("Failed to serialize sidecar config: {}", e))?;
+    settings::set(&app, settings::KEY_SIDECAR_CONFIG, value)
+}
+
+/// Levels the engine's `/admin/log-level` endpoint and `LOG_LEVEL` accept.
+const ENGINE_LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"];
+
+/// Body of the engine's `/admin/log-level` replies.
+#[derive(serde::Deserialize)]
+struct EngineLogLevelResponse {
+    level: String,
+}
+
+/// Change the engine's log level without a restart.
+///
+/// Persists `LOG_LEVEL` in the sidecar config so later spawns start at this
+/// level, then — if the engine is up — POSTs it to `/admin/log-level` and
+/// checks the level the engine reports back. Returns the level in effect.
+#[tauri::command]
+async fn set_log_level(app: tauri::AppHandle, level: String) -> Result<String, SidecarError> {
+    let level = level.trim().to_ascii_lowercase();
+    if !ENGINE_LOG_LEVELS.contains(&level.as_str()) {
+        return Err(format!(
+            "Invalid log level '{}' — expected one of {}",
+            level,
+            ENGINE_LOG_LEVELS.join(", ")
+        )
+        .into());
+    }
+
+    let mut config = settings::sidecar_config(&app);
+    config.env.insert("LOG_LEVEL".to_string(), level.clone());
+    let value = serde_json::to_value(&config)
+        .map_err(|e| format!("Failed to serialize sidecar config: {}", e))?;
+    settings::set(&app, settings::KEY_SIDECAR_CONFIG, value)?;
+
+    let state = app.state::<SidecarState>();
+    let state = state.engine();
+    if state.child.lock_recover().is_none() {
+        // Nothing to reconfigure; the next spawn picks it up.
+        return Ok(level);
+    }
+    if !state.ready.load(Ordering::SeqCst) {
+        return Err(SidecarError::HealthCheckFailed);
+    }
+
+    let request = app
+        .state::<EngineHttp>()
+        .request(&app, reqwest::Method::POST, &["admin", "log-level"])?
+        .timeout(std::time::Duration::from_secs(3))
+        .json(&serde_json::json!({ "level": level }));
+    let body: EngineLogLevelResponse = engine_http::send_retrying(request)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to set engine log level: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected /admin/log-level response: {}", e))?;
+    if body.level != level {
+        return Err(format!(
+            "Engine reports log level '{}' after being set to '{}'",
+            body.level, level
+        )
+        .into());
+    }
+    log::info!("[sidecar] Engine log level set to {}", level);
+    Ok(level)
+}
+
+/// Take the preferred engine port back from an orphaned engine and restart
+/// on it — the "reclaim" action offered after a `sidecar-port-in-use` event.
+///
+/// Refuses with `PortInUse` if the occupant isn't one of our engines (per the
+/// discovery file): killing some unrelated app that happens to use the port
+/// is never OK. Returns the port the new engine was told to bind.
+#[tauri::command]
+async fn reclaim_engine_port(app: tauri::AppHandle) -> Result<u16, SidecarError> {
+    let preferred = settings::preferred_port(&app);
+    let Some(pid) = orphaned_engine_on_port(preferred) else {
+        return Err(SidecarError::PortInUse(preferred));
+    };
+
+    log::info!("[sidecar] Reclaiming port {} from orphaned engine (pid={})", preferred, pid);
+    let exited = tauri::async_runtime::spawn_blocking(move || {
+        terminate_orphaned_engine(pid, SIDECAR_STOP_TIMEOUT)
+    })
+    .await
+    .map_err(|e| format!("Failed to stop orphaned engine: {}", e))?;
+    if !exited {
+        return Err(format!("Orphaned engine (pid={}) did not exit", pid).into());
+    }
+
+    app.state::<SidecarState>()
+        .engine()
+        .port
+        .store(preferred, Ordering::SeqCst);
+    restart_sidecar_process(&app, ENGINE_SIDECAR).await
+}
+
+/// Move the engine to `port`: persist it as the preferred-port setting and,
+/// if the engine is running, restart it there (sidecar-state-changed events
+/// follow as usual). A stopped engine just uses it on its next start.
+///
+/// Rejects ports below 1024, and fails with `PortInUse` if the port is held
+/// by anything but our own engine. An orphaned engine of ours on the port is
+/// stopped first, as reclaim_engine_port does. Returns the port the engine
+/// was told to bind — normally `port`, unless something grabbed it in the
+/// moment between this check and the spawn.
+#[tauri::command]
+async fn set_sidecar_port(app: tauri::AppHandle, port: u16) -> Result<u16, SidecarError> {
+    if port < 1024 {
+        return Err(format!(
+            "Port {} is a privileged port — choose one between 1024 and 65535",
+            port
+        )
+        .into());
+    }
+    let sidecars = app.state::<SidecarState>();
+    let state = sidecars.engine();
+    if external_engine_url(&app, state).is_some() {
+        return Err("The engine port is set by the external engine URL in dev mode"
+            .to_string()
+            .into());
+    }
+
+    let running = state.child.lock_recover().is_some();
+    let ours = running && state.port.load(Ordering::SeqCst) == port;
+    if !ours && std::net::TcpListener::bind(("127.0.0.1", port)).is_err() {
+        let Some(pid) = orphaned_engine_on_port(port) else {
+            return Err(SidecarError::PortInUse(port));
+        };
+        log::info!("[sidecar] Stopping orphaned engine (pid={}) on port {}", pid, port);
+        let exited = tauri::async_runtime::spawn_blocking(move || {
+            terminate_orphaned_engine(pid, SIDECAR_STOP_TIMEOUT)
+        })
+        .await
+        .map_err(|e| format!("Failed to stop orphaned engine: {}", e))?;
+        if !exited {
+            return Err(format!("Orphaned engine (pid={}) did not exit", pid).into());
+        }
+    }
+
+    settings::set(&app, settings::KEY_PREFERRED_PORT, serde_json::json!(port))?;
+    log::info!("[sidecar] Engine port set to {}", port);
+    if ours {
+        return Ok(port);
+    }
+    state.port.store(port, Ordering::SeqCst);
+    if !running {
+        emit_sidecar_state(&app, ENGINE_SIDECAR, SidecarLifecycle::Stopped, None);
+        return Ok(port);
+    }
+    restart_sidecar_process(&app, ENGINE_SIDECAR).await
+}
+
+/// Restart a sidecar (the engine unless `name` says otherwise) atomically.
+///
+/// Doing stop-then-start from JS races with the async spawn, so this command
+/// takes the child, runs the graceful→force shutdown ladder, waits until the OS has
+/// actually reaped the process, and only then spawns a fresh one. Safe to
+/// call when nothing is running — it then behaves like start_sidecar.
+///
+/// Returns the port the new process was told to bind.
+#[tauri::command]
+async fn restart_sidecar(
+    app: tauri::AppHandle,
+    name: Option<String>,
+) -> Result<u16, SidecarError> {
+    restart_sidecar_process(&app, name.as_deref().unwrap_or(ENGINE_SIDECAR)).await
+}
+
+/// Restart the engine without cutting off work in progress, e.g. to pick up
+/// a config change.
+///
+/// POSTs `/admin/drain` so the engine refuses new tool calls, polls
+/// `/admin/status` until `active_jobs` reaches 0, then restarts as
+/// restart_sidecar does. If jobs are still running after `drain_timeout_ms`
+/// (default 60s) it fails with `DrainTimeout` and leaves the engine drained
+/// — the caller can force it with restart_sidecar or wait and retry.
+///
+/// Returns the port the new process was told to bind.
 #[tauri::command]
-async fn start_sidecar(
+async fn reload_sidecar(
     app: tauri::AppHandle,
-    state: tauri::State<'_, SidecarState>,
-) -> Result<(), String> {
-    // Check if already running — but also detect and clear stale handles
-    // where the process exited without going through stop_sidecar().
-    {
-        let mut guard = state.child.lock().unwrap();
-        if guard.is_some() {
-            // On Unix we can probe liveness with kill(pid, 0): if it returns
-            // ESRCH the process is gone and the handle is stale.
-            #[cfg(unix)]
-            {
-                let pid = guard.as_ref().unwrap().pid();
-                let alive = unsafe { libc::kill(pid as libc::pid_t, 0) } == 0;
-                if !alive {
-                    eprintln!(
-                        "[sidecar] Stale child handle (pid={}) detected — process is gone. Clearing.",
-                        pid
-                    );
-                    *guard = None;
-                    // Fall through to respawn below.
-                } else {
-                    return Ok(()); // genuinely still running
-                }
-            }
-            // On Windows we have no cheap liveness check via the plugin API;
-            // treat a held handle as running. The watchdog / restart path
-            // always calls stop_sidecar() first on Windows, so this is fine.
-            #[cfg(not(unix))]
-            return Ok(());
-        }
+    drain_timeout_ms: Option<u64>,
+) -> Result<u16, SidecarError> {
+    let timeout = drain_timeout_ms
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(DRAIN_TIMEOUT);
+    let sidecars = app.state::<SidecarState>();
+    let state = sidecars.engine();
+    if external_engine_url(&app, state).is_some() {
+        // Nothing of ours to drain; this just re-checks the external engine.
+        return restart_sidecar_process(&app, ENGINE_SIDECAR).await;
+    }
+    if state.child.lock_recover().is_none() {
+        return Err(SidecarError::NotRunning);
+    }
+    if !state.ready.load(Ordering::SeqCst) {
+        return Err(SidecarError::HealthCheckFailed);
     }
 
-    // Kill any orphaned sidecar processes from a previous session before
-    // spawning a new one.  Without this, port 22140 may still be held by
-    // a zombie from a crash/force-quit, and the new sidecar will fail.
-    kill_orphaned_sidecars();
+    let request = app
+        .state::<EngineHttp>()
+        .request(&app, reqwest::Method::POST, &["admin", "drain"])?
+        .timeout(QUIT_STATUS_TIMEOUT);
+    engine_http::send_retrying(request)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Engine refused to drain: {}", e))?;
+    log::info!("[sidecar] Draining engine before reload");
 
-    // Build the engine command. On macOS production the engine lives inside
-    // a Helper .app sub-bundle; everywhere else Tauri's externalBin places
-    // it where the sidecar() API expects it.
-    let sidecar_command = {
-        #[cfg(target_os = "macos")]
-        {
-            if let Some(helper_path) = macos_helper_engine_path() {
-                app.shell().command(helper_path)
-            } else {
-                // Dev mode (no .app bundle) — fall through to sidecar() so the
-                // user sees a clear "binary not found" if they actually invoke
-                // start_sidecar from `pnpm tauri:dev`.
-                app.shell()
-                    .sidecar("matrx-engine")
-                    .map_err(|e| format!("Failed to create sidecar command: {}", e))?
-            }
+    let deadline = std::time::Instant::now() + timeout;
+    let mut jobs = None;
+    loop {
+        // An engine that briefly doesn't answer keeps its last known count.
+        if let Some(n) = engine_active_jobs(&app).await {
+            jobs = Some(n);
         }
-        #[cfg(not(target_os = "macos"))]
-        {
-            app.shell()
-                .sidecar("matrx-engine")
-                .map_err(|e| format!("Failed to create sidecar command: {}", e))?
+        // Exited on its own (crash, quit): nothing left to wait for.
+        if jobs == Some(0) || state.child.lock_recover().is_none() {
+            break;
         }
-    };
+        if std::time::Instant::now() >= deadline {
+            let jobs = jobs.unwrap_or(0);
+            log::warn!("[sidecar] Drain timed out with {} job(s) still running", jobs);
+            return Err(SidecarError::DrainTimeout(jobs));
+        }
+        tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+    }
 
-    let sidecar = sidecar_command
-        // Signal to run.py that it is running inside Tauri — suppress pystray tray icon.
-        .env("TAURI_SIDECAR", "1")
-        // Pass the Tauri app's own PID so the Python watchdog can watch the
-        // correct process on Windows.  On Windows, Tauri spawns the sidecar
-        // via an intermediate shim/pipe helper whose PPID is NOT the Tauri
-        // process itself — so os.getppid() in Python points to a short-lived
-        // launcher that exits immediately, causing a false "parent gone" kill.
-        .env("TAURI_APP_PID", std::process::id().to_string());
+    log::info!("[sidecar] Engine drained — restarting");
+    restart_sidecar_process(&app, ENGINE_SIDECAR).await
+}
 
-    let (mut rx, child) = sidecar
-        .spawn()
-        .map_err(|e| format!("Failed to spawn sidecar: {}", e))?;
+/// Shared restart path for the `restart_sidecar` command, the watchdog and
+/// the "Restart Engine" menu items.
+///
+/// Holds the sidecar's spawn gate from before the old child is taken until
+/// the new one is spawned, so a start_sidecar racing the restart waits and
+/// then finds the new child instead of spawning a second one. A restart is
+/// asked for explicitly, so like start_sidecar it closes the circuit breaker.
+async fn restart_sidecar_process(
+    app: &tauri::AppHandle,
+    name: &str,
+) -> Result<u16, SidecarError> {
+    let state = sidecar_process(app, name)?;
+    let name = state.spec.name;
+    let turn = state.spawning.enter().await;
+    // An adopted engine is replaced with a managed one.
+    adopt::release(app, state).await;
+    if let Some(url) = external_engine_url(app, state) {
+        // Can't restart a process we don't own; re-check it instead.
+        return connect_external_engine(app, &url).await;
+    }
+    // Taking the child first also tells the supervisor this exit is
+    // intentional, so it won't race us with its own respawn.
+    let child = state.child.lock_recover().take();
+    state.ready.store(false, Ordering::SeqCst);
+    if let Some(c) = child {
+        let pid = c.pid();
+        let port = state.port.load(Ordering::SeqCst);
+        emit_sidecar_state(app, name, SidecarLifecycle::Stopping, Some(pid));
+        // shutdown_sidecar blocks for up to its timeout — keep it off the async runtime.
+        let exited = tauri::async_runtime::spawn_blocking(move || {
+            shutdown_sidecar(c, Some(port), true, SIDECAR_STOP_TIMEOUT)
+        })
+        .await
+        .map_err(|e| format!("Failed to stop sidecar: {}", e))?;
+        if !exited {
+            return Err(format!(
+                "Sidecar (pid={}) did not exit after force-kill — not respawning",
+                pid
+            )
+            .into());
+        }
+        emit_sidecar_state(app, name, SidecarLifecycle::Stopped, Some(pid));
+    }
 
-    *state.child.lock().unwrap() = Some(child);
+    state.crash_history.lock_recover().clear();
+    state.circuit_open.store(false, Ordering::SeqCst);
+    spawn_sidecar_in_turn(app, name, READY_TIMEOUT, &turn).await
+}
 
-    // Forward sidecar output to Tauri logs AND to the frontend via events.
-    // The SidecarLogs ring buffer stores the last 200 lines so the frontend
-    // can retrieve them on demand (e.g. when the recovery modal opens).
-    let app_handle = app.clone();
-    let log_lines = app.state::<SidecarLogs>().lines.clone();
-    tauri::async_runtime::spawn(async move {
-        use tauri_plugin_shell::process::CommandEvent;
-        while let Some(event) = rx.recv().await {
-            match event {
-                CommandEvent::Stdout(line) => {
-                    let text = String::from_utf8_lossy(&line).to_string();
-                    println!("[engine] {}", text);
-                    {
-                        let mut lines = log_lines.lock().unwrap();
-                        lines.push(format!("[stdout] {}", text));
-                        let excess = lines.len().saturating_sub(200);
-                        if excess > 0 {
-                            lines.drain(..excess);
-                        }
-                    }
-                    let _ = app_handle.emit("sidecar-log", format!("[stdout] {}", text));
-                }
-                CommandEvent::Stderr(line) => {
-                    let text = String::from_utf8_lossy(&line).to_string();
-                    eprintln!("[engine] {}", text);
-                    {
-                        let mut lines = log_lines.lock().unwrap();
-                        lines.push(format!("[stderr] {}", text));
-                        let excess = lines.len().saturating_sub(200);
-                        if excess > 0 {
-                            lines.drain(..excess);
-                        }
-                    }
-                    let _ = app_handle.emit("sidecar-log", format!("[stderr] {}", text));
-                }
-                CommandEvent::Terminated(status) => {
-                    let msg = format!("[terminated] Process exited: {:?}", status);
-                    eprintln!("[engine] {}", msg);
-                    {
-                        let mut lines = log_lines.lock().unwrap();
-                        lines.push(msg.clone());
-                    }
-                    let _ = app_handle.emit("sidecar-log", msg);
-                    break;
-                }
-                _ => {}
+/// "Restart Engine" menu handler (tray and application menu). Disables the
+/// items and shows "Restarting..." for the duration so a second click can't
+/// stack restarts.
+async fn restart_sidecar_from_menu(app: tauri::AppHandle) {
+    let set_items_enabled = |enabled: bool| {
+        if let Some(items) = app.try_state::<RestartEngineItems>() {
+            for item in items.0.lock_recover().iter() {
+                let _ = item.set_enabled(enabled);
             }
         }
-    });
+    };
+    set_items_enabled(false);
+    set_tray_status(&app, "Status: Restarting...");
 
-    Ok(())
+    match restart_sidecar_process(&app, ENGINE_SIDECAR).await {
+        Ok(port) => log::info!("[menu] Engine restarted on port {}", port),
+        Err(e) => {
+            log::error!("[menu] Engine restart failed: {}", e);
+            set_tray_status(&app, "Status: Restart failed");
+        }
+    }
+
+    set_items_enabled(true);
 }
 
-/// Stop the Python/FastAPI engine sidecar gracefully.
+/// Return true if a process with this PID still exists.
 ///
-/// Uses sigterm_then_kill() which sends SIGTERM first (giving Python's signal
-/// handler time to run lifespan teardown) and falls back to SIGKILL after 8s.
-/// Always clears the child handle regardless of whether a child was held —
-/// this ensures a subsequent start_sidecar() can always respawn cleanly.
-#[tauri::command]
-async fn stop_sidecar(state: tauri::State<'_, SidecarState>) -> Result<(), String> {
-    let child = state.child.lock().unwrap().take();
-    if let Some(c) = child {
-        sigterm_then_kill(c);
+/// Unix uses kill(pid, 0). Windows has no signal-0 equivalent, so we ask
+/// `tasklist` to filter by PID and look for it in the CSV output.
+fn process_is_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
+    }
+    #[cfg(windows)]
+    {
+        let filter = format!("PID eq {}", pid);
+        std::process::Command::new("tasklist")
+            .args(["/FI", filter.as_str(), "/NH", "/FO", "CSV"])
+            .output()
+            .map(|out| String::from_utf8_lossy(&out.stdout).contains(&format!("\"{}\"", pid)))
+            .unwrap_or(false)
     }
-    // Also nuke any orphaned processes by name — covers the case where the
-    // process was SIGKILLed by the OS (e.g. macOS watchdog) before we could
-    // clear the handle, leaving port 22140 still bound by a lingering child.
-    kill_orphaned_sidecars();
-    Ok(())
 }
 
-/// Send SIGTERM to the sidecar's PID and wait for it to exit cleanly,
-/// then SIGKILL if it is still alive. This gives Python's signal handler
-/// (_handle_exit) time to set uvicorn.should_exit, which triggers the
-/// FastAPI lifespan teardown (closes proxy, scraper, Playwright browsers,
-/// SQLite, etc.) before the OS reclaims the ports and file handles.
-///
-/// Timeout is 8 s: lifespan teardown stops the proxy (4 s timeout),
-/// scraper Playwright pool (~1-2 s), and SQLite — all sequential.
-#[cfg(unix)]
-fn sigterm_then_kill(child: tauri_plugin_shell::process::CommandChild) {
-    use std::time::{Duration, Instant};
+/// Poll until `pid` is gone or `timeout` elapses. Returns true if it exited.
+fn wait_for_process_exit(pid: u32, timeout: std::time::Duration) -> bool {
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        if !process_is_alive(pid) {
+            return true;
+        }
+        if std::time::Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+}
 
+/// Stop one engine sidecar child, gracefully first if asked to.
+///
+/// Graceful path (`graceful = true`):
+///   1. POST /admin/shutdown (request_admin_shutdown) — works on every
+///      platform and is the only graceful option on Windows, which has no
+///      SIGTERM. The engine self-signals after the response flushes.
+///   2. Unix: SIGTERM as belt-and-suspenders. It fires the same run.py
+///      _handle_exit → uvicorn lifespan teardown (proxy, scraper, Playwright
+///      browsers, SQLite, …), so it's harmless if the HTTP call already did.
+///   3. Wait up to `timeout` for the process to actually exit.
+///
+/// If the graceful path fails or times out — or `graceful = false` — the
+/// process is force-killed via force_kill_sidecar(), and we then wait up to
+/// SIDECAR_KILL_TIMEOUT for the OS to actually reap it — kill is asynchronous,
+/// and returning early lets callers (quit in particular) outlive-race it.
+///
+/// Returns true once the process is confirmed gone. Blocks for up to
+/// `timeout + SIDECAR_KILL_TIMEOUT`; async callers should run it on
+/// spawn_blocking.
+fn shutdown_sidecar(
+    child: tauri_plugin_shell::process::CommandChild,
+    port: Option<u16>,
+    graceful: bool,
+    timeout: std::time::Duration,
+) -> bool {
     let pid = child.pid();
 
-    // Send SIGTERM — gives Python's signal handler time to run lifespan teardown.
-    // SAFETY: kill(2) with SIGTERM is safe; the PID comes from our own child.
-    let term_sent = unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) } == 0;
+    if graceful {
+        if request_sidecar_exit(pid, port) && wait_for_process_exit(pid, timeout) {
+            log::info!("[sidecar] Engine (pid={}) exited gracefully", pid);
+            return true;
+        }
+        log::warn!(
+            "[sidecar] Engine (pid={}) did not exit within {}s — force-killing",
+            pid,
+            timeout.as_secs()
+        );
+    }
+
+    force_kill_sidecar(child);
+    let exited = wait_for_process_exit(pid, SIDECAR_KILL_TIMEOUT);
+    if !exited {
+        log::warn!(
+            "[sidecar] WARNING: engine (pid={}) still alive {}s after force-kill",
+            pid,
+            SIDECAR_KILL_TIMEOUT.as_secs()
+        );
+    }
+    exited
+}
 
-    if term_sent {
-        // Wait up to 20 seconds for clean exit. The Python lifespan teardown
-        // budget is ~25s total (wake-word 3s + scheduler 3s + proxy 4s + tunnel 5s
-        // + scraper 5s + browsers 3s + margin). 20s lets most phases complete;
-        // if it's still alive, SIGKILL ensures we don't hang the Tauri exit.
-        let deadline = Instant::now() + Duration::from_secs(20);
-        loop {
-            std::thread::sleep(Duration::from_millis(100));
-            // kill(pid, 0) = existence check; ESRCH means the process exited.
-            let alive = unsafe { libc::kill(pid as libc::pid_t, 0) } == 0;
-            if !alive || Instant::now() >= deadline {
-                break;
-            }
+/// Deliver a shutdown request to the engine. Returns true if at least one
+/// request (HTTP or signal) was delivered, i.e. it's worth waiting for exit.
+fn request_sidecar_exit(pid: u32, port: Option<u16>) -> bool {
+    let admin_ok = request_admin_shutdown(port);
+
+    #[cfg(unix)]
+    {
+        if admin_ok {
+            // Give the engine a head start on its lifespan teardown before
+            // SIGTERM lands — the /admin/shutdown handler self-signals too.
+            std::thread::sleep(std::time::Duration::from_millis(200));
         }
+        // SAFETY: kill(2) with SIGTERM is safe; the PID comes from our own child.
+        let term_sent = unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) } == 0;
+        admin_ok || term_sent
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        admin_ok
     }
+}
 
-    // Final guarantee: SIGKILL if it did not exit in time (or SIGTERM failed).
+/// Unix: SIGKILL via the plugin's child.kill().
+#[cfg(unix)]
+fn force_kill_sidecar(child: tauri_plugin_shell::process::CommandChild) {
     let _ = child.kill();
 }
 
-/// Windows: no SIGTERM concept. Use `taskkill /F /T /PID` to forcibly
-/// terminate the entire process tree rooted at the sidecar PID.
+/// Windows: use `taskkill /F /T /PID` to forcibly terminate the entire
+/// process tree rooted at the sidecar PID.
 ///
 /// `child.kill()` only kills the immediate process (TerminateProcess API),
 /// leaving child processes (Playwright chromium, uvicorn workers, etc.) alive.
@@ -458,22 +2811,22 @@ fn sigterm_then_kill(child: tauri_plugin_shell::process::CommandChild) {
 /// causes "address already in use" and "file in use" errors on reinstall or
 /// update — requiring a Windows restart to clear. /T kills the entire tree.
 #[cfg(not(unix))]
-fn sigterm_then_kill(child: tauri_plugin_shell::process::CommandChild) {
-    let pid = child.pid();
+fn force_kill_sidecar(child: tauri_plugin_shell::process::CommandChild) {
+    let pid = child.pid().to_string();
 
     // Kill the entire process tree with /F (force) /T (tree) — this terminates
     // Playwright chromium children, uvicorn workers, and any other subprocesses.
     let _ = std::process::Command::new("taskkill")
-        .args(["/F", "/T", "/PID", &pid.to_string()])
+        .args(["/F", "/T", "/PID", pid.as_str()])
         .output();
 
     // Fall back to the Tauri kill() in case taskkill was unavailable or failed.
     let _ = child.kill();
 }
 
-/// Spawn a detached shell subprocess that will reliably terminate every
-/// managed sidecar process on a SIGTERM-then-SIGKILL ladder — even if our
-/// own process SIGABRTs partway through the GGML cleanup below.
+/// Spawn a detached shell subprocess that will reliably terminate our
+/// sidecar processes on a SIGTERM-then-SIGKILL ladder — even if our own
+/// process SIGABRTs partway through the GGML cleanup below.
 ///
 /// Why this exists: `graceful_shutdown_sync` races with GGML's C atexit
 /// handlers — if the wake-word thread, llama-server, or main WhisperContext
@@ -490,14 +2843,16 @@ fn sigterm_then_kill(child: tauri_plugin_shell::process::CommandChild) {
 /// and runs to completion regardless of what happens to us.
 ///
 /// Sequence (executed inside the detached subprocess):
-///   T+0.2s:  pkill -TERM   ← graceful; Python's lifespan teardown gets to run
-///   T+5.2s:  pkill -KILL   ← force; anything still alive dies
+///   T+0.2s:  kill -TERM   ← graceful; Python's lifespan teardown gets to run
+///   T+5.2s:  kill -KILL   ← force; anything still alive dies
 ///
-/// Patterns match the bundled macOS Helper-app ("Matrx Engine"), the Linux
-/// flat binary ("matrx-engine"), the legacy "aimatrx-engine" name, plus
-/// cloudflared and llama-server. They do NOT match "AI Matrx" (the parent
-/// app's process name), so this subprocess can never accidentally kill us
-/// or any other parent app instance.
+/// Only `pids` are targeted — our engine (spawned or adopted) and our
+/// llama-server — plus their direct children as they stand at T+0.2s,
+/// which is where the engine's cloudflared lives. Nothing is matched by
+/// name: a second copy of the app runs its own engine, tunnel and
+/// llama-server, and quitting this copy must leave those alone (see
+/// kill_orphaned_sidecars). Windows kills each PID's whole tree with
+/// `taskkill /T`.
 ///
 /// **Ownership note (see ARCHITECTURE.md → Lifecycle):**
 /// In normal operation, the engine kills cloudflared during its own lifespan
@@ -506,23 +2861,33 @@ fn sigterm_then_kill(child: tauri_plugin_shell::process::CommandChild) {
 /// `graceful_shutdown_sync` has had a chance to complete (we sleep 5s before
 /// SIGKILL), and only catches strays the engine couldn't clean up because
 /// it was killed mid-teardown by GGML's SIGABRT. If the engine's own teardown
-/// runs to completion, every pkill below is a no-op (nothing left to match).
+/// runs to completion, every kill below is a no-op (nothing left to hit).
 /// It is the parachute, not the primary chute.
-fn spawn_detached_shutdown_safety_net() {
+fn spawn_detached_shutdown_safety_net(pids: &[u32]) {
+    if pids.is_empty() {
+        return;
+    }
+    let pid_list = pids
+        .iter()
+        .map(|pid| pid.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+
     #[cfg(unix)]
     {
-        let script = "\
+        let script = format!(
+            "\
             sleep 0.2; \
-            pkill -TERM -f 'Matrx Engine|matrx-engine|aimatrx-engine' 2>/dev/null; \
-            pkill -TERM -f 'cloudflared tunnel' 2>/dev/null; \
+            kids=$(for pid in {pids}; do pgrep -P $pid; done); \
+            kill -TERM {pids} $kids 2>/dev/null; \
             sleep 5; \
-            pkill -KILL -f 'Matrx Engine|matrx-engine|aimatrx-engine' 2>/dev/null; \
-            pkill -KILL -f 'cloudflared tunnel' 2>/dev/null; \
-            pkill -KILL -f 'llama-server' 2>/dev/null; \
-            true";
+            kill -KILL {pids} $kids 2>/dev/null; \
+            true",
+            pids = pid_list
+        );
 
         let mut cmd = std::process::Command::new("sh");
-        cmd.args(["-c", script])
+        cmd.args(["-c", &script])
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::null())
             .stderr(std::process::Stdio::null());
@@ -549,22 +2914,18 @@ fn spawn_detached_shutdown_safety_net() {
     {
         // /T = kill process tree, /F = force. The leading `timeout` gives
         // anything mid-shutdown ~1s to exit on its own before /F lands.
-        let script = "\
-            timeout /t 1 /nobreak >nul & \
-            taskkill /F /T /IM \"matrx-engine.exe\" >nul 2>&1 & \
-            taskkill /F /T /IM \"matrx-engine-x86_64-pc-windows-msvc.exe\" >nul 2>&1 & \
-            taskkill /F /T /IM \"aimatrx-engine.exe\" >nul 2>&1 & \
-            taskkill /F /T /IM \"aimatrx-engine-x86_64-pc-windows-msvc.exe\" >nul 2>&1 & \
-            taskkill /F /T /IM \"cloudflared.exe\" >nul 2>&1 & \
-            taskkill /F /T /IM \"llama-server.exe\" >nul 2>&1 & \
-            exit /b 0";
+        let kills: String = pid_list
+            .split(' ')
+            .map(|pid| format!("taskkill /F /T /PID {} >nul 2>&1 & ", pid))
+            .collect();
+        let script = format!("timeout /t 1 /nobreak >nul & {}exit /b 0", kills);
 
         use std::os::windows::process::CommandExt;
         const DETACHED_PROCESS: u32 = 0x00000008;
         const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
 
         let _ = std::process::Command::new("cmd")
-            .args(["/c", script])
+            .args(["/c", &script])
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::null())
             .stderr(std::process::Stdio::null())
@@ -611,8 +2972,8 @@ fn graceful_shutdown_sync(
         return;
     }
 
-    // STEP 0 (LOAD-BEARING): Spawn a detached subprocess that will pkill all
-    // managed sidecar processes on a 5-second SIGTERM-then-SIGKILL ladder.
+    // STEP 0 (LOAD-BEARING): Spawn a detached subprocess that will kill our
+    // sidecar processes on a 5-second SIGTERM-then-SIGKILL ladder.
     //
     // This is the safety net that guarantees quit shuts down the sidecars
     // even if the GGML cleanup steps below SIGABRT us before reaching the
@@ -620,7 +2981,14 @@ fn graceful_shutdown_sync(
     // for the full rationale; in short, every step below this one is
     // best-effort, and this one always finishes because it lives in a
     // detached subprocess in its own session.
-    spawn_detached_shutdown_safety_net();
+    let llama_pid = llm::server::running_pid();
+    let engine_pid = {
+        let engine = sidecar_state.engine();
+        let child_pid = engine.child.lock_recover().as_ref().map(|c| c.pid());
+        child_pid.or_else(|| engine.adopted.lock_recover().and_then(|a| a.pid))
+    };
+    let owned: Vec<u32> = [engine_pid, llama_pid].into_iter().flatten().collect();
+    spawn_detached_shutdown_safety_net(&owned);
 
     // 0. Signal the wake-word thread to stop, then join it with a timeout.
     //
@@ -639,10 +3007,10 @@ fn graceful_shutdown_sync(
     //    is mid-inference (which can take >300ms on CPU-only machines), the thread outlives
     //    the sleep and GGML state is dropped from underneath it → SIGABRT.
     if let Some(ww) = wake_word_state {
-        *ww.0.running.lock().unwrap() = false;
+        *ww.0.running.lock_recover() = false;
 
         // Take the JoinHandle out of state so we own it for joining.
-        let handle = ww.0.thread_handle.lock().unwrap().take();
+        let handle = ww.0.thread_handle.lock_recover().take();
         if let Some(handle) = handle {
             // Spawn a helper thread to join with a 2-second deadline.
             // std::thread::JoinHandle has no built-in timeout, so we use a channel.
@@ -677,8 +3045,8 @@ fn graceful_shutdown_sync(
     //     to flush all chunks through Whisper; the 5-second timeout is a best-effort
     //     safeguard and is sufficient for typical use.
     if let Some(rec) = recording_state {
-        *rec.flag.lock().unwrap() = false;
-        let handle = rec.thread_handle.lock().unwrap().take();
+        *rec.flag.lock_recover() = false;
+        let handle = rec.thread_handle.lock_recover().take();
         if let Some(handle) = handle {
             let (tx, rx) = std::sync::mpsc::channel::<()>();
             std::thread::spawn(move || {
@@ -697,7 +3065,8 @@ fn graceful_shutdown_sync(
     //    a) Via LlmServerState (tokio::sync::Mutex — try_lock from sync context).
     //       This is the primary path since LlmServer.process is always populated.
     //    b) Via LlmProcessHandle (std::sync::Mutex — legacy/backup).
-    //    c) Via OS kill-by-name as a final fallback.
+    //    c) Via the PID llm::server recorded at spawn, as a final fallback.
+    //       Never by name: another copy of the app may run its own.
     let mut llm_killed = false;
     if let Some(server_state) = llm_server_state {
         if let Ok(mut server) = server_state.try_lock() {
@@ -712,17 +3081,19 @@ fn graceful_shutdown_sync(
             }
         }
     }
-    #[cfg(unix)]
-    {
-        let _ = std::process::Command::new("pkill")
-            .args(["-9", "-f", "llama-server"])
-            .output();
-    }
-    #[cfg(windows)]
-    {
-        let _ = std::process::Command::new("taskkill")
-            .args(["/F", "/T", "/IM", "llama-server.exe"])
-            .output();
+    if let Some(pid) = llama_pid.filter(|&pid| process_is_alive(pid)) {
+        #[cfg(unix)]
+        {
+            // SAFETY: plain kill(2) on the llama-server PID recorded at spawn.
+            unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) };
+        }
+        #[cfg(windows)]
+        {
+            let pid_arg = pid.to_string();
+            let _ = std::process::Command::new("taskkill")
+                .args(["/F", "/T", "/PID", pid_arg.as_str()])
+                .output();
+        }
     }
 
     // 2. Drop the main WhisperContext — this runs GGML cleanup in Rust before
@@ -746,27 +3117,57 @@ fn graceful_shutdown_sync(
     //
     //    Fallback path: if the HTTP call fails (engine wedged, port not
     //    bound yet, etc.) we fall back to SIGTERM, which fires the same
-    //    handler. sigterm_then_kill below escalates to SIGKILL after 20s
-    //    as the last resort. After that point, the detached safety net
-    //    spawned at the top of this function takes over.
-    let admin_shutdown_ok = request_admin_shutdown();
-    if let Some(child) = sidecar_state.child.lock().unwrap().take() {
-        if admin_shutdown_ok {
-            // Give the engine a head start on its lifespan teardown before
-            // we send SIGTERM. The /admin/shutdown handler also sends
-            // SIGTERM internally, so this is largely redundant — but keeps
-            // the existing 20s wait→SIGKILL ladder as a safety belt for
-            // engines that ignore the HTTP-driven self-signal.
-            std::thread::sleep(std::time::Duration::from_millis(200));
+    //    handler. shutdown_sidecar escalates to a force-kill after
+    //    SIDECAR_QUIT_TIMEOUT as the last resort. After that point, the
+    //    detached safety net spawned at the top of this function takes over.
+    let engine = sidecar_state.engine();
+    let port = engine.port.load(Ordering::SeqCst);
+    let child = engine.child.lock_recover().take();
+    //
+    //    shutdown_sidecar only returns once the engine is confirmed dead (or
+    //    it has warned that it couldn't be), so callers that exit right after
+    //    us never leave the engine orphaned holding its port.
+    match child {
+        Some(child) => {
+            let _ = shutdown_sidecar(child, Some(port), true, SIDECAR_QUIT_TIMEOUT);
+        }
+        None => {
+            // No handle (e.g. an engine adopted from a previous session) —
+            // still ask whatever is listening to tear itself down.
+            let _ = request_admin_shutdown(Some(port));
+        }
+    }
+
+    // 3b. Any other sidecars get the same graceful→force ladder. They're
+    //     independent services, so the engine's teardown doesn't cover them.
+    for process in sidecar_state.0.values().filter(|p| !p.is_engine()) {
+        let child = process.child.lock_recover().take();
+        if let Some(child) = child {
+            let port = process.port.load(Ordering::SeqCst);
+            let _ = shutdown_sidecar(child, Some(port), true, SIDECAR_STOP_TIMEOUT);
         }
-        sigterm_then_kill(child);
     }
 
-    // 4. Kill any remaining orphan ENGINE sidecars + clean up discovery file.
-    //    Note: this only kills engine binaries, NOT cloudflared. The engine
+    // 4. Kill a remaining orphan engine on our port + clean up discovery file.
+    //    Note: this only kills the engine, NOT cloudflared. The engine
     //    owns cloudflared (and reclaims/kills any cloudflared orphans during
     //    its own preflight). See kill_orphaned_sidecars() for ownership rules.
-    kill_orphaned_sidecars();
+    kill_orphaned_sidecars(port);
+
+    SHUTDOWN_COMPLETE.store(true, Ordering::SeqCst);
+}
+
+/// Block until a graceful_shutdown_sync started elsewhere has finished, or
+/// `timeout` elapses. Returns false on timeout.
+fn wait_for_shutdown_complete(timeout: std::time::Duration) -> bool {
+    let deadline = std::time::Instant::now() + timeout;
+    while !SHUTDOWN_COMPLETE.load(Ordering::SeqCst) {
+        if std::time::Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    true
 }
 
 /// POST http://127.0.0.1:{port}/admin/shutdown to ask the engine to
@@ -777,6 +3178,9 @@ fn graceful_shutdown_sync(
 /// signal). Returns false if anything fails — port discovery, connection,
 /// timeout — so the caller can fall back to SIGTERM immediately.
 ///
+/// `port` is the port we handed the engine at spawn; if unknown we fall back
+/// to the one the engine recorded in ~/.matrx/local.json.
+///
 /// Why a hand-rolled HTTP request instead of pulling in `reqwest`:
 ///   - We block on this from the synchronous shutdown handler. reqwest's
 ///     blocking client opens its own tokio runtime which conflicts with
@@ -785,8 +3189,8 @@ fn graceful_shutdown_sync(
 ///     reqwest would add ~600 KB to the binary for one POST.
 ///   - We need a tight timeout (1.5s) so a wedged engine doesn't delay
 ///     the shutdown sequence past the detached safety net's window.
-fn request_admin_shutdown() -> bool {
-    let Some(port) = read_engine_port_from_discovery() else {
+fn request_admin_shutdown(port: Option<u16>) -> bool {
+    let Some(port) = port.or_else(read_engine_port_from_discovery) else {
         return false;
     };
 
@@ -829,21 +3233,34 @@ fn request_admin_shutdown() -> bool {
     }
 }
 
+/// ~/.matrx/local.json, where the engine records its pid and port.
+fn discovery_file_path() -> Option<std::path::PathBuf> {
+    #[cfg(unix)]
+    let home = std::env::var("HOME");
+    #[cfg(windows)]
+    let home = std::env::var("USERPROFILE");
+    home.ok()
+        .map(|h| std::path::PathBuf::from(h).join(".matrx").join("local.json"))
+}
+
 /// Read the engine port from ~/.matrx/local.json without pulling in serde_json.
 ///
-/// Mirrors the parsing logic in kill_orphaned_sidecars() — find the "port":N
-/// key in the JSON without a real parser. Returns None on any failure;
-/// callers should treat that as "engine isn't reachable, fall back to SIGTERM".
+/// Returns None on any failure; callers should treat that as "engine isn't
+/// reachable, fall back to SIGTERM".
 fn read_engine_port_from_discovery() -> Option<u16> {
-    let path = {
-        #[cfg(unix)]
-        { std::env::var("HOME").ok().map(|h| std::path::PathBuf::from(h).join(".matrx").join("local.json")) }
-        #[cfg(windows)]
-        { std::env::var("USERPROFILE").ok().map(|h| std::path::PathBuf::from(h).join(".matrx").join("local.json")) }
-    }?;
-    let text = std::fs::read_to_string(path).ok()?;
+    read_discovery_number("port").and_then(|p| u16::try_from(p).ok())
+}
+
+/// Read the engine PID from ~/.matrx/local.json.
+fn read_engine_pid_from_discovery() -> Option<u32> {
+    read_discovery_number("pid").and_then(|p| u32::try_from(p).ok())
+}
+
+/// Find the "key":N entry in the discovery JSON without a real parser.
+fn read_discovery_number(key: &str) -> Option<u64> {
+    let text = std::fs::read_to_string(discovery_file_path()?).ok()?;
     text.split('"')
-        .skip_while(|tok| *tok != "port")
+        .skip_while(|tok| *tok != key)
         .nth(2)
         .and_then(|tok| {
             let digits: String = tok
@@ -855,6 +3272,41 @@ fn read_engine_port_from_discovery() -> Option<u16> {
         })
 }
 
+/// PID of a live engine that the discovery file says is serving `port`.
+/// Callers only ask about ports our managed child isn't on, so a hit is an
+/// orphan. Anything else on the port is a foreign app we must not touch.
+fn orphaned_engine_on_port(port: u16) -> Option<u32> {
+    if read_engine_port_from_discovery()? != port {
+        return None;
+    }
+    let pid = read_engine_pid_from_discovery()?;
+    process_is_alive(pid).then_some(pid)
+}
+
+/// Terminate an engine process we don't hold a CommandChild for: SIGTERM,
+/// wait up to `timeout`, then SIGKILL (Windows: taskkill /F /T for the tree).
+/// Returns true once the process is gone.
+fn terminate_orphaned_engine(pid: u32, timeout: std::time::Duration) -> bool {
+    #[cfg(unix)]
+    {
+        // SAFETY: plain kill(2) on a PID we just verified via the discovery file.
+        unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) };
+        if wait_for_process_exit(pid, timeout) {
+            return true;
+        }
+        unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) };
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = timeout;
+        let pid_arg = pid.to_string();
+        let _ = std::process::Command::new("taskkill")
+            .args(["/F", "/T", "/PID", pid_arg.as_str()])
+            .output();
+    }
+    wait_for_process_exit(pid, SIDECAR_KILL_TIMEOUT)
+}
+
 /// Restart the app after an update with a clean shutdown sequence.
 ///
 /// Unlike calling `relaunch()` directly from the frontend (which terminates
@@ -897,14 +3349,49 @@ async fn restart_for_update(
     Ok(())
 }
 
-/// Get sidecar status.
+/// Get sidecar status (the engine unless `name` says otherwise).
 ///
 /// On Unix, cross-checks the stored PID with the OS to detect stale handles
 /// (process died without going through stop_sidecar). On Windows we trust the
 /// handle — a held handle means the process is alive.
 #[tauri::command]
-async fn sidecar_status(state: tauri::State<'_, SidecarState>) -> Result<SidecarStatus, String> {
-    let mut guard = state.child.lock().unwrap();
+async fn sidecar_status(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, SidecarState>,
+    name: Option<String>,
+) -> Result<SidecarStatus, SidecarError> {
+    let state = state.get(name.as_deref().unwrap_or(ENGINE_SIDECAR))?;
+    let (restart_policy, max_restarts) = *state.restart_policy.lock_recover();
+    if let Some(url) = external_engine_url(&app, state) {
+        let ready = state.ready.load(Ordering::SeqCst);
+        let adopted = *state.adopted.lock_recover();
+        return Ok(SidecarStatus {
+            name: state.spec.name,
+            running: ready,
+            pid: adopted.and_then(|a| a.pid),
+            port: state.port.load(Ordering::SeqCst),
+            ready,
+            base_url: url.trim_end_matches('/').to_string(),
+            uptime_secs: 0,
+            restart_count: 0,
+            paused: state.paused.load(Ordering::SeqCst),
+            suspended: state.suspended.load(Ordering::SeqCst),
+            ephemeral: false,
+            circuit_open: false,
+            unresponsive: false,
+            last_startup_duration_ms: None,
+            bind_host: None,
+            last_exit: None,
+            restart_policy,
+            max_restarts,
+            mode: if adopted.is_some() {
+                "adopted"
+            } else {
+                "external"
+            },
+        });
+    }
+    let mut guard = state.child.lock_recover();
     let running = if let Some(ref child) = *guard {
         #[cfg(unix)]
         {
@@ -912,7 +3399,7 @@ async fn sidecar_status(state: tauri::State<'_, SidecarState>) -> Result<Sidecar
             let alive = unsafe { libc::kill(pid as libc::pid_t, 0) } == 0;
             if !alive {
                 // Clear the stale handle so start_sidecar() can respawn.
-                eprintln!("[sidecar] sidecar_status: pid={} is gone, clearing stale handle", pid);
+                log::warn!("[sidecar] sidecar_status: pid={} is gone, clearing stale handle", pid);
                 *guard = None;
                 false
             } else {
@@ -928,8 +3415,178 @@ async fn sidecar_status(state: tauri::State<'_, SidecarState>) -> Result<Sidecar
         false
     };
     Ok(SidecarStatus {
+        name: state.spec.name,
         running,
-        port: 22140,
+        pid: if running {
+            guard.as_ref().map(|c| c.pid())
+        } else {
+            None
+        },
+        port: state.port.load(Ordering::SeqCst),
+        ready: running && state.ready.load(Ordering::SeqCst),
+        base_url: state.base_url(),
+        uptime_secs: state
+            .started_at
+            .lock_recover()
+            .filter(|_| running)
+            .map(|t| t.elapsed().as_secs())
+            .unwrap_or(0),
+        restart_count: state.restart_count.load(Ordering::SeqCst),
+        paused: running && state.paused.load(Ordering::SeqCst),
+        suspended: running && state.suspended.load(Ordering::SeqCst),
+        ephemeral: state.ephemeral_dir.lock_recover().is_some(),
+        circuit_open: state.circuit_open.load(Ordering::SeqCst),
+        unresponsive: running && state.unresponsive.load(Ordering::SeqCst),
+        last_startup_duration_ms: *state.last_startup_ms.lock_recover(),
+        bind_host: lan_access::effective_bind_host(&app, state),
+        last_exit: state.last_exit.lock_recover().clone(),
+        restart_policy,
+        max_restarts,
+        mode: "managed",
+    })
+}
+
+/// Sample the engine process's CPU and memory usage.
+///
+/// CPU usage is measured between consecutive calls, so the first sample after
+/// launch reads 0 — poll at a steady interval (≥1s) when graphing. Only the
+/// engine process itself is counted, not its children (Playwright, etc.).
+/// Returns `None` when no engine is running.
+#[tauri::command]
+async fn sidecar_metrics(
+    state: tauri::State<'_, SidecarState>,
+    sampler: tauri::State<'_, SidecarMetricsSampler>,
+) -> Result<Option<SidecarMetrics>, SidecarError> {
+    let state = state.engine();
+    let Some(pid) = state.child.lock_recover().as_ref().map(|c| c.pid()) else {
+        return Ok(None);
+    };
+    let uptime_secs = state
+        .started_at
+        .lock_recover()
+        .map(|t| t.elapsed().as_secs())
+        .unwrap_or(0);
+
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    let mut sys = sampler.0.lock_recover();
+    sys.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::Some(&[sys_pid]),
+        true,
+        sysinfo::ProcessRefreshKind::new().with_cpu().with_memory(),
+    );
+    Ok(sys.process(sys_pid).map(|p| SidecarMetrics {
+        pid,
+        cpu_percent: p.cpu_usage(),
+        memory_bytes: p.memory(),
+        uptime_secs,
+    }))
+}
+
+/// Report which engine build is running, via its `/version` endpoint.
+///
+/// Cached per engine process — repeat calls are free until the next
+/// (re)start. Fails fast with `HealthCheckFailed` instead of waiting while
+/// the engine is still booting.
+#[tauri::command]
+async fn sidecar_version(
+    state: tauri::State<'_, SidecarState>,
+    http: tauri::State<'_, EngineHttp>,
+) -> Result<SidecarVersion, SidecarError> {
+    let state = state.engine();
+    let Some(pid) = state.child.lock_recover().as_ref().map(|c| c.pid()) else {
+        return Err(SidecarError::NotRunning);
+    };
+    if let Some((cached_pid, version)) = state.version_cache.lock_recover().clone() {
+        if cached_pid == pid {
+            return Ok(version);
+        }
+    }
+    if !state.ready.load(Ordering::SeqCst) {
+        return Err(SidecarError::HealthCheckFailed);
+    }
+
+    let request = http
+        .request_to(state, reqwest::Method::GET, &["version"])?
+        .timeout(std::time::Duration::from_secs(3));
+    let body: EngineVersionResponse = engine_http::send_retrying(request)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to query engine version: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected /version response: {}", e))?;
+
+    let version = SidecarVersion {
+        engine_version: body.version,
+        python_version: body.python_version,
+        build_date: body.build_date,
+    };
+    *state.version_cache.lock_recover() = Some((pid, version.clone()));
+    Ok(version)
+}
+
+/// One bundled binary as reported by `list_sidecars`.
+#[derive(Debug, Clone, Serialize)]
+struct BundledSidecar {
+    /// externalBin name, e.g. "matrx-engine".
+    name: &'static str,
+    /// Where the app looks for it; empty if that can't be determined.
+    path: String,
+    exists: bool,
+    /// `None` when the file is missing or unreadable.
+    size_bytes: Option<u64>,
+}
+
+/// Report every bundled sidecar binary and whether it is installed, to spot
+/// partial installs where only some binaries made it into the bundle. On
+/// macOS the engine is reported at its Helper bundle path when present.
+#[tauri::command]
+fn list_sidecars() -> Vec<BundledSidecar> {
+    BUNDLED_BINARIES
+        .iter()
+        .map(|&name| {
+            #[cfg(target_os = "macos")]
+            let path = if SIDECARS
+                .iter()
+                .any(|spec| spec.name == ENGINE_SIDECAR && spec.binary == name)
+            {
+                macos_helper_engine_path().or_else(|| bundled_binary_path(name))
+            } else {
+                bundled_binary_path(name)
+            };
+            #[cfg(not(target_os = "macos"))]
+            let path = bundled_binary_path(name);
+            let size_bytes = path
+                .as_ref()
+                .and_then(|p| std::fs::metadata(p).ok())
+                .filter(|m| m.is_file())
+                .map(|m| m.len());
+            BundledSidecar {
+                name,
+                path: path.map(|p| p.display().to_string()).unwrap_or_default(),
+                exists: size_bytes.is_some(),
+                size_bytes,
+            }
+        })
+        .collect()
+}
+
+/// Time one `/health` round trip to a sidecar (the engine unless `name` says
+/// otherwise), for the UI's latency badge. Never fails for a known sidecar:
+/// no answer, a non-200 or a timeout comes back as `ok: false`.
+#[tauri::command]
+async fn ping_sidecar(
+    app: tauri::AppHandle,
+    name: Option<String>,
+) -> Result<SidecarPing, SidecarError> {
+    let name = sidecar_process(&app, name.as_deref().unwrap_or(ENGINE_SIDECAR))?
+        .spec
+        .name;
+    let started = std::time::Instant::now();
+    let ok = ping_sidecar_health(&app, name, PING_TIMEOUT, false).await;
+    Ok(SidecarPing {
+        latency_ms: started.elapsed().as_millis() as u64,
+        ok,
     })
 }
 
@@ -939,14 +3596,16 @@ async fn sidecar_status(state: tauri::State<'_, SidecarState>) -> Result<Sidecar
 /// WebView2 loopback network isolation restriction that blocks `fetch()`
 /// calls to 127.0.0.1 from the JS layer on Windows.
 #[tauri::command]
-async fn check_engine_health(port: u16) -> Result<bool, String> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_millis(2000))
-        .build()
-        .map_err(|e| e.to_string())?;
-
+async fn check_engine_health(
+    http: tauri::State<'_, EngineHttp>,
+    port: u16,
+) -> Result<bool, String> {
     let url = format!("http://127.0.0.1:{}/tools/list", port);
-    match client.get(&url).send().await {
+    let request = http
+        .client()
+        .get(&url)
+        .timeout(std::time::Duration::from_millis(2000));
+    match request.send().await {
         Ok(resp) => Ok(resp.status().is_success()),
         Err(_) => Ok(false),
     }
@@ -957,15 +3616,16 @@ async fn check_engine_health(port: u16) -> Result<bool, String> {
 /// Same rationale as check_engine_health — runs from Rust to bypass Windows
 /// WebView2 loopback isolation that prevents JS fetch() from reaching 127.0.0.1.
 #[tauri::command]
-async fn discover_engine_port() -> Result<Option<u16>, String> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_millis(1000))
-        .build()
-        .map_err(|e| e.to_string())?;
-
+async fn discover_engine_port(
+    http: tauri::State<'_, EngineHttp>,
+) -> Result<Option<u16>, String> {
     for port in 22140u16..22160u16 {
         let url = format!("http://127.0.0.1:{}/tools/list", port);
-        if let Ok(resp) = client.get(&url).send().await {
+        let request = http
+            .client()
+            .get(&url)
+            .timeout(std::time::Duration::from_millis(1000));
+        if let Ok(resp) = request.send().await {
             if resp.status().is_success() {
                 return Ok(Some(port));
             }
@@ -974,27 +3634,64 @@ async fn discover_engine_port() -> Result<Option<u16>, String> {
     Ok(None)
 }
 
-/// Get recent sidecar output lines (for recovery modal diagnostics).
+/// Get recent output lines of a sidecar (the engine unless `name` says
+/// otherwise), for recovery modal diagnostics.
+#[tauri::command]
+async fn get_sidecar_logs(
+    state: tauri::State<'_, SidecarState>,
+    name: Option<String>,
+) -> Result<Vec<String>, SidecarError> {
+    let lines = state
+        .get(name.as_deref().unwrap_or(ENGINE_SIDECAR))?
+        .logs
+        .lock_recover()
+        .clone();
+    Ok(lines)
+}
+
+/// The running engine's MATRX_ENGINE_TOKEN, for the UI to send as the
+/// `X-Engine-Token` header. Changes on every (re)start, so fetch it per
+/// request rather than caching it. `None` when no engine is running.
 #[tauri::command]
-async fn get_sidecar_logs(state: tauri::State<'_, SidecarLogs>) -> Result<Vec<String>, String> {
-    let lines = state.lines.lock().unwrap();
-    Ok(lines.clone())
+fn get_engine_token(state: tauri::State<'_, SidecarState>) -> Option<String> {
+    let state = state.engine();
+    if state.child.lock_recover().is_none() {
+        return None;
+    }
+    state.token.lock_recover().clone()
 }
 
 /// Set whether closing the window hides to tray or quits the app.
+/// Persisted as the `minimize-to-tray-on-close` setting.
 #[tauri::command]
-async fn set_close_to_tray(
-    enabled: bool,
-    state: tauri::State<'_, CloseToTray>,
-) -> Result<(), String> {
-    state.0.store(enabled, Ordering::Relaxed);
-    Ok(())
+async fn set_close_to_tray(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    settings::set(&app, settings::KEY_MINIMIZE_TO_TRAY, serde_json::Value::Bool(enabled))
+}
+
+/// Set what closing the main window does: `"tray"` hides it (the engine
+/// keeps running) and `"exit"` runs the graceful shutdown and quits.
+///
+/// The CloseRequested handler reads the setting on every close, so the
+/// change applies immediately — no restart needed.
+#[tauri::command]
+async fn set_close_behavior(app: tauri::AppHandle, mode: String) -> Result<(), String> {
+    let close_to_tray = match mode.as_str() {
+        "tray" => true,
+        "exit" => false,
+        other => {
+            return Err(format!(
+                "Unknown close behavior '{}' (expected \"tray\" or \"exit\")",
+                other
+            ))
+        }
+    };
+    set_close_to_tray(app, close_to_tray).await
 }
 
 /// Get current close-to-tray setting.
 #[tauri::command]
-async fn get_close_to_tray(state: tauri::State<'_, CloseToTray>) -> Result<bool, String> {
-    Ok(state.0.load(Ordering::Relaxed))
+async fn get_close_to_tray(app: tauri::AppHandle) -> Result<bool, String> {
+    Ok(settings::minimize_to_tray_on_close(&app))
 }
 
 /// Resize the main window to a compact recorder size or restore to full size.
@@ -1092,21 +3789,40 @@ async fn proxy_fetch(url: String) -> Result<FetchResponse, String> {
 #[derive(Clone, Serialize, Debug)]
 struct UpdateProgress {
     status: String,
+    /// False only for `up_to_date`.
+    update_available: bool,
+    /// The manifest's version when there is an update; same as `version`.
+    latest_version: Option<String>,
     version: Option<String>,
     body: Option<String>,
+    /// Release-notes page, from the manifest's optional `notes_url` field.
+    notes_url: Option<String>,
     content_length: Option<u64>,
     downloaded: u64,
 }
 
 /// Check for app updates and optionally install them.
 ///
+/// The release manifest comes from the `update-manifest-url` setting when
+/// set, otherwise from the endpoint compiled into tauri.conf.json; the
+/// updater compares its `version` with the running app's.
+///
 /// When `install` is true, downloads and installs the update, emitting
 /// `update-progress` events with cumulative byte counts so the frontend
 /// can render an accurate progress bar.
 #[tauri::command]
 async fn check_for_updates(app: tauri::AppHandle, install: bool) -> Result<UpdateProgress, String> {
-    let updater = app
-        .updater()
+    let mut builder = app.updater_builder();
+    if let Some(url) = settings::update_manifest_url(&app) {
+        let url = url.parse::<tauri::Url>().map_err(|e| {
+            format!("Invalid {} '{}': {}", settings::KEY_UPDATE_MANIFEST_URL, url, e)
+        })?;
+        builder = builder
+            .endpoints(vec![url])
+            .map_err(|e| format!("Invalid update endpoint: {}", e))?;
+    }
+    let updater = builder
+        .build()
         .map_err(|e| format!("Updater not available: {}", e))?;
 
     let update = updater
@@ -1118,6 +3834,11 @@ async fn check_for_updates(app: tauri::AppHandle, install: bool) -> Result<Updat
         Some(update) => {
             let version = update.version.clone();
             let body = update.body.clone();
+            let notes_url = update
+                .raw_json
+                .get("notes_url")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
 
             if install {
                 let app_handle = app.clone();
@@ -1136,8 +3857,11 @@ async fn check_for_updates(app: tauri::AppHandle, install: bool) -> Result<Updat
                                 "update-progress",
                                 UpdateProgress {
                                     status: "downloading".to_string(),
+                                    update_available: true,
+                                    latest_version: Some(ver.clone()),
                                     version: Some(ver.clone()),
                                     body: None,
+                                    notes_url: None,
                                     content_length,
                                     downloaded: cumulative,
                                 },
@@ -1152,8 +3876,11 @@ async fn check_for_updates(app: tauri::AppHandle, install: bool) -> Result<Updat
 
                 let result = UpdateProgress {
                     status: "installed".to_string(),
+                    update_available: true,
+                    latest_version: Some(version.clone()),
                     version: Some(version),
                     body,
+                    notes_url,
                     content_length: None,
                     downloaded: final_downloaded,
                 };
@@ -1163,8 +3890,11 @@ async fn check_for_updates(app: tauri::AppHandle, install: bool) -> Result<Updat
             } else {
                 Ok(UpdateProgress {
                     status: "available".to_string(),
+                    update_available: true,
+                    latest_version: Some(version.clone()),
                     version: Some(version),
                     body,
+                    notes_url,
                     content_length: None,
                     downloaded: 0,
                 })
@@ -1172,8 +3902,11 @@ async fn check_for_updates(app: tauri::AppHandle, install: bool) -> Result<Updat
         }
         None => Ok(UpdateProgress {
             status: "up_to_date".to_string(),
+            update_available: false,
+            latest_version: None,
             version: None,
             body: None,
+            notes_url: None,
             content_length: None,
             downloaded: 0,
         }),
@@ -1184,7 +3917,14 @@ async fn check_for_updates(app: tauri::AppHandle, install: bool) -> Result<Updat
 /// listener was ready) and clear it from state. Returns null if none pending.
 #[tauri::command]
 fn get_pending_oauth_url(state: tauri::State<'_, PendingOAuthUrl>) -> Option<String> {
-    state.0.lock().unwrap().take()
+    state.0.lock_recover().take()
+}
+
+/// Return the pending deep link (if one arrived before the frontend listener
+/// was ready) and clear it from state. Returns null if none pending.
+#[tauri::command]
+fn get_pending_deep_link(state: tauri::State<'_, PendingDeepLink>) -> Option<DeepLinkPayload> {
+    state.0.lock_recover().take()
 }
 
 /// Bring the main window to front.
@@ -1198,12 +3938,16 @@ fn get_pending_oauth_url(state: tauri::State<'_, PendingOAuthUrl>) -> Option<Str
 /// On macOS, we also switch the activation policy back to Regular so the Dock
 /// icon appears (it was set to Accessory when the window was hidden to tray).
 fn show_main_window(app: &tauri::AppHandle) {
+    if headless() {
+        return;
+    }
     #[cfg(target_os = "macos")]
     {
         // Switch to Regular policy BEFORE show() so the Dock icon appears
         // at the same moment the window becomes visible.  Must be called on
         // the main thread — all callers of show_main_window run on the main
-        // thread (tray event, Reopen event, single-instance callback).
+        // thread (tray event, Reopen event, and the single-instance
+        // callback via run_on_main_thread).
         let _ = app.set_activation_policy(tauri::ActivationPolicy::Regular);
     }
     if let Some(window) = app.get_webview_window("main") {
@@ -1213,74 +3957,647 @@ fn show_main_window(app: &tauri::AppHandle) {
     }
 }
 
+/// Hide the main window to the tray — the counterpart of show_main_window().
+///
+/// On macOS this also switches to Accessory policy so the Dock icon goes away
+/// with the window, same as the CloseRequested hide-to-tray path. Must be
+/// called on the main thread.
+fn hide_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = app.set_activation_policy(tauri::ActivationPolicy::Accessory);
+    }
+}
+
+/// Hand an aimatrx:// URL to the frontend as a `deep-link` event.
+/// OAuth callbacks (aimatrx://auth/callback) additionally go through the
+/// dedicated `oauth-callback` flow that use-auth.ts listens for.
+fn deliver_deep_link(app: &tauri::AppHandle, url_str: String) {
+    let Ok(url) = tauri::Url::parse(&url_str) else {
+        log::warn!("[deep-link] Ignoring unparseable URL: {}", url_str);
+        return;
+    };
+    let path = format!(
+        "/{}{}",
+        url.host_str().unwrap_or_default(),
+        url.path().trim_end_matches('/')
+    );
+    let payload = DeepLinkPayload {
+        url: url_str.clone(),
+        query: url.query_pairs().into_owned().collect(),
+        path,
+    };
+    let is_oauth = payload.path.starts_with("/auth/callback");
+
+    if let Some(state) = app.try_state::<PendingDeepLink>() {
+        *state.0.lock_recover() = Some(payload.clone());
+    }
+    let _ = app.emit("deep-link", payload);
+
+    if is_oauth {
+        deliver_oauth_callback(app, url_str);
+    }
+}
+
+fn deliver_oauth_callback(app: &tauri::AppHandle, url_str: String) {
+    // Store in app state — OAuthPending.tsx will poll this via
+    // get_pending_oauth_url() in case it wasn't mounted yet when
+    // the event fired (race condition on app activation).
+    if let Some(state) = app.try_state::<PendingOAuthUrl>() {
+        *state.0.lock_recover() = Some(url_str.clone());
+    }
+
+    // Also emit the event for the case where OAuthPending IS
+    // already mounted and listening — whichever wins, the other
+    // is ignored via the handled.current guard.
+    let _ = app.emit("oauth-callback", url_str);
+}
+
+/// Record whether sidecar `name` is answering the watchdog, emitting
+/// `engine-unresponsive` / `engine-responsive` only when that changes. The
+/// watchdog calls this on every ping, so a UI banner driven by the events
+/// shows once on the first failure and hides once — on the next good ping,
+/// when a replacement process is ready ("recovered"), or when the process
+/// goes away ("stopped").
+fn set_sidecar_unresponsive(
+    app: &tauri::AppHandle,
+    name: &'static str,
+    unresponsive: bool,
+    pid: Option<u32>,
+    reason: &'static str,
+) {
+    let Ok(process) = sidecar_process(app, name) else {
+        return;
+    };
+    if process.unresponsive.swap(unresponsive, Ordering::SeqCst) == unresponsive {
+        return;
+    }
+    let event = if unresponsive {
+        "engine-unresponsive"
+    } else {
+        "engine-responsive"
+    };
+    log::info!("[sidecar] {} {} ({})", name, event, reason);
+    let _ = app.emit(
+        event,
+        serde_json::json!({ "name": name, "pid": pid, "reason": reason }),
+    );
+}
+
+/// Broadcast a lifecycle transition as `sidecar-state-changed`, so the UI can
+/// react to start/stop/crash without polling sidecar_status.
+fn emit_sidecar_state(
+    app: &tauri::AppHandle,
+    name: &'static str,
+    state: SidecarLifecycle,
+    pid: Option<u32>,
+) {
+    let Ok(process) = sidecar_process(app, name) else {
+        return;
+    };
+    if process.is_engine() {
+        set_tray_health(
+            app,
+            match state {
+                SidecarLifecycle::Running => TrayHealth::Running,
+                SidecarLifecycle::Crashed | SidecarLifecycle::Failed => TrayHealth::Error,
+                _ => TrayHealth::Stopped,
+            },
+        );
+        let running = matches!(state, SidecarLifecycle::Running);
+        if let Some(items) = app.try_state::<ApiDocsItems>() {
+            for item in items.0.lock_recover().iter() {
+                let _ = item.set_enabled(running);
+            }
+        }
+        if !running {
+            process.paused.store(false, Ordering::SeqCst);
+            process.suspended.store(false, Ordering::SeqCst);
+        }
+        if let Some(item) = app.try_state::<PauseEngineItem>() {
+            let _ = item.0.set_enabled(running);
+            let _ = item.0.set_checked(process.paused.load(Ordering::SeqCst));
+        }
+    }
+    match state {
+        SidecarLifecycle::Running => {
+            set_sidecar_unresponsive(app, name, false, pid, "recovered");
+        }
+        SidecarLifecycle::Stopping
+        | SidecarLifecycle::Stopped
+        | SidecarLifecycle::Crashed
+        | SidecarLifecycle::Failed => {
+            set_sidecar_unresponsive(app, name, false, pid, "stopped");
+        }
+        SidecarLifecycle::Starting => {}
+    }
+    let port = process.port.load(Ordering::SeqCst);
+    let exit = match state {
+        SidecarLifecycle::Stopped | SidecarLifecycle::Crashed => process
+            .last_exit
+            .lock_recover()
+            .clone()
+            .filter(|exit| pid == Some(exit.pid)),
+        _ => None,
+    };
+    let mut entry = lifecycle_log::LifecycleEntry::new(
+        match state {
+            SidecarLifecycle::Starting => "start",
+            SidecarLifecycle::Running => "ready",
+            SidecarLifecycle::Stopping => "stopping",
+            SidecarLifecycle::Stopped => "stop",
+            SidecarLifecycle::Crashed => "crash",
+            SidecarLifecycle::Failed => "circuit-open",
+        },
+        name,
+        pid,
+        port,
+    );
+    entry.exit = exit.as_ref().and_then(|e| serde_json::to_value(e).ok());
+    lifecycle_log::record(app, entry);
+    let _ = app.emit(
+        "sidecar-state-changed",
+        SidecarStateEvent {
+            name,
+            state,
+            port,
+            pid,
+            exit,
+        },
+    );
+}
+
+/// set_tray_status() for lifecycle code shared by every sidecar — the tray's
+/// status line only describes the engine.
+fn set_sidecar_tray_status(app: &tauri::AppHandle, name: &str, text: &str) {
+    if name == ENGINE_SIDECAR {
+        set_tray_status(app, text);
+    }
+}
+
+/// Swap the tray icon (and tooltip) to reflect engine health. Some Linux
+/// indicator hosts ignore runtime icon changes; the tooltip is set either
+/// way, so the state is still discoverable there.
+fn set_tray_health(app: &tauri::AppHandle, health: TrayHealth) {
+    let Some(tray) = app.try_state::<TrayHandle>() else {
+        return;
+    };
+    let (icon, tooltip) = match health {
+        TrayHealth::Stopped => (
+            tauri::include_image!("icons/tray/tray-stopped.png"),
+            "AI Matrx — engine stopped",
+        ),
+        TrayHealth::Running => (
+            tauri::include_image!("icons/tray/tray-running.png"),
+            "AI Matrx — engine running",
+        ),
+        TrayHealth::Error => (
+            tauri::include_image!("icons/tray/tray-error.png"),
+            "AI Matrx — engine not responding",
+        ),
+    };
+    if let Err(e) = tray.0.set_icon(Some(icon)) {
+        log::error!("[tray] Could not update icon: {}", e);
+    }
+    let _ = tray.0.set_tooltip(Some(tooltip));
+}
+
+/// Desktop notification for engine crash/recovery — visible even with the
+/// window hidden in the tray. Silenced by the `notify-on-crash` setting.
+/// Falls back to the tray when notifications are denied (see notifications).
+fn notify_engine_health(app: &tauri::AppHandle, title: &str, body: &str) {
+    if !settings::notify_on_crash(app) {
+        return;
+    }
+    notifications::notify(app, title, body);
+}
+
+/// Spawn the engine in the background during setup. A failure can't fail
+/// setup; it's reported like a failed self-check instead: a notification,
+/// `sidecar-autostart-failed` for the frontend, and the window brought up.
+fn autostart_engine(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // An engine the last session left behind saves a cold start.
+        if adopt::try_adopt(&app).await {
+            return;
+        }
+        let spawned = spawn_sidecar(&app, ENGINE_SIDECAR, READY_TIMEOUT).await;
+        let Err(e) = spawned else {
+            return;
+        };
+        log::error!("[sidecar] Autostart failed: {}", e);
+        set_sidecar_tray_status(&app, ENGINE_SIDECAR, "Status: Failed to start");
+        set_tray_health(&app, TrayHealth::Error);
+        if matches!(e, SidecarError::BinaryMissing { .. }) {
+            notify_binary_missing(&app);
+        } else {
+            notifications::notify(&app, "AI Matrx engine failed to start", &e.to_string());
+        }
+        let _ = app.emit("sidecar-autostart-failed", &e);
+        let _ = app.run_on_main_thread({
+            let app = app.clone();
+            move || show_main_window(&app)
+        });
+    });
+}
+
+/// Desktop notification for a broken install (see check_sidecar_binary).
+/// Not gated by `notify-on-crash`: nothing works until the user reinstalls.
+fn notify_binary_missing(app: &tauri::AppHandle) {
+    notifications::notify(
+        app,
+        "AI Matrx needs to be reinstalled",
+        "The AI Matrx engine is missing from this installation. Reinstall to fix it.",
+    );
+}
+
+/// Desktop notification for a found update, so a check started from the
+/// tray is noticed even if the window ends up behind something.
+fn notify_update_available(app: &tauri::AppHandle, version: &str) {
+    let body = format!("Version {} is ready to download.", version);
+    notifications::notify(app, "AI Matrx update available", &body);
+}
+
+/// One-time notification on the first close-to-tray, so a new user doesn't
+/// think closing the window quit the app. `seen-tray-hint` is set even if
+/// the notification can't be shown, so it is never retried.
+fn notify_tray_hint_once(app: &tauri::AppHandle) {
+    if settings::seen_tray_hint(app) {
+        return;
+    }
+    if let Err(e) = settings::set(app, settings::KEY_SEEN_TRAY_HINT, serde_json::json!(true)) {
+        log::error!("[tray] Could not save {}: {}", settings::KEY_SEEN_TRAY_HINT, e);
+    }
+    notifications::notify(
+        app,
+        "AI Matrx is still running in the tray",
+        "Closing the window keeps the engine running. Use the tray icon to reopen or quit.",
+    );
+}
+
+/// Update the tray menu's status line. No-op if the tray isn't set up.
+fn set_tray_status(app: &tauri::AppHandle, text: &str) {
+    if let Some(item) = app.try_state::<TrayStatusItem>() {
+        let _ = item.0.set_text(text);
+    }
+}
+
+/// Open the engine's Swagger UI (`{base_url}/docs`) in the default browser.
+/// Fails with `NotRunning` unless the engine is running and ready — the same
+/// condition that enables the "Open API Docs" menu items.
+#[tauri::command]
+fn open_api_docs(app: tauri::AppHandle) -> Result<(), SidecarError> {
+    let sidecars = app.state::<SidecarState>();
+    let state = sidecars.engine();
+    let external = external_engine_url(&app, state).is_some();
+    if (!external && state.child.lock_recover().is_none()) || !state.ready.load(Ordering::SeqCst)
+    {
+        return Err(SidecarError::NotRunning);
+    }
+    let url = format!("{}/docs", state.base_url());
+    // Shell::open is deprecated in favour of the opener plugin, which this
+    // app doesn't ship; its default scope admits http(s) URLs like this one.
+    #[allow(deprecated)]
+    let opened = app.shell().open(&url, None);
+    opened.map_err(|e| format!("Failed to open {}: {}", url, e).into())
+}
+
+/// Pause the engine to save battery without losing its state: it refuses new
+/// tool calls and stops background scanning until resume_sidecar, while
+/// calls already running finish. The watchdog stands down meanwhile.
+///
+/// Fails with `NotRunning` / `HealthCheckFailed` when there is no ready
+/// engine. Pausing ends with the process — a restart comes back unpaused.
+#[tauri::command]
+async fn pause_sidecar(app: tauri::AppHandle) -> Result<(), SidecarError> {
+    set_engine_paused(&app, true).await
+}
+
+/// Undo pause_sidecar.
+#[tauri::command]
+async fn resume_sidecar(app: tauri::AppHandle) -> Result<(), SidecarError> {
+    set_engine_paused(&app, false).await
+}
+
+async fn set_engine_paused(app: &tauri::AppHandle, paused: bool) -> Result<(), SidecarError> {
+    let action = if paused { "pause" } else { "resume" };
+    let request = app
+        .state::<EngineHttp>()
+        .request(app, reqwest::Method::POST, &["admin", action])?;
+    engine_http::send_retrying(request)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to {} the engine: {}", action, e))?;
+
+    let sidecars = app.state::<SidecarState>();
+    let state = sidecars.engine();
+    state.paused.store(paused, Ordering::SeqCst);
+    // idle.rs marks its own pauses as suspensions after this returns.
+    state.suspended.store(false, Ordering::SeqCst);
+    if let Some(item) = app.try_state::<PauseEngineItem>() {
+        let _ = item.0.set_checked(paused);
+    }
+    let port = state.port.load(Ordering::SeqCst);
+    set_tray_status(
+        app,
+        &if paused {
+            format!("Status: Paused (port {})", port)
+        } else {
+            format!("Status: Running (port {})", port)
+        },
+    );
+    log::info!("[sidecar] Engine {}d", action);
+    let _ = app.emit("sidecar-paused", serde_json::json!({ "paused": paused }));
+    Ok(())
+}
+
+/// Tray "Pause Engine" click. The check mark has already flipped; flip the
+/// engine to match, or put the mark back if that fails.
+async fn toggle_pause_from_menu(app: tauri::AppHandle) {
+    let paused = app.state::<SidecarState>().engine().paused.load(Ordering::SeqCst);
+    if let Err(e) = set_engine_paused(&app, !paused).await {
+        log::warn!("[menu] {}", e);
+        if let Some(item) = app.try_state::<PauseEngineItem>() {
+            let _ = item.0.set_checked(paused);
+        }
+    }
+}
+
+/// Open a directory in the OS file manager (Finder / Explorer / xdg-open).
+///
+/// Deliberately not `app.shell().open()`: the shell plugin's default open
+/// scope only admits http(s)/mailto/tel URLs and rejects filesystem paths.
+fn open_in_file_manager(path: &std::path::Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(windows)]
+    let program = "explorer";
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let program = "xdg-open";
+
+    std::process::Command::new(program)
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
+
+/// Open the OS file manager at `path`'s folder with `path` selected (Finder
+/// reveal / Explorer `/select,`). Linux file managers have no common way to
+/// select a file, so there the folder is just opened.
+fn reveal_in_file_manager(path: &std::path::Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .spawn();
+    #[cfg(windows)]
+    let result = {
+        // Explorer parses its own command line: the path must be quoted on
+        // its own, after the comma, not as part of one quoted argument.
+        use std::os::windows::process::CommandExt;
+        std::process::Command::new("explorer")
+            .raw_arg(format!("/select,\"{}\"", path.display()))
+            .spawn()
+    };
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let result = {
+        let dir = path.parent().unwrap_or(path);
+        std::process::Command::new("xdg-open").arg(dir).spawn()
+    };
+
+    result
+        .map(|_| ())
+        .map_err(|e| format!("Failed to reveal {}: {}", path.display(), e))
+}
+
+/// Single dispatcher for tray and application-menu clicks, which share ids.
+fn handle_menu_event(app: &tauri::AppHandle, id: &str) {
+    match id {
+        "show" => show_main_window(app),
+        "restart_engine" => {
+            tauri::async_runtime::spawn(restart_sidecar_from_menu(app.clone()));
+        }
+        "open_logs" => {
+            if let Err(e) = engine_log::open_log_dir(app) {
+                log::warn!("[menu] {}", e);
+            }
+        }
+        "open_api_docs" => {
+            if let Err(e) = open_api_docs(app.clone()) {
+                log::warn!("[menu] {}", e);
+            }
+        }
+        "pause_engine" => {
+            tauri::async_runtime::spawn(toggle_pause_from_menu(app.clone()));
+        }
+        "check_updates" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                // Same shape as the check_for_updates command's result, plus
+                // an "error" status, so the frontend can reuse its update UI.
+                let payload = match check_for_updates(app.clone(), false).await {
+                    Ok(progress) => {
+                        if let Some(version) = progress.version.as_deref() {
+                            notify_update_available(&app, version);
+                        }
+                        serde_json::to_value(progress).unwrap_or_default()
+                    }
+                    Err(e) => {
+                        log::warn!("[menu] {}", e);
+                        serde_json::json!({ "status": "error", "error": e })
+                    }
+                };
+                show_main_window(&app);
+                let _ = app.emit("update-check-result", payload);
+            });
+        }
+        "start_at_login" => autostart::toggle_from_menu(app),
+        "quit" => quit_app(app),
+        _ => {}
+    }
+}
+
+/// Track a "Restart Engine" item (see RestartEngineItems).
+fn register_restart_item(app: &tauri::AppHandle, item: tauri::menu::MenuItem<tauri::Wry>) {
+    if app.try_state::<RestartEngineItems>().is_none() {
+        app.manage(RestartEngineItems(Mutex::new(Vec::new())));
+    }
+    app.state::<RestartEngineItems>().0.lock_recover().push(item);
+}
+
+/// Track an "Open API Docs" item (see ApiDocsItems).
+fn register_api_docs_item(app: &tauri::AppHandle, item: tauri::menu::MenuItem<tauri::Wry>) {
+    if app.try_state::<ApiDocsItems>().is_none() {
+        app.manage(ApiDocsItems(Mutex::new(Vec::new())));
+    }
+    app.state::<ApiDocsItems>().0.lock_recover().push(item);
+}
+
+/// Tray / app-menu Quit. If the engine reports running jobs, emits
+/// `confirm-quit` and shows the window instead of exiting; the UI answers
+/// with confirm_quit(). Choosing Quit again while that prompt is open quits
+/// anyway, so an unresponsive webview can't keep the app alive.
+fn quit_app(app: &tauri::AppHandle) {
+    if SHUTDOWN_DONE.load(Ordering::SeqCst) || QUIT_PENDING.swap(false, Ordering::SeqCst) {
+        shutdown_and_exit(app);
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match engine_active_jobs(&app).await {
+            Some(jobs) if jobs > 0 => {
+                log::info!("[quit] Engine has {} job(s) running — asking to confirm", jobs);
+                QUIT_PENDING.store(true, Ordering::SeqCst);
+                show_main_window(&app);
+                let _ = app.emit("confirm-quit", serde_json::json!({ "active_jobs": jobs }));
+            }
+            _ => shutdown_and_exit(&app),
+        }
+    });
+}
+
+/// Jobs the engine reports as running (`active_jobs` from GET /admin/status).
+/// `None` when it isn't running or doesn't answer within QUIT_STATUS_TIMEOUT.
+async fn engine_active_jobs(app: &tauri::AppHandle) -> Option<u64> {
+    let state = app.state::<SidecarState>();
+    let state = state.engine();
+    if state.child.lock_recover().is_none() || !state.ready.load(Ordering::SeqCst) {
+        return None;
+    }
+    // Sent once: quit is waiting on this, and the drain loop tolerates a miss.
+    let body: serde_json::Value = app
+        .state::<EngineHttp>()
+        .request_to(state, reqwest::Method::GET, &["admin", "status"])
+        .ok()?
+        .timeout(QUIT_STATUS_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    body.get("active_jobs").and_then(|v| v.as_u64())
+}
+
+/// The UI's answer to `confirm-quit`: `force` quits with the usual graceful
+/// shutdown; otherwise the quit is dropped and the window brought forward.
+#[tauri::command]
+fn confirm_quit(app: tauri::AppHandle, force: bool) {
+    QUIT_PENDING.store(false, Ordering::SeqCst);
+    if force {
+        shutdown_and_exit(&app);
+    } else {
+        show_main_window(&app);
+    }
+}
+
+/// Shut everything down gracefully, then exit.
+fn shutdown_and_exit(app: &tauri::AppHandle) {
+    // Run graceful shutdown on a background thread so the main thread
+    // stays responsive to macOS's NSApplication watchdog (which sends
+    // SIGKILL if the main thread is unresponsive for ~5-10s).
+    //
+    // If SHUTDOWN_DONE is already true (e.g. the window CloseRequested
+    // handler already started cleanup), we don't run it again — but we
+    // still wait for that run to finish so we never exit while the
+    // engine is mid-teardown.
+    if SHUTDOWN_DONE.load(Ordering::SeqCst) {
+        let app_handle = app.clone();
+        std::thread::spawn(move || {
+            if !wait_for_shutdown_complete(SHUTDOWN_WAIT_TIMEOUT) {
+                log::warn!(
+                    "[quit] Shutdown still running after {}s — exiting anyway",
+                    SHUTDOWN_WAIT_TIMEOUT.as_secs()
+                );
+            }
+            app_handle.exit(0);
+        });
+        return;
+    }
+    let app_handle = app.clone();
+    // AppHandle implements Send+Sync and can retrieve managed state
+    // from any thread — use it instead of raw pointer casts.
+    std::thread::spawn(move || {
+        if let (Some(sidecar), Some(transcription), Some(llm_proc)) = (
+            app_handle.try_state::<SidecarState>(),
+            app_handle.try_state::<TranscriptionState>(),
+            app_handle.try_state::<llm::commands::LlmProcessHandle>(),
+        ) {
+            let llm_srv = app_handle.try_state::<llm::commands::LlmServerState>();
+            let ww = app_handle.try_state::<WakeWordAppState>();
+            let rec = app_handle.try_state::<RecordingState>();
+            graceful_shutdown_sync(
+                &sidecar,
+                &transcription,
+                &llm_proc,
+                llm_srv.as_deref(),
+                ww.as_deref(),
+                rec.as_deref(),
+            );
+        }
+        app_handle.exit(0);
+    });
+}
+
 /// Set up the system tray icon and menu.
 ///
 /// Only ONE tray icon is created here — the auto-trayIcon in tauri.conf.json
 /// has been removed to prevent a second blank icon from appearing.
 fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     let show = MenuItemBuilder::with_id("show", "Show AI Matrx").build(app)?;
-    let status = MenuItemBuilder::with_id("status", "Status: Starting...")
+    let status = MenuItemBuilder::with_id("status", "Status: Stopped")
+        .enabled(false)
+        .build(app)?;
+    app.manage(TrayStatusItem(status.clone()));
+    let restart_engine =
+        MenuItemBuilder::with_id("restart_engine", "Restart Engine").build(app)?;
+    register_restart_item(app.handle(), restart_engine.clone());
+    let pause_engine = CheckMenuItemBuilder::with_id("pause_engine", "Pause Engine")
+        .enabled(false)
+        .build(app)?;
+    app.manage(PauseEngineItem(pause_engine.clone()));
+    let open_logs = MenuItemBuilder::with_id("open_logs", "Open Logs").build(app)?;
+    let open_api_docs = MenuItemBuilder::with_id("open_api_docs", "Open API Docs")
         .enabled(false)
         .build(app)?;
+    register_api_docs_item(app.handle(), open_api_docs.clone());
+    let check_updates =
+        MenuItemBuilder::with_id("check_updates", "Check for Updates…").build(app)?;
+    let start_at_login = CheckMenuItemBuilder::with_id("start_at_login", "Start at Login")
+        .checked(autostart::is_enabled(app.handle()))
+        .build(app)?;
+    app.manage(autostart::AutostartMenuItem(start_at_login.clone()));
     let quit = MenuItemBuilder::with_id("quit", "Quit AI Matrx").build(app)?;
 
     let menu = MenuBuilder::new(app)
         .item(&show)
         .separator()
         .item(&status)
+        .item(&restart_engine)
+        .item(&pause_engine)
+        .separator()
+        .item(&open_logs)
+        .item(&open_api_docs)
+        .item(&check_updates)
+        .item(&start_at_login)
         .separator()
         .item(&quit)
         .build()?;
 
-    // Use the application's default window icon for the tray.
-    // `app.default_window_icon()` returns the icon configured by the Tauri build system
-    // (from the icon list in tauri.conf.json), so the tray icon always matches the
-    // dock / taskbar icon without any runtime file path resolution.
-    let tray_builder = TrayIconBuilder::new()
+    // Start on the grey "stopped" icon; set_tray_health() swaps between the
+    // bundled icons/tray/ images as the engine's lifecycle changes. They're
+    // embedded at compile time, so there's no runtime path resolution.
+    // Item clicks are handled by the app-wide handle_menu_event(), shared
+    // with the application menu (see app_menu.rs).
+    let tray = TrayIconBuilder::new()
+        .icon(tauri::include_image!("icons/tray/tray-stopped.png"))
         .menu(&menu)
-        .tooltip("AI Matrx")
-        .on_menu_event(move |app, event| match event.id().as_ref() {
-            "show" => {
-                show_main_window(app);
-            }
-            "quit" => {
-                // Run graceful shutdown on a background thread so the main thread
-                // stays responsive to macOS's NSApplication watchdog (which sends
-                // SIGKILL if the main thread is unresponsive for ~5-10s).
-                //
-                // If SHUTDOWN_DONE is already true (e.g. the window CloseRequested
-                // handler already started cleanup), we exit immediately instead of
-                // spawning a redundant thread.
-                if SHUTDOWN_DONE.load(Ordering::SeqCst) {
-                    app.exit(0);
-                    return;
-                }
-                let app_handle = app.clone();
-                // AppHandle implements Send+Sync and can retrieve managed state
-                // from any thread — use it instead of raw pointer casts.
-                std::thread::spawn(move || {
-                    if let (Some(sidecar), Some(transcription), Some(llm_proc)) = (
-                        app_handle.try_state::<SidecarState>(),
-                        app_handle.try_state::<TranscriptionState>(),
-                        app_handle.try_state::<llm::commands::LlmProcessHandle>(),
-                    ) {
-                        let llm_srv = app_handle.try_state::<llm::commands::LlmServerState>();
-                        let ww = app_handle.try_state::<WakeWordAppState>();
-                        let rec = app_handle.try_state::<RecordingState>();
-                        graceful_shutdown_sync(
-                            &sidecar,
-                            &transcription,
-                            &llm_proc,
-                            llm_srv.as_deref(),
-                            ww.as_deref(),
-                            rec.as_deref(),
-                        );
-                    }
-                    app_handle.exit(0);
-                });
-            }
-            _ => {}
-        })
+        .tooltip("AI Matrx — engine stopped")
         .on_tray_icon_event(|tray, event| {
             if let TrayIconEvent::Click {
                 button: MouseButton::Left,
@@ -1288,60 +4605,85 @@ fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
                 ..
             } = event
             {
-                show_main_window(tray.app_handle());
+                let app = tray.app_handle();
+                // Visible-but-unfocused still counts as visible: clicking the
+                // tray takes focus from the window on Windows.
+                let visible = app.get_webview_window("main").is_some_and(|w| {
+                    w.is_visible().unwrap_or(false) && !w.is_minimized().unwrap_or(false)
+                });
+                if visible && settings::tray_click_toggles(app) {
+                    hide_main_window(app);
+                } else {
+                    show_main_window(app);
+                }
             }
-        });
-
-    let _tray = if let Some(icon) = app.default_window_icon() {
-        tray_builder.icon(icon.clone()).build(app)?
-    } else {
-        tray_builder.build(app)?
-    };
+        })
+        .build(app)?;
+    app.manage(TrayHandle(tray));
 
     Ok(())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    panic_hook::install();
     tauri::Builder::default()
         // Single-instance must be registered before deep-link so that on Windows,
         // when the OS launches a second instance to deliver an aimatrx:// deep-link
         // URL, this plugin intercepts it, terminates the new instance, and forwards
         // the argv (which contains the aimatrx:// URL) to the already-running app.
         // The callback below handles that forwarded URL the same way on_open_url does.
-        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+        //
+        // Any launch while we're running — with or without a URL — lands here
+        // instead of starting a second engine sidecar on the same port.
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            log::info!("[single-instance] Second launch redirected, argv: {:?}", argv);
+
+            // Always re-reveal the window: it is usually hidden to tray (and
+            // on macOS the app is in Accessory policy). Hop to the main thread
+            // because the plugin doesn't guarantee which thread calls us and
+            // set_activation_policy must run on the main thread.
+            let handle = app.clone();
+            let _ = app.run_on_main_thread(move || show_main_window(&handle));
+
             // Find the aimatrx:// URL in the forwarded arguments and process it.
             if let Some(url_str) = argv.iter().find(|a| a.starts_with("aimatrx://")) {
-                println!("[single-instance] Received deep-link via argv: {}", url_str);
-                show_main_window(app);
-                if let Some(state) = app.try_state::<PendingOAuthUrl>() {
-                    *state.0.lock().unwrap() = Some(url_str.clone());
-                }
-                let _ = app.emit("oauth-callback", url_str.clone());
-            } else {
-                // No deep-link URL — just bring the existing window to front.
-                show_main_window(app);
+                log::info!("[single-instance] Received deep-link via argv: {}", url_str);
+                deliver_deep_link(app, url_str.clone());
             }
+
+            // Forward the raw launch arguments too, for anything beyond OAuth.
+            let _ = app.emit(
+                "second-instance",
+                SecondInstancePayload {
+                    args: argv.into_iter().skip(1).collect(),
+                    cwd,
+                },
+            );
         }))
         .plugin(tauri_plugin_deep_link::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(shortcuts::handle)
+                .build(),
+        )
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_macos_permissions::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_autostart::init(
             MacosLauncher::LaunchAgent,
-            None,
+            // Lets setup recognise login launches (autostart::launched_at_login).
+            Some(vec![autostart::AUTOSTART_ARG]),
         ))
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
-        .manage(SidecarState {
-            child: Mutex::new(None),
-        })
-        .manage(SidecarLogs {
-            lines: Arc::new(Mutex::new(Vec::new())),
-        })
-        .manage(CloseToTray(AtomicBool::new(true)))
+        .manage(SidecarState::new())
+        .manage(EngineHttp::new())
+        .manage(EngineStreams::default())
+        .manage(SidecarMetricsSampler(Mutex::new(sysinfo::System::new())))
         .manage(PendingOAuthUrl(Mutex::new(None)))
+        .manage(PendingDeepLink(Mutex::new(None)))
         .manage(TranscriptionState(Mutex::new(None)))
         .manage(RecordingState::new())
         .manage(WakeWordAppState(Arc::new(WakeWordState::new())))
@@ -1361,18 +4703,83 @@ pub fn run() {
         // Universal download manager — initialized in .setup() below after AppHandle is available
         .invoke_handler(tauri::generate_handler![
             start_sidecar,
+            wait_for_ready,
             stop_sidecar,
+            restart_sidecar,
+            reload_sidecar,
+            set_restart_policy,
+            set_data_dir,
+            run_self_check,
+            get_feature_flags,
+            set_feature_flags,
+            configure_sidecar_env,
+            set_log_level,
+            reclaim_engine_port,
+            set_sidecar_port,
             restart_for_update,
+            confirm_quit,
+            reset_app_data,
+            export_diagnostics,
             sidecar_status,
+            sidecar_metrics,
+            sidecar_version,
+            ping_sidecar,
+            list_sidecars,
             get_sidecar_logs,
+            get_engine_token,
+            request_notification_permission,
+            open_api_docs,
+            pause_sidecar,
+            resume_sidecar,
+            list_active_jobs,
+            cancel_job,
+            subscribe_engine_stream,
+            unsubscribe_engine_stream,
+            get_engine_config,
+            patch_engine_config,
+            set_engine_config_file,
+            check_version_compat,
+            probe_existing_sidecar,
+            check_port,
+            get_openapi_schema,
+            start_sidecar_ephemeral,
+            get_resource_limits,
+            set_resource_limits,
+            reveal_sidecar_binary,
+            dump_engine_state,
+            reset_circuit_and_start,
+            kill_stray_engines,
+            get_lifecycle_history,
+            set_app_log_level,
+            schedule_restart,
+            cancel_scheduled_restart,
+            get_effective_sidecar_args,
+            set_lan_access,
+            pause_watchdog,
+            resume_watchdog,
+            data_dir_space,
             check_engine_health,
             discover_engine_port,
             set_close_to_tray,
+            set_close_behavior,
             get_close_to_tray,
+            get_setting,
+            set_setting,
+            export_settings,
+            import_settings,
+            set_global_shortcut,
+            set_quit_shortcut,
+            set_autostart,
+            is_autostart_enabled,
             check_for_updates,
             set_compact_mode,
             proxy_fetch,
             get_pending_oauth_url,
+            get_pending_deep_link,
+            get_log_path,
+            tail_engine_log,
+            query_engine_log,
+            rotate_engine_log,
             // Transcription commands
             detect_hardware,
             download_whisper_model,
@@ -1429,6 +4836,22 @@ pub fn run() {
             // was booting up.
             kill_orphaned_llama_server();
 
+            // ── Engine log file ────────────────────────────────────────────
+            // One writer for the whole app lifetime so size-based rotation
+            // stays consistent across sidecar restarts.
+            {
+                let log_dir = app
+                    .path()
+                    .app_log_dir()
+                    .or_else(|_| app.path().app_data_dir().map(|d| d.join("logs")))
+                    .unwrap_or_else(|_| std::env::temp_dir().join("aimatrx-logs"));
+                panic_hook::attach(app.handle(), &log_dir);
+                app_log::init(log_dir.clone());
+                app.manage(
+                    Arc::new(engine_log::EngineLog::new(log_dir)) as engine_log::EngineLogState
+                );
+            }
+
             // ── Universal download manager ─────────────────────────────────
             // Must be initialized here (after AppHandle is available) so the
             // worker tokio task has a live AppHandle for event emission.
@@ -1459,7 +4882,7 @@ pub fn run() {
                     // saved value instead of the hardcoded default.
                     if !config.wake_keyword.is_empty() {
                         let ww = handle.state::<WakeWordAppState>();
-                        *ww.0.keyword.lock().unwrap() = config.wake_keyword.clone();
+                        *ww.0.keyword.lock_recover() = config.wake_keyword.clone();
                     }
 
                     if !config.setup_complete {
@@ -1482,7 +4905,7 @@ pub fn run() {
                     }
                     let state = handle.state::<TranscriptionState>();
                     // Only load if not already initialized (another path may have beaten us).
-                    if state.0.lock().unwrap().is_some() {
+                    if state.0.lock_recover().is_some() {
                         return;
                     }
                     match tokio::task::spawn_blocking(move || {
@@ -1491,11 +4914,11 @@ pub fn run() {
                     .await
                     {
                         Ok(Ok(manager)) => {
-                            *state.0.lock().unwrap() = Some(manager);
-                            println!("[transcription] Auto-loaded model: {}", filename);
+                            *state.0.lock_recover() = Some(manager);
+                            log::info!("[transcription] Auto-loaded model: {}", filename);
                         }
-                        Ok(Err(e)) => eprintln!("[transcription] Auto-load failed: {}", e),
-                        Err(e) => eprintln!("[transcription] Auto-load task panicked: {}", e),
+                        Ok(Err(e)) => log::error!("[transcription] Auto-load failed: {}", e),
+                        Err(e) => log::error!("[transcription] Auto-load task panicked: {}", e),
                     }
                 });
             }
@@ -1529,25 +4952,25 @@ pub fn run() {
                     use llm::model_selector::compute_gpu_layers_for_hw;
                     use llm::server::find_free_port;
 
-                    println!("[llm-autostart] entry");
+                    log::info!("[llm-autostart] entry");
 
                     let config_dir = match handle.path().app_data_dir() {
                         Ok(d) => d,
                         Err(e) => {
-                            println!("[llm-autostart] skip: app_data_dir unavailable: {}", e);
+                            log::info!("[llm-autostart] skip: app_data_dir unavailable: {}", e);
                             return;
                         }
                     };
                     let config = LlmConfig::load(&config_dir);
                     if !config.setup_complete {
-                        println!(
+                        log::info!(
                             "[llm-autostart] skip: setup_complete=false — \
                              user has not finished LLM setup yet"
                         );
                         return;
                     }
                     let Some(filename) = config.selected_model.clone() else {
-                        println!(
+                        log::info!(
                             "[llm-autostart] skip: setup_complete=true but \
                              selected_model is None"
                         );
@@ -1555,7 +4978,7 @@ pub fn run() {
                     };
                     let model_path = config_dir.join("models").join(&filename);
                     if !model_path.exists() {
-                        println!(
+                        log::info!(
                             "[llm-autostart] skip: selected_model={} but file \
                              not found at {}",
                             filename,
@@ -1568,7 +4991,7 @@ pub fn run() {
                     {
                         let server = llm_state.lock().await;
                         if server.status.running {
-                            println!(
+                            log::info!(
                                 "[llm-autostart] skip: server already running on port {}",
                                 server.status.port
                             );
@@ -1576,7 +4999,7 @@ pub fn run() {
                         }
                     }
 
-                    println!(
+                    log::info!(
                         "[llm-autostart] proceeding: model={} setup_complete=true file_exists=true running=false",
                         filename
                     );
@@ -1590,12 +5013,12 @@ pub fn run() {
                     let port = match find_free_port(11434) {
                         Ok(p) => p,
                         Err(e) => {
-                            eprintln!("[llm-autostart] FAIL: could not find free port: {}", e);
+                            log::error!("[llm-autostart] FAIL: could not find free port: {}", e);
                             return;
                         }
                     };
 
-                    println!(
+                    log::info!(
                         "[llm-autostart] spawning llama-server: model={} port={} gpu_layers={} ctx={}",
                         filename, port, gpu_layers, ctx
                     );
@@ -1631,51 +5054,144 @@ pub fn run() {
                             updated.last_port = Some(port);
                             let _ = updated.save(&config_dir);
                             let _ = handle.emit("llm-server-ready", &server.status);
-                            println!(
+                            log::info!(
                                 "[llm-autostart] SUCCESS: llama-server ready on port {} (model={})",
                                 port, filename
                             );
                         }
-                        Err(e) => eprintln!("[llm-autostart] FAIL: server.start() returned: {}", e),
+                        Err(e) => {
+                            log::error!("[llm-autostart] FAIL: server.start() returned: {}", e)
+                        }
                     }
                 });
             }
 
-            // Register the deep-link listener for OAuth callbacks.
-            // When Supabase redirects to aimatrx://auth/callback?..., the OS fires this handler.
+            // Register the deep-link listener for aimatrx:// URLs, including
+            // OAuth callbacks: when Supabase redirects to
+            // aimatrx://auth/callback?..., the OS fires this handler.
+            //
+            // When the app isn't running yet, Windows/Linux hand the launch
+            // URL over in argv instead; pick it up here. (macOS delivers it
+            // through on_open_url below either way.)
+            #[cfg(not(target_os = "macos"))]
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                if let Some(url) = urls.first() {
+                    log::info!("[deep-link] Launched with URL: {}", url);
+                    deliver_deep_link(app.handle(), url.to_string());
+                }
+            }
             let handle = app.handle().clone();
             app.deep_link().on_open_url(move |event| {
                 let urls = event.urls();
                 if let Some(url) = urls.first() {
                     let url_str = url.to_string();
-                    println!("[deep-link] Received URL: {}", url_str);
+                    log::info!("[deep-link] Received URL: {}", url_str);
 
                     // Bring the window to front
                     show_main_window(&handle);
-
-                    // Store in app state — OAuthPending.tsx will poll this via
-                    // get_pending_oauth_url() in case it wasn't mounted yet when
-                    // the event fired (race condition on app activation).
-                    if let Some(state) = handle.try_state::<PendingOAuthUrl>() {
-                        *state.0.lock().unwrap() = Some(url_str.clone());
-                    }
-
-                    // Also emit the event for the case where OAuthPending IS
-                    // already mounted and listening — whichever wins, the other
-                    // is ignored via the handled.current guard.
-                    let _ = handle.emit("oauth-callback", url_str);
+                    deliver_deep_link(&handle, url_str);
                 }
             });
 
+            // Re-apply the main window geometry saved on the last move/resize/close.
+            window_state::restore(app.handle());
+
+            // The main window is created hidden (tauri.conf.json "visible":
+            // false) so a login launch can stay in the tray without flashing,
+            // and a normal launch appears at its restored geometry instead of
+            // jumping there from the default position.
+            if headless() {
+                log::info!("[headless] MATRX_HEADLESS set — no tray, window stays hidden");
+                hide_main_window(app.handle());
+            } else if autostart::launched_at_login()
+                && settings::start_hidden_at_login(app.handle())
+            {
+                log::info!("[autostart] Launched at login — starting hidden in the tray");
+                hide_main_window(app.handle());
+            } else if !splash::show(app.handle()) {
+                // Otherwise the splash brings the main window up when it closes.
+                show_main_window(app.handle());
+            }
+
             // Set up ONE system tray icon for the whole application.
             // The trayIcon declaration in tauri.conf.json has been removed to prevent
             // a second blank icon from appearing alongside this one.
-            if let Err(e) = setup_tray(app) {
-                eprintln!("Failed to setup tray: {}", e);
+            if !headless() {
+                if let Err(e) = setup_tray(app) {
+                    log::error!("Failed to setup tray: {}", e);
+                }
+            }
+
+            // After the tray, which is where alerts go if permission is denied.
+            notifications::check_permission(app.handle());
+
+            // Hold off the watchdog after the machine wakes from sleep.
+            power::start(app.handle());
+
+            // Suspend the engine when idle, if idle-suspend-minutes is set.
+            idle::start(app.handle());
+
+            // Warn when the data folder's disk runs low on space.
+            disk_space::start(app.handle());
+
+            // SIGTERM / logoff and friends shut the engine down like Quit.
+            signals::install(app.handle());
+
+            // After the notification check, which it reports on. A critical
+            // failure brings the window up even on a start-hidden launch so
+            // the problem is on screen rather than silent.
+            let checks = self_check::run_checks(app.handle());
+            if self_check::has_critical_failure(&checks) {
+                show_main_window(app.handle());
+            }
+
+            // Native application menu (macOS menu bar / Windows+Linux window
+            // menu). Clicks from it and from the tray share one dispatcher.
+            if let Err(e) = app_menu::setup(app) {
+                log::error!("Failed to setup app menu: {}", e);
+            }
+            app.on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()));
+
+            // Register the saved (or default) show/hide and quit hotkeys. A
+            // failure here (accelerator taken by another app) must not block
+            // startup.
+            if let Err(e) = shortcuts::register_saved(app.handle()) {
+                log::warn!("[shortcut] {}", e);
+            }
+
+            // ── Engine sidecar startup settings ───────────────────────────
+            // Before anything can spawn the engine: drop the data dir of an
+            // ephemeral engine the last session didn't get to stop.
+            ephemeral::clean_stale(app.handle());
+            // Seed the port the first spawn tries — the last port the engine
+            // served on, else preferred-port. pick_sidecar_port() still
+            // falls back to an ephemeral port if it's taken.
+            app.state::<SidecarState>()
+                .engine()
+                .port
+                .store(settings::startup_engine_port(app.handle()), Ordering::SeqCst);
+            // Warm start: with autostart on (the default) the engine is
+            // already booting by the time the frontend mounts, which just
+            // waits for `sidecar-ready` (or gets AlreadyRunning from
+            // start_sidecar). Off the setup thread, since the orphan sweep
+            // before the spawn can block.
+            if settings::autostart_sidecar(app.handle())
+                && settings::dev_engine_url(app.handle()).is_none()
+            {
+                autostart_engine(app.handle().clone());
             }
             Ok(())
         })
         .on_window_event(|window, event| {
+            if window.label() == "main"
+                && matches!(
+                    event,
+                    tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_)
+                )
+            {
+                window_state::save_debounced(window);
+            }
+
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 // Only act on the main window.  The transcript overlay has its own
                 // lifecycle and should close freely without triggering app shutdown.
@@ -1683,11 +5199,12 @@ pub fn run() {
                     return;
                 }
 
-                let close_to_tray = window
-                    .app_handle()
-                    .try_state::<CloseToTray>()
-                    .map(|s| s.0.load(Ordering::Relaxed))
-                    .unwrap_or(true);
+                // Persist geometry before hiding/closing so the next show restores it.
+                window_state::save(window);
+
+                // Headless has no tray to hide to.
+                let close_to_tray =
+                    !headless() && settings::minimize_to_tray_on_close(window.app_handle());
 
                 if close_to_tray {
                     // Hide the window instead of closing — the Python sidecar keeps running
@@ -1706,6 +5223,8 @@ pub fn run() {
                     {
                         let _ = window.app_handle().set_activation_policy(tauri::ActivationPolicy::Accessory);
                     }
+
+                    notify_tray_hint_once(window.app_handle());
                 } else {
                     // User explicitly chose "Quit" — run graceful shutdown on a background
                     // thread so the main thread stays responsive to the macOS watchdog.
@@ -1722,7 +5241,6 @@ pub fn run() {
                     api.prevent_close();
 
                     let app_handle = window.app_handle().clone();
-                    let window_label = window.label().to_string();
 
                     // AppHandle is Send+Sync — use it to retrieve state inside the thread.
                     std::thread::spawn(move || {
@@ -1743,12 +5261,11 @@ pub fn run() {
                                 rec.as_deref(),
                             );
                         }
-                        // Cleanup done — close the window programmatically.
-                        // This fires CloseRequested again, but SHUTDOWN_DONE=true
-                        // causes it to be a no-op, allowing the close to proceed.
-                        if let Some(w) = app_handle.get_webview_window(&window_label) {
-                            let _ = w.close();
-                        }
+                        // Cleanup done — exit the app. Closing just this window
+                        // isn't enough: the tray icon (and the transcript overlay,
+                        // if open) would keep the process alive. ExitRequested sees
+                        // SHUTDOWN_DONE=true and lets the exit proceed.
+                        app_handle.exit(0);
                     });
                 }
             }
@@ -1828,6 +5345,11 @@ pub fn run() {
                     });
                 }
 
+                tauri::RunEvent::Exit => {
+                    shortcuts::unregister_all(app);
+                    engine_stream::cancel_all(app);
+                }
+
                 _ => {}
             }
         });
@@ -0,0 +1,139 @@
+//! lifecycle_log — a machine-readable record of what the supervisor did.
+//!
+//! Each sidecar lifecycle transition and supervisor decision is appended as
+//! one JSON object per line to `{app_log_dir}/lifecycle.jsonl`: `start`,
+//! `ready`, `stopping`, `stop`, `crash` (with the exit info), `restart` (a
+//! respawn scheduled after a crash), `hung` (the watchdog giving up on a
+//! process), `gave-up`, `circuit-open` and `restart-suppressed` (an exit
+//! left alone during a watchdog pause). Where engine.log is the engine's
+//! own output, this is the shell's side of the story — enough to replay a
+//! flaky startup after the fact.
+//!
+//! Once the file reaches MAX_BYTES it is moved to `lifecycle.jsonl.1`
+//! (replacing the previous one). get_lifecycle_history reads recent entries
+//! back from both.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{engine_log, LockRecover};
+
+const FILE_NAME: &str = "lifecycle.jsonl";
+
+/// Move the file aside once it is this big.
+const MAX_BYTES: u64 = 1024 * 1024;
+
+/// get_lifecycle_history's default `limit`.
+const DEFAULT_LIMIT: usize = 200;
+
+/// Serializes appends and the size check that precedes them.
+static WRITE: Mutex<()> = Mutex::new(());
+
+/// One line of `lifecycle.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleEntry {
+    /// Milliseconds since the Unix epoch.
+    pub ts: u64,
+    pub event: String,
+    /// The sidecar, e.g. ENGINE_SIDECAR.
+    pub name: String,
+    pub pid: Option<u32>,
+    pub port: u16,
+    /// How the process ended, on `stop` / `crash` once the exit was seen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit: Option<serde_json::Value>,
+    /// Free-form context, e.g. the restart delay or why it gave up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl LifecycleEntry {
+    /// An entry stamped now, without exit info or detail.
+    pub fn new(event: &str, name: &str, pid: Option<u32>, port: u16) -> Self {
+        Self {
+            ts: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            event: event.to_string(),
+            name: name.to_string(),
+            pid,
+            port,
+            exit: None,
+            detail: None,
+        }
+    }
+}
+
+/// Append `entry`. Best effort: a log that can't be written must not get in
+/// the way of the lifecycle it records.
+pub fn record(app: &AppHandle, entry: LifecycleEntry) {
+    let Some(dir) = log_dir(app) else {
+        return;
+    };
+    let Ok(mut line) = serde_json::to_string(&entry) else {
+        return;
+    };
+    line.push('\n');
+    let _guard = WRITE.lock_recover();
+    if let Err(e) = append(&dir, &line) {
+        log::error!(
+            "[lifecycle] Failed to write {}: {}",
+            dir.join(FILE_NAME).display(),
+            e
+        );
+    }
+}
+
+fn append(dir: &Path, line: &str) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(FILE_NAME);
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_BYTES) {
+        std::fs::rename(&path, archive_path(dir))?;
+    }
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?
+        .write_all(line.as_bytes())
+}
+
+/// The last `limit` entries (default 200), oldest first. Lines that don't
+/// parse are skipped.
+#[tauri::command]
+pub fn get_lifecycle_history(
+    app: AppHandle,
+    limit: Option<usize>,
+) -> Result<Vec<LifecycleEntry>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    let Some(dir) = log_dir(&app) else {
+        return Ok(Vec::new());
+    };
+    let mut text = String::new();
+    for path in [archive_path(&dir), dir.join(FILE_NAME)] {
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => text.push_str(&contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+    let entries: Vec<LifecycleEntry> = text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.into_iter().skip(skip).collect())
+}
+
+fn log_dir(app: &AppHandle) -> Option<PathBuf> {
+    let log = app.try_state::<engine_log::EngineLogState>()?;
+    Some(log.dir().to_path_buf())
+}
+
+fn archive_path(dir: &Path) -> PathBuf {
+    dir.join(format!("{}.1", FILE_NAME))
+}
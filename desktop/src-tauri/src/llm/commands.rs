@@ -56,7 +56,7 @@ pub async fn start_llm_server(
     context_length: Option<u32>,
     mmproj_filename: Option<String>,
 ) -> Result<LlmServerStatus, String> {
-    println!(
+    log::info!(
         "[llm-cmd] start_llm_server invoked: model={} gpu_layers={} ctx={:?}",
         model_filename, gpu_layers, context_length
     );
@@ -322,11 +322,11 @@ pub async fn download_llm_model(
     use crate::downloads::commands::DownloadManagerState;
 
     if urls.is_empty() {
-        eprintln!("[llm] download_llm_model ERROR: no URLs provided for '{}'", filename);
+        log::error!("[llm] download_llm_model ERROR: no URLs provided for '{}'", filename);
         return Err("No download URLs provided".to_string());
     }
 
-    eprintln!(
+    log::info!(
         "[llm] download_llm_model START: '{}' — {} part(s), HF token: {}",
         filename,
         urls.len(),
@@ -402,7 +402,7 @@ pub async fn download_llm_model(
         let dest_path = dest_dir.join(&filename);
         let expected_size = catalog_entry.map(|m| m.expected_size_bytes);
 
-        eprintln!(
+        log::info!(
             "[llm] download '{}': single-file, dest='{}', expected_size={:?}",
             filename,
             dest_path.display(),
@@ -411,7 +411,7 @@ pub async fn download_llm_model(
 
         // Skip if already fully downloaded
         if is_valid_gguf(&dest_path, expected_size) {
-            eprintln!("[llm] download '{}': already complete (valid GGUF on disk), skipping", filename);
+            log::info!("[llm] download '{}': already complete (valid GGUF on disk), skipping", filename);
             let _ = app.emit(
                 "llm-download-progress",
                 serde_json::json!({
@@ -437,15 +437,15 @@ pub async fn download_llm_model(
         let mut last_error = String::new();
         for attempt in 0..3u32 {
             if cancel_ref.load(Ordering::SeqCst) {
-                eprintln!("[llm] download '{}': cancelled by user before attempt {}", filename, attempt + 1);
+                log::info!("[llm] download '{}': cancelled by user before attempt {}", filename, attempt + 1);
                 return Err("Download cancelled by user".to_string());
             }
             if attempt > 0 {
                 let wait = 2u64.pow(attempt);
-                eprintln!("[llm] download '{}': retrying in {}s (attempt {}/3)", filename, wait, attempt + 1);
+                log::info!("[llm] download '{}': retrying in {}s (attempt {}/3)", filename, wait, attempt + 1);
                 tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
             } else {
-                eprintln!("[llm] download '{}': starting attempt 1/3 from '{}'", filename, url);
+                log::info!("[llm] download '{}': starting attempt 1/3 from '{}'", filename, url);
             }
             match try_download_part(
                 &client,
@@ -463,9 +463,9 @@ pub async fn download_llm_model(
             .await
             {
                 Ok(_) => {
-                    eprintln!("[llm] download '{}': attempt {} HTTP stream complete, validating…", filename, attempt + 1);
+                    log::info!("[llm] download '{}': attempt {} HTTP stream complete, validating…", filename, attempt + 1);
                     if is_valid_gguf(&dest_path, expected_size) {
-                        eprintln!("[llm] download '{}': SUCCESS — validation passed", filename);
+                        log::info!("[llm] download '{}': SUCCESS — validation passed", filename);
                         if let Some(ref dm) = dm_arc {
                             dm.mark_external_completed(&app, &dm_id, expected_size.unwrap_or(0)).await;
                         }
@@ -475,23 +475,23 @@ pub async fn download_llm_model(
                     last_error =
                         "Downloaded file failed validation (size mismatch or bad magic bytes)"
                             .to_string();
-                    eprintln!("[llm] download '{}': attempt {} validation FAILED — deleting partial file", filename, attempt + 1);
+                    log::error!("[llm] download '{}': attempt {} validation FAILED — deleting partial file", filename, attempt + 1);
                     let _ = tokio::fs::remove_file(&dest_path).await;
                 }
                 Err(e) if e.contains("cancelled") => {
-                    eprintln!("[llm] download '{}': cancelled during download", filename);
+                    log::info!("[llm] download '{}': cancelled during download", filename);
                     let _ = tokio::fs::remove_file(&dest_path).await;
                     return Err("Download cancelled by user".to_string());
                 }
                 Err(e) => {
                     last_error = format!("Attempt {}: {}", attempt + 1, e);
-                    eprintln!("[llm] download '{}': attempt {} FAILED — {}", filename, attempt + 1, e);
+                    log::error!("[llm] download '{}': attempt {} FAILED — {}", filename, attempt + 1, e);
                     let _ = tokio::fs::remove_file(&dest_path).await;
                 }
             }
         }
         let err_msg = format!("Download failed after 3 attempts. Last error: {}", last_error);
-        eprintln!("[llm] download '{}': FINAL FAILURE — {}", filename, err_msg);
+        log::error!("[llm] download '{}': FINAL FAILURE — {}", filename, err_msg);
         if let Some(ref dm) = dm_arc {
             dm.mark_external_failed(&app, &dm_id, &err_msg).await;
         }
@@ -502,7 +502,7 @@ pub async fn download_llm_model(
     // llama.cpp loads split GGUF files natively when given the first part path.
     // We must preserve the `-00001-of-N` filenames — DO NOT concatenate.
 
-    eprintln!("[llm] download '{}': split model — {} parts", filename, total_parts);
+    log::info!("[llm] download '{}': split model — {} parts", filename, total_parts);
 
     // Build per-part expected sizes from catalog (index 0 = first part)
     let part_expected_sizes: Vec<Option<u64>> = (0..total_parts)
@@ -547,14 +547,14 @@ pub async fn download_llm_model(
         let part_path = dest_dir.join(&part_filename);
         let expected = part_expected_sizes[i];
 
-        eprintln!(
+        log::info!(
             "[llm] download '{}': part {}/{} — file='{}', expected_size={:?}",
             filename, part_num, total_parts, part_filename, expected
         );
 
         // Skip if already fully downloaded
         if is_valid_gguf(&part_path, expected) {
-            eprintln!("[llm] download '{}': part {}/{} already complete, skipping", filename, part_num, total_parts);
+            log::info!("[llm] download '{}': part {}/{} already complete, skipping", filename, part_num, total_parts);
             let already = part_sizes[i];
             let overall = bytes_before_this_part + already;
             let pct = if grand_total > 0 {
@@ -590,16 +590,16 @@ pub async fn download_llm_model(
 
         for attempt in 0..3u32 {
             if cancel_ref.load(Ordering::SeqCst) {
-                eprintln!("[llm] download '{}': cancelled before part {}/{} attempt {}", filename, part_num, total_parts, attempt + 1);
+                log::info!("[llm] download '{}': cancelled before part {}/{} attempt {}", filename, part_num, total_parts, attempt + 1);
                 let _ = tokio::fs::remove_file(&part_path).await;
                 return Err("Download cancelled by user".to_string());
             }
             if attempt > 0 {
                 let wait = 2u64.pow(attempt);
-                eprintln!("[llm] download '{}': retrying part {}/{} in {}s (attempt {}/3)", filename, part_num, total_parts, wait, attempt + 1);
+                log::info!("[llm] download '{}': retrying part {}/{} in {}s (attempt {}/3)", filename, part_num, total_parts, wait, attempt + 1);
                 tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
             } else {
-                eprintln!("[llm] download '{}': starting part {}/{} attempt 1/3", filename, part_num, total_parts);
+                log::info!("[llm] download '{}': starting part {}/{} attempt 1/3", filename, part_num, total_parts);
             }
 
             match try_download_part(
@@ -618,25 +618,25 @@ pub async fn download_llm_model(
             .await
             {
                 Ok(_) => {
-                    eprintln!("[llm] download '{}': part {}/{} attempt {} HTTP stream complete, validating…", filename, part_num, total_parts, attempt + 1);
+                    log::info!("[llm] download '{}': part {}/{} attempt {} HTTP stream complete, validating…", filename, part_num, total_parts, attempt + 1);
                     if is_valid_gguf(&part_path, expected) {
-                        eprintln!("[llm] download '{}': part {}/{} SUCCESS", filename, part_num, total_parts);
+                        log::info!("[llm] download '{}': part {}/{} SUCCESS", filename, part_num, total_parts);
                         success = true;
                         break;
                     }
                     // is_valid_gguf already logged the specific reason
                     last_error = format!("Part {} failed validation after download", part_num);
-                    eprintln!("[llm] download '{}': part {}/{} attempt {} validation FAILED — deleting partial file", filename, part_num, total_parts, attempt + 1);
+                    log::error!("[llm] download '{}': part {}/{} attempt {} validation FAILED — deleting partial file", filename, part_num, total_parts, attempt + 1);
                     let _ = tokio::fs::remove_file(&part_path).await;
                 }
                 Err(e) if e.contains("cancelled") => {
-                    eprintln!("[llm] download '{}': cancelled during part {}/{}", filename, part_num, total_parts);
+                    log::info!("[llm] download '{}': cancelled during part {}/{}", filename, part_num, total_parts);
                     let _ = tokio::fs::remove_file(&part_path).await;
                     return Err("Download cancelled by user".to_string());
                 }
                 Err(e) => {
                     last_error = format!("Part {} attempt {}: {}", part_num, attempt + 1, e);
-                    eprintln!("[llm] download '{}': part {}/{} attempt {} FAILED — {}", filename, part_num, total_parts, attempt + 1, e);
+                    log::error!("[llm] download '{}': part {}/{} attempt {} FAILED — {}", filename, part_num, total_parts, attempt + 1, e);
                     let _ = tokio::fs::remove_file(&part_path).await;
                 }
             }
@@ -644,7 +644,7 @@ pub async fn download_llm_model(
 
         if !success {
             let err_msg = format!("Failed to download part {}/{}: {}", part_num, total_parts, last_error);
-            eprintln!("[llm] download '{}': FINAL FAILURE at part {}/{} — {}", filename, part_num, total_parts, err_msg);
+            log::error!("[llm] download '{}': FINAL FAILURE at part {}/{} — {}", filename, part_num, total_parts, err_msg);
             if let Some(ref dm) = dm_arc {
                 dm.mark_external_failed(&app, &dm_id, &err_msg).await;
             }
@@ -655,7 +655,7 @@ pub async fn download_llm_model(
     }
 
     // All parts downloaded — update DM state and return the first part path
-    eprintln!("[llm] download '{}': ALL {} part(s) complete — SUCCESS", filename, total_parts);
+    log::info!("[llm] download '{}': ALL {} part(s) complete — SUCCESS", filename, total_parts);
     if let Some(ref dm) = dm_arc {
         dm.mark_external_completed(&app, &dm_id, grand_total).await;
     }
@@ -973,7 +973,7 @@ pub async fn get_llm_setup_status(
         // This is the old concatenated file — remove it so the UI shows
         // "not downloaded" and offers to fetch the native split parts instead
         let _ = std::fs::remove_file(&old_assembled);
-        eprintln!("[llm] Removed legacy assembled Qwen2.5-14B file (incompatible with llama.cpp b8281+). Please re-download.");
+        log::info!("[llm] Removed legacy assembled Qwen2.5-14B file (incompatible with llama.cpp b8281+). Please re-download.");
     }
 
     let config = LlmConfig::load(&config_dir);
@@ -1026,7 +1026,7 @@ fn is_valid_gguf(path: &std::path::Path, expected_bytes: Option<u64>) -> bool {
 
     // Minimum sanity check — all real models are much larger than this
     if actual_size < 10_000_000 {
-        eprintln!(
+        log::error!(
             "[llm] is_valid_gguf FAIL (too small): {} — actual {} bytes < 10 MB minimum",
             path.display(),
             actual_size
@@ -1042,7 +1042,7 @@ fn is_valid_gguf(path: &std::path::Path, expected_bytes: Option<u64>) -> bool {
         if expected > 0 {
             let min_acceptable = (expected as f64 * 0.90) as u64;
             if actual_size < min_acceptable {
-                eprintln!(
+                log::error!(
                     "[llm] is_valid_gguf FAIL (size mismatch): {} — actual {} bytes is < 90% of expected {} bytes",
                     path.display(),
                     actual_size,
@@ -1053,7 +1053,7 @@ fn is_valid_gguf(path: &std::path::Path, expected_bytes: Option<u64>) -> bool {
             // Log divergence even when within tolerance so we can update catalog values
             let divergence_pct = ((actual_size as f64 - expected as f64) / expected as f64 * 100.0).abs();
             if divergence_pct > 2.0 {
-                eprintln!(
+                log::info!(
                     "[llm] is_valid_gguf WARNING (size divergence {:.1}%): {} — actual {} vs expected {} bytes (within tolerance, file accepted)",
                     divergence_pct,
                     path.display(),
@@ -1068,19 +1068,19 @@ fn is_valid_gguf(path: &std::path::Path, expected_bytes: Option<u64>) -> bool {
     let mut file = match std::fs::File::open(path) {
         Ok(f) => f,
         Err(e) => {
-            eprintln!("[llm] is_valid_gguf FAIL (cannot open): {} — {}", path.display(), e);
+            log::error!("[llm] is_valid_gguf FAIL (cannot open): {} — {}", path.display(), e);
             return false;
         }
     };
     let mut magic = [0u8; 4];
     use std::io::Read;
     if file.read_exact(&mut magic).is_err() {
-        eprintln!("[llm] is_valid_gguf FAIL (cannot read magic bytes): {}", path.display());
+        log::error!("[llm] is_valid_gguf FAIL (cannot read magic bytes): {}", path.display());
         return false;
     }
     let valid = magic == [0x47, 0x47, 0x55, 0x46];
     if !valid {
-        eprintln!(
+        log::error!(
             "[llm] is_valid_gguf FAIL (bad magic bytes): {} — got {:02X} {:02X} {:02X} {:02X}, expected 47 47 55 46",
             path.display(),
             magic[0], magic[1], magic[2], magic[3]
@@ -1147,13 +1147,13 @@ async fn try_download_part(
         req = req.header("Authorization", format!("Bearer {}", token));
     }
     let response = req.send().await.map_err(|e| {
-        eprintln!("[llm] try_download_part '{}' part {}/{}: connection error — {}", filename, part, total_parts, e);
+        log::error!("[llm] try_download_part '{}' part {}/{}: connection error — {}", filename, part, total_parts, e);
         e.to_string()
     })?;
 
     if !response.status().is_success() {
         let status = response.status();
-        eprintln!(
+        log::info!(
             "[llm] try_download_part '{}' part {}/{}: HTTP {} for {}",
             filename, part, total_parts, status, url
         );
@@ -1161,7 +1161,7 @@ async fn try_download_part(
     }
 
     let part_total = response.content_length().unwrap_or(0);
-    eprintln!(
+    log::info!(
         "[llm] try_download_part '{}' part {}/{}: HTTP 200, content-length={} bytes",
         filename, part, total_parts, part_total
     );
@@ -1192,19 +1192,19 @@ async fn try_download_part(
                     part,
                     total_parts
                 );
-                eprintln!("[llm] try_download_part '{}': {}", filename, msg);
+                log::info!("[llm] try_download_part '{}': {}", filename, msg);
                 return Err(msg);
             }
             Ok(None) => {
                 // Stream ended cleanly
-                eprintln!(
+                log::info!(
                     "[llm] try_download_part '{}' part {}/{}: stream complete, {} bytes written",
                     filename, part, total_parts, part_downloaded
                 );
                 break;
             }
             Ok(Some(Err(e))) => {
-                eprintln!("[llm] try_download_part '{}' part {}/{}: stream error — {}", filename, part, total_parts, e);
+                log::error!("[llm] try_download_part '{}' part {}/{}: stream error — {}", filename, part, total_parts, e);
                 return Err(format!("Stream error: {}", e));
             }
             Ok(Some(Ok(chunk))) => {
@@ -1,7 +1,18 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_shell::ShellExt;
 
+/// PID of the running llama-server, 0 when there is none. Kept outside
+/// LlmServerState so the quit path can find it without taking that lock.
+static SERVER_PID: AtomicU32 = AtomicU32::new(0);
+
+/// The llama-server this app started, if it is still running.
+pub fn running_pid() -> Option<u32> {
+    Some(SERVER_PID.load(Ordering::SeqCst)).filter(|&pid| pid != 0)
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct LlmServerStatus {
     pub running: bool,
@@ -174,6 +185,7 @@ impl LlmServer {
         }
 
         let captured = log_buf.lock().unwrap().clone();
+        SERVER_PID.store(child.pid(), Ordering::SeqCst);
         self.process = Some(child);
         self.status = LlmServerStatus {
             running: true,
@@ -190,6 +202,7 @@ impl LlmServer {
 
     pub async fn stop(&mut self) {
         if let Some(child) = self.process.take() {
+            SERVER_PID.store(0, Ordering::SeqCst);
             let _ = child.kill();
             tokio::time::sleep(std::time::Duration::from_millis(500)).await;
         }
@@ -203,6 +216,7 @@ impl LlmServer {
     /// Kills the child immediately without the 500 ms async sleep.
     pub fn stop_blocking(&mut self) {
         if let Some(child) = self.process.take() {
+            SERVER_PID.store(0, Ordering::SeqCst);
             let _ = child.kill();
         }
         self.status.running = false;
@@ -212,6 +226,7 @@ impl LlmServer {
 
     pub fn take_process(&mut self) -> Option<tauri_plugin_shell::process::CommandChild> {
         self.status.running = false;
+        SERVER_PID.store(0, Ordering::SeqCst);
         self.process.take()
     }
 
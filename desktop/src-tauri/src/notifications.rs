@@ -0,0 +1,85 @@
+//! notifications — desktop notifications with a tray fallback.
+//!
+//! Crash, recovery and update alerts all go through `notify`. When the OS
+//! has denied notification permission (macOS lets the user do this), the
+//! plugin's `show()` quietly does nothing, so instead the message is put on
+//! the tray: the status line gets the title and the tooltip gets the full
+//! text. The user still sees it the next time they look at the tray.
+//!
+//! Permission is checked once in setup and after every
+//! `request_notification_permission`. The result is mirrored into the
+//! `notifications-available` setting so the frontend can read it with
+//! get_setting and explain why alerts aren't appearing.
+//!
+//! In headless mode notifications are only logged.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+use crate::{settings, TrayHandle};
+
+/// False once the OS has denied permission. Optimistic until checked.
+static AVAILABLE: AtomicBool = AtomicBool::new(true);
+
+/// Read the current permission and record it. Called from setup.
+pub fn check_permission(app: &AppHandle) {
+    match app.notification().permission_state() {
+        Ok(state) => record(app, state),
+        Err(e) => log::error!("[notifications] Could not read permission state: {}", e),
+    }
+}
+
+/// False when the OS has denied notification permission.
+pub fn available() -> bool {
+    AVAILABLE.load(Ordering::SeqCst)
+}
+
+/// Show a notification, or put it on the tray if notifications are denied
+/// or fail to show.
+pub fn notify(app: &AppHandle, title: &str, body: &str) {
+    if crate::headless() {
+        log::info!("[notifications] {}: {}", title, body);
+        return;
+    }
+    if AVAILABLE.load(Ordering::SeqCst) {
+        match app.notification().builder().title(title).body(body).show() {
+            Ok(()) => return,
+            Err(e) => log::error!("[notifications] Could not show notification: {}", e),
+        }
+    }
+    crate::set_tray_status(app, title);
+    if let Some(tray) = app.try_state::<TrayHandle>() {
+        let _ = tray
+            .0
+            .set_tooltip(Some(format!("AI Matrx — {}: {}", title, body)));
+    }
+}
+
+/// Ask the OS for notification permission again (re-prompting where the OS
+/// allows it). Returns whether notifications are now available.
+#[tauri::command]
+pub async fn request_notification_permission(app: AppHandle) -> Result<bool, String> {
+    let state = app
+        .notification()
+        .request_permission()
+        .map_err(|e| format!("Failed to request notification permission: {}", e))?;
+    record(&app, state);
+    Ok(AVAILABLE.load(Ordering::SeqCst))
+}
+
+fn record(app: &AppHandle, state: PermissionState) {
+    // Prompt means "not asked yet": the first show() asks, so leave it on.
+    let available = !matches!(state, PermissionState::Denied);
+    AVAILABLE.store(available, Ordering::SeqCst);
+    if !available {
+        log::info!("[notifications] Permission denied — alerts go to the tray");
+    }
+    let key = settings::KEY_NOTIFICATIONS_AVAILABLE;
+    if settings::get(app, key).and_then(|v| v.as_bool()) != Some(available) {
+        if let Err(e) = settings::set(app, key, serde_json::json!(available)) {
+            log::warn!("[notifications] {}", e);
+        }
+    }
+}
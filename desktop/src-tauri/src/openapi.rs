@@ -0,0 +1,59 @@
+//! openapi — the engine's OpenAPI schema, for generating a typed client.
+//!
+//! The WebView can't fetch `{base_url}/openapi.json` itself: the request
+//! needs the engine token, which only the shell holds. `get_openapi_schema`
+//! fetches it through EngineHttp instead and caches it on the engine's
+//! SidecarProcess, keyed by pid like sidecar_version's cache and cleared
+//! at spawn and adopt — the schema only changes when the engine restarts
+//! (with other feature flags, say). An external dev engine is never
+//! cached, since it can be restarted behind our back.
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::engine_http::EngineHttp;
+use crate::{settings, LockRecover, SidecarError, SidecarState};
+
+/// The running engine's OpenAPI schema as JSON. Fails with `NotRunning`
+/// without an engine and `NotReady` while it is still starting.
+#[tauri::command]
+pub async fn get_openapi_schema(
+    app: AppHandle,
+    http: State<'_, EngineHttp>,
+) -> Result<serde_json::Value, SidecarError> {
+    crate::idle::resume_if_suspended(&app).await?;
+    let request = http
+        .request(&app, reqwest::Method::GET, &["openapi.json"])
+        .map_err(|e| match e {
+            SidecarError::HealthCheckFailed => SidecarError::NotReady,
+            e => e,
+        })?;
+    let sidecars = app.state::<SidecarState>();
+    let engine = sidecars.engine();
+    let pid = {
+        let child_pid = engine.child.lock_recover().as_ref().map(|c| c.pid());
+        let pid = child_pid.or_else(|| engine.adopted.lock_recover().and_then(|a| a.pid));
+        pid.filter(|_| settings::dev_engine_url(&app).is_none())
+    };
+    let cached = engine
+        .openapi_cache
+        .lock_recover()
+        .as_ref()
+        .filter(|(cached_pid, _)| pid == Some(*cached_pid))
+        .map(|(_, schema)| schema.clone());
+    if let Some(schema) = cached {
+        return Ok(schema);
+    }
+
+    let schema: serde_json::Value = request
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch the engine's OpenAPI schema: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected /openapi.json response: {}", e))?;
+    if let Some(pid) = pid {
+        *engine.openapi_cache.lock_recover() = Some((pid, schema.clone()));
+    }
+    Ok(schema)
+}
@@ -0,0 +1,79 @@
+//! panic_hook — leave a trace when the Rust side panics.
+//!
+//! `install` runs first thing in `run()` and chains onto the default hook
+//! (which still prints to stderr). Every panic is appended, with a forced
+//! backtrace, to `crash.log` next to engine.log, and a best-effort desktop
+//! notification goes out, since in a packaged app stderr goes nowhere. Both
+//! need what `attach` hands over from setup; earlier panics only reach stderr.
+//!
+//! The hook itself must not panic and must not take any lock app code might
+//! hold — the panicking thread may be holding it — so it writes with plain
+//! file I/O rather than through EngineLog.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+const CRASH_LOG_FILE_NAME: &str = "crash.log";
+
+/// Set by `attach`: the app, and the log dir crash.log goes in.
+static ATTACHED: OnceLock<(AppHandle, PathBuf)> = OnceLock::new();
+
+/// Install the hook. Panics before `attach` only reach the default hook.
+pub fn install() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        let Some((app, log_dir)) = ATTACHED.get() else {
+            return;
+        };
+
+        let thread = std::thread::current();
+        let report = format!(
+            "[{}] panic on thread '{}': {}\n{}\n",
+            unix_secs(),
+            thread.name().unwrap_or("<unnamed>"),
+            info,
+            std::backtrace::Backtrace::force_capture()
+        );
+        let path = crash_log_path(log_dir);
+        let written = std::fs::create_dir_all(log_dir)
+            .and_then(|_| {
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+            })
+            .and_then(|mut file| file.write_all(report.as_bytes()));
+        if let Err(e) = written {
+            eprintln!("[panic] Failed to write {}: {}", path.display(), e);
+        }
+
+        let _ = app
+            .notification()
+            .builder()
+            .title("AI Matrx hit an internal error")
+            .body("Details were saved to crash.log in the logs folder.")
+            .show();
+    }));
+}
+
+/// Let the hook notify through `app` and write into `log_dir` (the engine
+/// log dir, so crash.log sits next to engine.log).
+pub fn attach(app: &AppHandle, log_dir: &Path) {
+    let _ = ATTACHED.set((app.clone(), log_dir.to_path_buf()));
+}
+
+pub fn crash_log_path(log_dir: &Path) -> PathBuf {
+    log_dir.join(CRASH_LOG_FILE_NAME)
+}
+
+fn unix_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
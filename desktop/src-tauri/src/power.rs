@@ -0,0 +1,272 @@
+//! power — notice when the machine wakes from sleep, so the watchdog doesn't
+//! mistake the engine's post-sleep stupor for a hang.
+//!
+//! Sleep and wake come from the OS where it reports them:
+//!
+//!   Linux:   logind's PrepareForSleep signal on the system D-Bus.
+//!   macOS:   NSWorkspaceWillSleep / NSWorkspaceDidWake notifications.
+//!   Windows: the PBT_APMSUSPEND / PBT_APMRESUMEAUTOMATIC power broadcasts
+//!            (WM_POWERBROADCAST), delivered to a callback registered with
+//!            PowerRegisterSuspendResumeNotification, so no window is needed.
+//!
+//! Where none of those can be subscribed to (no logind, a sandbox), wake is
+//! detected from the wall clock instead: a thread sleeps WAKE_POLL_INTERVAL
+//! at a time, and if the wall clock moved much further than that, the
+//! machine was asleep in between. (Changing the system clock by hand looks
+//! the same; the only effect is one extra probe.)
+//!
+//! On wake the watchdog ignores failed pings for WAKE_GRACE, which gives the
+//! engine's sockets and timers time to settle. Then `/health` is probed once
+//! and `system-resumed` is emitted so the frontend can refresh its
+//! connection. A probe failure doesn't restart anything by itself: a dead
+//! process is the supervisor's to respawn, and a live but wedged one gets the
+//! watchdog's usual consecutive-failure treatment once the grace is over.
+
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{LockRecover, SidecarState};
+
+/// How often the wake detector checks the clock.
+const WAKE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Extra wall-clock time beyond WAKE_POLL_INTERVAL that counts as a sleep.
+const WAKE_THRESHOLD: Duration = Duration::from_secs(10);
+/// How long after a wake the watchdog ignores failed pings.
+const WAKE_GRACE: Duration = Duration::from_secs(15);
+/// Bound on the post-wake `/health` probe.
+const WAKE_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// When the last wake was detected.
+static LAST_WAKE: Mutex<Option<Instant>> = Mutex::new(None);
+/// When the OS last said it was going to sleep. Wall-clock time, because
+/// Instant stops while the machine sleeps on some platforms.
+static SLEPT_AT: Mutex<Option<SystemTime>> = Mutex::new(None);
+
+/// Payload of `system-resumed`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemResumed {
+    /// Roughly how long the machine was asleep; 0 if the OS reported the
+    /// wake without the sleep before it.
+    pub slept_secs: u64,
+    /// Whether the engine answered `/health` after the grace period; `None`
+    /// when it wasn't running.
+    pub engine_healthy: Option<bool>,
+}
+
+/// True within WAKE_GRACE of a detected wake. The watchdog doesn't count
+/// failures while this holds.
+pub fn in_wake_grace() -> bool {
+    LAST_WAKE
+        .lock_recover()
+        .is_some_and(|at| at.elapsed() < WAKE_GRACE)
+}
+
+/// Subscribe to the OS power events, or start the clock-based detector if
+/// that fails. Called once from setup.
+pub fn start(app: &AppHandle) {
+    if let Err(e) = watch_os_events(app) {
+        log::warn!(
+            "[power] No OS sleep/wake events ({}) — watching the clock instead",
+            e
+        );
+        start_clock_detector(app);
+    }
+}
+
+/// The OS says the machine is about to sleep.
+fn on_sleep() {
+    log::info!("[power] Going to sleep");
+    *SLEPT_AT.lock_recover() = Some(SystemTime::now());
+}
+
+/// The OS says the machine woke.
+fn on_resume(app: &AppHandle) {
+    let slept = SLEPT_AT
+        .lock_recover()
+        .take()
+        .and_then(|at| SystemTime::now().duration_since(at).ok())
+        .unwrap_or_default();
+    on_wake(app, slept);
+}
+
+#[cfg(target_os = "linux")]
+fn watch_os_events(app: &AppHandle) -> Result<(), String> {
+    let conn = zbus::blocking::Connection::system().map_err(|e| e.to_string())?;
+    let manager = zbus::blocking::Proxy::new(
+        &conn,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1",
+        "org.freedesktop.login1.Manager",
+    )
+    .map_err(|e| e.to_string())?;
+    let signals = manager
+        .receive_signal("PrepareForSleep")
+        .map_err(|e| e.to_string())?;
+    let app = app.clone();
+    std::thread::Builder::new()
+        .name("power-events".into())
+        .spawn(move || {
+            // `true` just before sleeping, `false` after waking.
+            for signal in signals {
+                match signal.body().deserialize::<bool>() {
+                    Ok(true) => on_sleep(),
+                    Ok(false) => on_resume(&app),
+                    Err(e) => log::warn!("[power] Unexpected PrepareForSleep signal: {}", e),
+                }
+            }
+            log::warn!("[power] logind connection closed — no more wake events");
+        })
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn watch_os_events(app: &AppHandle) -> Result<(), String> {
+    use std::ptr::NonNull;
+
+    use block2::RcBlock;
+    use objc2_app_kit::{
+        NSWorkspace, NSWorkspaceDidWakeNotification, NSWorkspaceWillSleepNotification,
+    };
+    use objc2_foundation::NSNotification;
+
+    let center = NSWorkspace::sharedWorkspace().notificationCenter();
+    let will_sleep = RcBlock::new(|_: NonNull<NSNotification>| on_sleep());
+    let app = app.clone();
+    let did_wake = RcBlock::new(move |_: NonNull<NSNotification>| on_resume(&app));
+    // SAFETY: the names are AppKit's notification constants, no object
+    // filter is given, and the blocks only touch Send + Sync state. With no
+    // queue they run on the posting (main) thread.
+    unsafe {
+        let observers = [
+            center.addObserverForName_object_queue_usingBlock(
+                Some(NSWorkspaceWillSleepNotification),
+                None,
+                None,
+                &will_sleep,
+            ),
+            center.addObserverForName_object_queue_usingBlock(
+                Some(NSWorkspaceDidWakeNotification),
+                None,
+                None,
+                &did_wake,
+            ),
+        ];
+        // Observing for the life of the app.
+        std::mem::forget(observers);
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn watch_os_events(app: &AppHandle) -> Result<(), String> {
+    use std::ffi::c_void;
+
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Power::{
+        PowerRegisterSuspendResumeNotification, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND,
+    };
+
+    unsafe extern "system" fn callback(context: *const c_void, kind: u32, _: *const c_void) -> u32 {
+        // SAFETY: `context` is the AppHandle leaked below.
+        let app = unsafe { &*(context as *const AppHandle) };
+        match kind {
+            PBT_APMSUSPEND => on_sleep(),
+            PBT_APMRESUMEAUTOMATIC => on_resume(app),
+            _ => {}
+        }
+        ERROR_SUCCESS
+    }
+
+    // Both live for the rest of the process, as the subscription does.
+    let context = Box::into_raw(Box::new(app.clone()));
+    let params = Box::leak(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+        Callback: Some(callback),
+        Context: context as *mut c_void,
+    }));
+    let mut registration = std::ptr::null_mut();
+    // SAFETY: with DEVICE_NOTIFY_CALLBACK the recipient is a pointer to the
+    // subscribe parameters, which outlive the registration.
+    let rc = unsafe {
+        PowerRegisterSuspendResumeNotification(
+            DEVICE_NOTIFY_CALLBACK,
+            params as *mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS as *mut c_void,
+            &mut registration,
+        )
+    };
+    if rc != ERROR_SUCCESS {
+        return Err(std::io::Error::from_raw_os_error(rc as i32).to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn watch_os_events(_app: &AppHandle) -> Result<(), String> {
+    Err("not supported on this platform".into())
+}
+
+/// The fallback: a thread that notices jumps in the wall clock.
+fn start_clock_detector(app: &AppHandle) {
+    let app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("wake-detector".into())
+        .spawn(move || {
+            let mut last = SystemTime::now();
+            loop {
+                std::thread::sleep(WAKE_POLL_INTERVAL);
+                let now = SystemTime::now();
+                // Err means the clock went backwards — not a sleep.
+                if let Ok(gap) = now.duration_since(last) {
+                    if gap > WAKE_POLL_INTERVAL + WAKE_THRESHOLD {
+                        on_wake(&app, gap - WAKE_POLL_INTERVAL);
+                    }
+                }
+                last = now;
+            }
+        });
+    if let Err(e) = spawned {
+        log::error!("[power] Could not start the wake detector: {}", e);
+    }
+}
+
+fn on_wake(app: &AppHandle, slept: Duration) {
+    log::info!(
+        "[power] Woke from sleep (~{}s) — holding the watchdog for {}s",
+        slept.as_secs(),
+        WAKE_GRACE.as_secs()
+    );
+    *LAST_WAKE.lock_recover() = Some(Instant::now());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(WAKE_GRACE).await;
+        let sidecars = app.state::<SidecarState>();
+        let engine = sidecars.engine();
+        let running = crate::external_engine_url(&app, engine).is_some()
+            || (engine.child.lock_recover().is_some() && engine.ready.load(Ordering::SeqCst));
+        let engine_healthy = if running {
+            let ok =
+                crate::ping_sidecar_health(&app, crate::ENGINE_SIDECAR, WAKE_PROBE_TIMEOUT, true)
+                    .await;
+            if !ok {
+                log::warn!("[power] Engine not answering after wake — leaving it to the watchdog");
+            }
+            Some(ok)
+        } else {
+            None
+        };
+        let _ = app.emit(
+            "system-resumed",
+            SystemResumed {
+                slept_secs: slept.as_secs(),
+                engine_healthy,
+            },
+        );
+    });
+}
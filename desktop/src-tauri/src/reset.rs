@@ -0,0 +1,146 @@
+//! reset — "factory reset" of the desktop app's own data.
+//!
+//! `reset_app_data` wipes the settings store and the contents of the app's
+//! config / data / local-data / cache directories so the next launch behaves
+//! like a fresh install. The engine's own data (~/.matrx) is left alone — it
+//! belongs to the engine, not the shell.
+//!
+//! It refuses to touch anything until the engine is confirmed stopped: a live
+//! engine may be writing into these directories, and deleting files out from
+//! under it corrupts more than it resets. Engine logs are kept by default so
+//! a reset done while debugging doesn't destroy the evidence.
+//!
+//! Other in-process components (download manager DB, LLM state) still hold
+//! handles into the deleted directories, so the frontend should restart the
+//! app (restartApp() → `restart_for_update`) once it receives the
+//! `app-data-reset` event.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{engine_log, settings, SidecarState};
+
+/// Result of a reset, also emitted as the `app-data-reset` event.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResetReport {
+    /// Files and directories that were deleted.
+    pub removed: Vec<String>,
+    /// Entries that could not be deleted (e.g. locked on Windows).
+    pub errors: Vec<String>,
+    pub kept_logs: bool,
+}
+
+/// Stop the engine, then delete all app settings and data.
+///
+/// `keep_logs` defaults to true. Fails without deleting anything if the
+/// engine (or an orphaned engine on our port) can't be stopped.
+#[tauri::command]
+pub async fn reset_app_data(
+    app: AppHandle,
+    keep_logs: Option<bool>,
+) -> Result<ResetReport, String> {
+    let keep_logs = keep_logs.unwrap_or(true);
+
+    if !crate::stop_sidecar_process(&app, crate::ENGINE_SIDECAR).await? {
+        return Err("Engine did not stop — nothing was reset".to_string());
+    }
+    let port = app
+        .state::<SidecarState>()
+        .engine()
+        .port
+        .load(Ordering::SeqCst);
+    let orphan_stopped =
+        tauri::async_runtime::spawn_blocking(move || match crate::orphaned_engine_on_port(port) {
+            Some(pid) => crate::terminate_orphaned_engine(pid, crate::SIDECAR_STOP_TIMEOUT),
+            None => true,
+        })
+        .await
+        .map_err(|e| format!("Failed to check for a running engine: {}", e))?;
+    if !orphan_stopped {
+        return Err(format!(
+            "An engine process is still running on port {} — nothing was reset",
+            port
+        ));
+    }
+
+    settings::clear(&app)?;
+
+    let mut report = ResetReport {
+        kept_logs: keep_logs,
+        ..Default::default()
+    };
+    let log_dir = app.try_state::<engine_log::EngineLogState>().map(|log| {
+        if !keep_logs {
+            log.close();
+        }
+        log.dir().to_path_buf()
+    });
+    let keep = log_dir.as_deref().filter(|_| keep_logs);
+
+    let path = app.path();
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for dir in [
+        path.app_config_dir(),
+        path.app_data_dir(),
+        path.app_local_data_dir(),
+        path.app_cache_dir(),
+    ]
+    .into_iter()
+    .flatten()
+    .chain(log_dir.clone().filter(|_| !keep_logs))
+    {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    for dir in &dirs {
+        wipe_dir(dir, keep, &mut report);
+    }
+
+    log::info!(
+        "[reset] Removed {} entries ({} errors, logs {})",
+        report.removed.len(),
+        report.errors.len(),
+        if keep_logs { "kept" } else { "deleted" }
+    );
+    let _ = app.emit("app-data-reset", &report);
+    Ok(report)
+}
+
+/// Delete everything inside `dir` except `keep`. Directories on the way to
+/// `keep` are descended into rather than removed. Missing dirs are skipped
+/// (one may sit inside another that was already wiped).
+fn wipe_dir(dir: &Path, keep: Option<&Path>, report: &mut ResetReport) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => {
+            report.errors.push(format!("{}: {}", dir.display(), e));
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if keep == Some(path.as_path()) {
+            continue;
+        }
+        // Symlinks are removed, never followed.
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        if is_dir && keep.is_some_and(|k| k.starts_with(&path)) {
+            wipe_dir(&path, keep, report);
+            continue;
+        }
+        let result = if is_dir {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        match result {
+            Ok(()) => report.removed.push(path.display().to_string()),
+            Err(e) => report.errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! resource_limits — cap the engine's CPU and memory on shared machines.
+//!
+//! The `resource-limits` setting is passed to each engine spawn as
+//! `MATRX_CPU_CORES` / `MATRX_MEMORY_MB`, and the engine applies them to
+//! itself first thing in run.py, before it starts a thread or a worker
+//! (which inherit them). The shell plugin starts the binary itself, so
+//! there is no pre-exec hook or wrapper; setting them from outside on the
+//! new pid raced the PyInstaller bootloader, whose forked child is the real
+//! engine and could start its workers first. A limit the engine can't apply
+//! is reported on its stderr (`[limits] ...`), which lands in the engine log.
+//!
+//!   Linux:   `cpu_cores` pins the engine to the first N cores
+//!            (sched_setaffinity), `memory_mb` caps its virtual address
+//!            space (RLIMIT_AS).
+//!   macOS:   best effort — `cpu_cores` lowers the engine's priority (nice)
+//!            rather than capping it; `memory_mb` isn't enforced.
+//!   Windows: the engine puts itself in a job object — `cpu_cores` as its
+//!            affinity (first N cores), `memory_mb` as the job's committed
+//!            memory limit, which its worker processes share.
+//!
+//! On Linux, RLIMIT_AS limits address space, not memory in use: Python, its native
+//! libraries and model runtimes reserve far more address space than they
+//! touch, so a `memory_mb` near the engine's real footprint makes
+//! allocations fail long before it runs out of RAM. Set it well above the
+//! expected use; actually capping resident memory would need a cgroup.
+//!
+//! set_resource_limits reports the limits the platform can't apply instead
+//! of ignoring them. Like feature flags, limits only take effect on the
+//! next start, so a change on a running engine emits `restart-required`.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::features::RestartRequired;
+use crate::settings::{self, ResourceLimits};
+use crate::{LockRecover, SidecarState};
+
+/// Below this the engine can't even import its dependencies.
+const MIN_MEMORY_MB: u64 = 256;
+
+const CPU_CORES_ENV: &str = "MATRX_CPU_CORES";
+const MEMORY_MB_ENV: &str = "MATRX_MEMORY_MB";
+
+/// Limits the running engine was started with; `None` before the first spawn.
+static APPLIED: Mutex<Option<ResourceLimits>> = Mutex::new(None);
+
+/// The saved limits.
+#[tauri::command]
+pub fn get_resource_limits(app: AppHandle) -> ResourceLimits {
+    settings::resource_limits(&app)
+}
+
+/// Save new limits for the engine; `null` for a field removes that limit.
+/// Returns the names of the limits this platform can't enforce (empty when
+/// all of them apply). If the engine is running, emits `restart-required`
+/// (`required: false` when it already runs with these limits).
+#[tauri::command]
+pub fn set_resource_limits(
+    app: AppHandle,
+    limits: ResourceLimits,
+) -> Result<Vec<&'static str>, String> {
+    if let Some(cores) = limits.cpu_cores {
+        let available = std::thread::available_parallelism().map_or(1, |n| n.get() as u32);
+        if cores == 0 || cores > available {
+            return Err(format!(
+                "cpu_cores must be between 1 and {} (the cores on this machine)",
+                available
+            ));
+        }
+    }
+    if limits.memory_mb.is_some_and(|mb| mb < MIN_MEMORY_MB) {
+        return Err(format!("memory_mb must be at least {}", MIN_MEMORY_MB));
+    }
+    let value = serde_json::to_value(limits)
+        .map_err(|e| format!("Failed to serialize resource limits: {}", e))?;
+    settings::set(&app, settings::KEY_RESOURCE_LIMITS, value)?;
+    log::info!("[limits] Engine resource limits: {:?}", limits);
+
+    let sidecars = app.state::<SidecarState>();
+    let running = sidecars.engine().child.lock_recover().is_some();
+    let applied = *APPLIED.lock_recover();
+    if let (true, Some(applied)) = (running, applied) {
+        let _ = app.emit(
+            "restart-required",
+            RestartRequired {
+                reason: "resource-limits",
+                required: applied != limits,
+            },
+        );
+    }
+    Ok(unsupported(&limits))
+}
+
+/// The set limits this platform has no way to enforce.
+fn unsupported(limits: &ResourceLimits) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    if limits.cpu_cores.is_some() && !cfg!(any(target_os = "linux", target_os = "macos", windows)) {
+        names.push("cpu_cores");
+    }
+    if limits.memory_mb.is_some() && !cfg!(any(target_os = "linux", windows)) {
+        names.push("memory_mb");
+    }
+    names
+}
+
+/// The saved limits as environment for the engine spawn, which applies
+/// them to itself (see the module doc). Called by spawn_sidecar for the
+/// engine, which also records them as the running engine's limits.
+pub fn spawn_env(app: &AppHandle) -> Vec<(&'static str, String)> {
+    let limits = settings::resource_limits(app);
+    *APPLIED.lock_recover() = Some(limits);
+    for name in unsupported(&limits) {
+        log::error!(
+            "[limits] {} can't be enforced on this platform — ignored",
+            name
+        );
+    }
+    let mut env = Vec::new();
+    if let Some(cores) = limits.cpu_cores {
+        env.push((CPU_CORES_ENV, cores.to_string()));
+    }
+    if let Some(mb) = limits.memory_mb {
+        env.push((MEMORY_MB_ENV, mb.to_string()));
+    }
+    env
+}
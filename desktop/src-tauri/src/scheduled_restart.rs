@@ -0,0 +1,135 @@
+//! scheduled_restart — restart the engine a little later.
+//!
+//! After applying an update the UI may want the engine restarted only once
+//! a download has had time to finish. schedule_restart arms a one-shot timer
+//! that then restarts through restart_sidecar_process — the same graceful,
+//! then forced, path as restart_sidecar — and returns a handle for
+//! cancel_scheduled_restart.
+//!
+//! At most one restart is pending: arming a new one replaces the previous
+//! timer, which is reported as cancelled with reason "replaced". Both
+//! transitions are emitted, as `restart-scheduled` and `restart-cancelled`.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+
+use crate::{LockRecover, ENGINE_SIDECAR};
+
+/// The next handle schedule_restart gives out.
+static NEXT_HANDLE: AtomicU64 = AtomicU64::new(1);
+
+/// The pending restart: its handle and timer task.
+static PENDING: Mutex<Option<(u64, JoinHandle<()>)>> = Mutex::new(None);
+
+/// The longest delay schedule_restart accepts: one hour.
+const MAX_DELAY_MS: u64 = 60 * 60 * 1000;
+
+/// Payload of `restart-scheduled`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RestartScheduled {
+    pub handle: u64,
+    pub delay_ms: u64,
+    /// When the restart is due, in milliseconds since the Unix epoch.
+    pub due_at: u64,
+}
+
+/// Payload of `restart-cancelled`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RestartCancelled {
+    pub handle: u64,
+    /// "cancelled" by cancel_scheduled_restart, or "replaced" by a newer
+    /// schedule_restart.
+    pub reason: &'static str,
+}
+
+/// Restart the engine gracefully after `delay_ms`, replacing any restart
+/// already scheduled. Returns the handle to cancel it with.
+#[tauri::command]
+pub fn schedule_restart(app: AppHandle, delay_ms: u64) -> Result<u64, String> {
+    if delay_ms > MAX_DELAY_MS {
+        return Err(format!(
+            "A restart can be scheduled at most {}ms ahead",
+            MAX_DELAY_MS
+        ));
+    }
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::SeqCst);
+    let task_app = app.clone();
+    // Held until the new timer is stored, so even a zero delay finds it.
+    let mut pending = PENDING.lock_recover();
+    let task = tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+        {
+            let mut pending = PENDING.lock_recover();
+            if !pending.as_ref().is_some_and(|(h, _)| *h == handle) {
+                return;
+            }
+            *pending = None;
+        }
+        log::info!("[sidecar] Running scheduled restart {}", handle);
+        if let Err(e) = crate::restart_sidecar_process(&task_app, ENGINE_SIDECAR).await {
+            log::error!("[sidecar] Scheduled restart {} failed: {}", handle, e);
+        }
+    });
+
+    let replaced = pending.replace((handle, task));
+    drop(pending);
+    if let Some((old, old_task)) = replaced {
+        old_task.abort();
+        let _ = app.emit(
+            "restart-cancelled",
+            RestartCancelled {
+                handle: old,
+                reason: "replaced",
+            },
+        );
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    log::info!(
+        "[sidecar] Engine restart {} scheduled in {}ms",
+        handle,
+        delay_ms
+    );
+    let _ = app.emit(
+        "restart-scheduled",
+        RestartScheduled {
+            handle,
+            delay_ms,
+            due_at: now + delay_ms,
+        },
+    );
+    Ok(handle)
+}
+
+/// Cancel the pending restart. With `handle`, only if that is the one
+/// pending. Returns whether a restart was cancelled.
+#[tauri::command]
+pub fn cancel_scheduled_restart(app: AppHandle, handle: Option<u64>) -> Result<bool, String> {
+    let cancelled = {
+        let mut pending = PENDING.lock_recover();
+        if handle.is_some_and(|h| !pending.as_ref().is_some_and(|(p, _)| *p == h)) {
+            return Ok(false);
+        }
+        pending.take()
+    };
+    let Some((handle, task)) = cancelled else {
+        return Ok(false);
+    };
+    task.abort();
+    log::info!("[sidecar] Scheduled engine restart {} cancelled", handle);
+    let _ = app.emit(
+        "restart-cancelled",
+        RestartCancelled {
+            handle,
+            reason: "cancelled",
+        },
+    );
+    Ok(true)
+}
@@ -0,0 +1,198 @@
+//! self_check — catch broken environments at launch, not at first use.
+//!
+//! `run_checks` looks at the things that otherwise fail silently or much
+//! later: the engine binary being installed, the data folders being
+//! writable, the preferred port being free, notification permission, and
+//! the engine's version matching the UI's.
+//! Setup runs it once and, if a critical check failed, shows the main window
+//! even on a start-hidden login launch so the frontend can show the results.
+//! The frontend can re-run it any time with `run_self_check`; every run emits
+//! `self-check-complete` with the results.
+//!
+//! The version check needs a running engine, so at launch it only reports
+//! "not checked yet"; version_compat runs it when the engine comes up, and
+//! later self-checks report that result.
+//!
+//! Only a missing binary, an unwritable folder or an incompatible engine is
+//! critical. A taken port
+//! just means the engine falls back to another one, and denied notifications
+//! go to the tray.
+
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    data_dir, notifications, settings, version_compat, LockRecover, SidecarError, SidecarState,
+};
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfCheckResult {
+    /// "sidecar-binary", "data-dir", "port", "notifications" or
+    /// "engine-version".
+    pub check: &'static str,
+    pub ok: bool,
+    /// A failure here keeps the app from working, not just degrades it.
+    pub critical: bool,
+    pub detail: String,
+}
+
+/// Run every check and emit `self-check-complete`.
+pub fn run_checks(app: &AppHandle) -> Vec<SelfCheckResult> {
+    let results = vec![
+        check_binary(app),
+        check_data_dirs(app),
+        check_port(app),
+        check_notifications(),
+        check_engine_version(),
+    ];
+    for result in results.iter().filter(|r| !r.ok) {
+        log::error!("[self-check] {} failed: {}", result.check, result.detail);
+    }
+    let _ = app.emit("self-check-complete", &results);
+    results
+}
+
+/// True if any check that matters failed.
+pub fn has_critical_failure(results: &[SelfCheckResult]) -> bool {
+    results.iter().any(|r| r.critical && !r.ok)
+}
+
+/// Re-run the launch checks, e.g. for a diagnostics screen.
+#[tauri::command]
+pub fn run_self_check(app: AppHandle) -> Vec<SelfCheckResult> {
+    run_checks(&app)
+}
+
+fn check_binary(app: &AppHandle) -> SelfCheckResult {
+    let sidecars = app.state::<SidecarState>();
+    let engine = sidecars.engine();
+    let (ok, detail) = if let Some(url) = crate::external_engine_url(app, engine) {
+        (
+            true,
+            format!("Not needed — using the external engine at {}", url),
+        )
+    } else {
+        match installed_binary(engine.spec.binary) {
+            Ok(detail) => (true, detail),
+            Err(SidecarError::BinaryMissing { expected_path }) => (
+                false,
+                format!("Engine not found at {} — reinstall the app", expected_path),
+            ),
+            Err(e) => (false, e.to_string()),
+        }
+    };
+    SelfCheckResult {
+        check: "sidecar-binary",
+        ok,
+        critical: true,
+        detail,
+    }
+}
+
+fn installed_binary(binary: &str) -> Result<String, SidecarError> {
+    #[cfg(target_os = "macos")]
+    if let Some(path) = crate::macos_helper_engine_path() {
+        return Ok(format!("Found {}", path.display()));
+    }
+    crate::check_sidecar_binary(binary).map(|_| "Found next to the app".to_string())
+}
+
+/// The engine's data folder (configured, else the app's own) and ~/.matrx,
+/// which the engine always writes its discovery file and database into.
+fn check_data_dirs(app: &AppHandle) -> SelfCheckResult {
+    let path = app.path();
+    let dirs: Vec<PathBuf> = data_dir::configured(app)
+        .or_else(|| path.app_data_dir().ok())
+        .into_iter()
+        .chain(path.home_dir().ok().map(|home| home.join(".matrx")))
+        .collect();
+    let failure = dirs.iter().find_map(|dir| {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Could not create {}: {}", dir.display(), e))
+            .and_then(|_| data_dir::probe_writable(dir))
+            .err()
+    });
+    let detail = failure.clone().unwrap_or_else(|| {
+        let dirs: Vec<String> = dirs.iter().map(|d| d.display().to_string()).collect();
+        format!("Writable: {}", dirs.join(", "))
+    });
+    SelfCheckResult {
+        check: "data-dir",
+        ok: failure.is_none(),
+        critical: true,
+        detail,
+    }
+}
+
+fn check_port(app: &AppHandle) -> SelfCheckResult {
+    let port = settings::preferred_port(app);
+    let sidecars = app.state::<SidecarState>();
+    let engine = sidecars.engine();
+    let ours = engine.child.lock_recover().is_some() && engine.port.load(Ordering::SeqCst) == port;
+    let (ok, detail) = if ours {
+        (true, format!("Port {} is in use by the engine", port))
+    } else if std::net::TcpListener::bind(("127.0.0.1", port)).is_ok() {
+        (true, format!("Port {} is free", port))
+    } else {
+        (
+            false,
+            format!(
+                "Port {} is taken by another program — the engine will use another port",
+                port
+            ),
+        )
+    };
+    SelfCheckResult {
+        check: "port",
+        ok,
+        critical: false,
+        detail,
+    }
+}
+
+fn check_notifications() -> SelfCheckResult {
+    let ok = notifications::available();
+    SelfCheckResult {
+        check: "notifications",
+        ok,
+        critical: false,
+        detail: if ok {
+            "Allowed".to_string()
+        } else {
+            "Denied by the OS — alerts appear on the tray instead".to_string()
+        },
+    }
+}
+
+fn check_engine_version() -> SelfCheckResult {
+    let (ok, detail) = match version_compat::last() {
+        Some(result) if result.compatible => (
+            true,
+            format!(
+                "Engine {} works with app {}",
+                result.engine_version, result.app_version
+            ),
+        ),
+        Some(result) => (
+            false,
+            format!(
+                "Engine {} doesn't work with app {} (needs {}) — reinstall the app",
+                result.engine_version, result.app_version, result.required_range
+            ),
+        ),
+        None => (
+            true,
+            "Not checked yet — runs when the engine starts".to_string(),
+        ),
+    };
+    SelfCheckResult {
+        check: "engine-version",
+        ok,
+        critical: true,
+        detail,
+    }
+}
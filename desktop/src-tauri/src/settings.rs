@@ -0,0 +1,532 @@
+//! settings — native-side app settings backed by tauri-plugin-store.
+//!
+//! Everything lives in `settings.json` in the app data dir (the same store
+//! window_state uses). The frontend reads and writes arbitrary keys through
+//! the `get_setting` / `set_setting` commands; Rust code that needs a value
+//! during startup — before any webview exists — goes through the typed
+//! accessors below, which fall back to a default when the key is missing or
+//! holds the wrong type.
+//!
+//! Every write is saved to disk immediately so a crash or force-quit never
+//! loses a change. `set_setting` rejects malformed values for the few keys
+//! that have rules (see validate).
+//!
+//! All store access goes through `store`, which survives a corrupted file
+//! (a partial write, a full disk): the first access each session checks that
+//! the file parses, and one that doesn't is renamed to
+//! `settings.json.corrupt-{unix secs}` for inspection and replaced with an
+//! empty store, so every setting falls back to its default instead of
+//! breaking startup. The user is notified that their settings were reset.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_store::{Store, StoreExt};
+use tokio::sync::Notify;
+
+pub const STORE_FILE: &str = "settings.json";
+
+/// Port the engine sidecar should try to bind first (u16).
+pub const KEY_PREFERRED_PORT: &str = "preferred-port";
+/// Port the engine last served on, plus the preferred port at the time
+/// (LastEnginePort). Written by the app, not the settings UI.
+pub const KEY_LAST_ENGINE_PORT: &str = "last-engine-port";
+/// Spawn the engine in the background during setup instead of waiting for
+/// the frontend to call start_sidecar (bool, default true).
+pub const KEY_AUTOSTART_SIDECAR: &str = "autostart-sidecar-on-launch";
+/// Hide the main window to the tray on close instead of quitting (bool).
+pub const KEY_MINIMIZE_TO_TRAY: &str = "minimize-to-tray-on-close";
+/// Mirrors the OS launch-at-login registration (bool). See autostart.rs.
+pub const KEY_START_AT_LOGIN: &str = "start-at-login";
+/// Start hidden in the tray when launched at login (bool).
+pub const KEY_START_HIDDEN_AT_LOGIN: &str = "start-hidden-at-login";
+/// Show a splash window instead of the main one while the autostarted
+/// engine boots (bool, default false). See splash.rs.
+pub const KEY_SHOW_SPLASH: &str = "show-splash";
+/// Show a desktop notification when the engine crashes and recovers (bool).
+pub const KEY_NOTIFY_ON_CRASH: &str = "notify-on-crash";
+/// Accelerator for the show/hide-main-window hotkey, e.g. "CmdOrCtrl+Shift+M".
+pub const KEY_GLOBAL_SHORTCUT: &str = "global-shortcut";
+/// Accelerator for the quit hotkey, e.g. "CmdOrCtrl+Shift+Q".
+pub const KEY_QUIT_SHORTCUT: &str = "quit-shortcut";
+/// What a left-click on the tray icon does: "toggle" (hide the window if
+/// it's visible, show it if not) or "show" (always show and focus).
+pub const KEY_TRAY_CLICK_ACTION: &str = "tray-click-action";
+/// Release manifest check_for_updates reads instead of the tauri.conf.json
+/// endpoint, e.g. a beta channel (string URL).
+pub const KEY_UPDATE_MANIFEST_URL: &str = "update-manifest-url";
+/// Development only: use an engine the developer runs by hand instead of
+/// spawning the bundled sidecar. A base URL string on this machine
+/// (`http://127.0.0.1:PORT` or `http://localhost:PORT`), or `true` for the
+/// default port. Overridden by the MATRX_DEV_ENGINE_URL env var.
+pub const KEY_DEV_EXTERNAL_ENGINE: &str = "dev-external-engine";
+/// Set once the "still running in the tray" hint has been shown after the
+/// first close-to-tray (bool). Written by the app, not the settings UI.
+pub const KEY_SEEN_TRAY_HINT: &str = "seen-tray-hint";
+/// Whether the OS allows desktop notifications (bool), as last checked by
+/// notifications.rs. Written by the app, read by the frontend.
+pub const KEY_NOTIFICATIONS_AVAILABLE: &str = "notifications-available";
+/// Optional engine features to pass as `--enable NAME` on spawn (array of
+/// names from features::KNOWN_FEATURES).
+pub const KEY_FEATURE_FLAGS: &str = "feature-flags";
+/// Engine config file (TOML/YAML/JSON) passed as `--config PATH` on spawn
+/// (string path). Set through set_engine_config_file, which validates it.
+pub const KEY_ENGINE_CONFIG_FILE: &str = "engine-config-file";
+/// Suspend the engine after this many minutes without a tool call (u64,
+/// default 0 = never). See idle.rs.
+pub const KEY_IDLE_SUSPEND_MINUTES: &str = "idle-suspend-minutes";
+/// Path the readiness poll and the watchdog ping, e.g. "/healthz" (string
+/// starting with "/", default "/health").
+pub const KEY_HEALTH_ENDPOINT: &str = "health-endpoint";
+/// How often the watchdog pings a running engine (u64 ms, default 10000,
+/// at least MIN_HEALTH_INTERVAL_MS). Picked up by running watchdogs at once.
+pub const KEY_HEALTH_INTERVAL_MS: &str = "health-interval-ms";
+/// Temp data dir of a running start_sidecar_ephemeral engine (string path).
+/// Written by ephemeral.rs so a dir a crash left behind goes on next launch.
+pub const KEY_EPHEMERAL_DATA_DIR: &str = "ephemeral-data-dir";
+/// Crashes within `restart-circuit-window-secs` that open the supervisor's
+/// circuit breaker, stopping automatic restarts (u64, default 5, at least 1).
+pub const KEY_RESTART_CIRCUIT_MAX_FAILURES: &str = "restart-circuit-max-failures";
+/// The breaker's rolling window (u64 seconds, default 60, at least 1).
+pub const KEY_RESTART_CIRCUIT_WINDOW_SECS: &str = "restart-circuit-window-secs";
+/// CPU / memory caps for the engine, applied at spawn (ResourceLimits). Set
+/// through set_resource_limits, which validates them.
+pub const KEY_RESOURCE_LIMITS: &str = "resource-limits";
+/// Let the engine listen on every interface so other devices on the LAN
+/// can reach it (bool, default false). Set through set_lan_access, which
+/// requires a confirmation. See lan_access.rs.
+pub const KEY_LAN_ACCESS: &str = "lan-access";
+/// Clients that can't hold the engine token but may still call the engine:
+/// any of EXTERNAL_CLIENTS (array, default all three). Passed to the engine
+/// as MATRX_EXTERNAL_CLIENTS on spawn; see app/api/auth.py.
+pub const KEY_EXTERNAL_CLIENTS: &str = "external-clients";
+/// Warn when the data folder's disk has less than this much free space
+/// (u64 MiB, default 1024; 0 turns the warning off). See disk_space.rs.
+pub const KEY_LOW_DISK_THRESHOLD_MB: &str = "low-disk-threshold-mb";
+/// Recent engine cold-start times and the version they were measured on
+/// (StartupBaseline). Written by the app, not the settings UI.
+pub const KEY_STARTUP_BASELINE: &str = "startup-baseline";
+/// Extra environment / CLI args for the engine sidecar (SidecarConfig).
+pub const KEY_SIDECAR_CONFIG: &str = "sidecar-config";
+
+pub const DEFAULT_HEALTH_ENDPOINT: &str = "/health";
+pub const DEFAULT_HEALTH_INTERVAL_MS: u64 = 10_000;
+/// Anything faster is the tight loop the setting exists to avoid.
+pub const MIN_HEALTH_INTERVAL_MS: u64 = 1_000;
+
+pub const DEFAULT_LOW_DISK_THRESHOLD_MB: u64 = 1024;
+
+/// The web app, the browser extension and Cloudflare tunnel clients.
+pub const EXTERNAL_CLIENTS: &[&str] = &["web", "extension", "tunnel"];
+
+pub const DEFAULT_RESTART_CIRCUIT_MAX_FAILURES: u64 = 5;
+pub const DEFAULT_RESTART_CIRCUIT_WINDOW_SECS: u64 = 60;
+
+/// Woken when `health-interval-ms` changes, so watchdogs mid-sleep restart
+/// their timer with the new interval.
+pub static HEALTH_INTERVAL_CHANGED: Notify = Notify::const_new();
+
+/// Per-install engine launch configuration, applied by spawn_sidecar().
+///
+/// Read at spawn time, so edits only take effect on the next (re)start.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SidecarConfig {
+    /// Extra environment variables, e.g. `MATRX_DATA_DIR`.
+    pub env: BTreeMap<String, String>,
+    /// Extra CLI args appended after `--port`, e.g. `["--log-level", "debug"]`.
+    pub args: Vec<String>,
+}
+
+/// Caps on the engine process; `None` leaves that resource unlimited. How
+/// each is enforced depends on the platform — see resource_limits.rs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceLimits {
+    /// Most CPU cores the engine may run on.
+    pub cpu_cores: Option<u32>,
+    /// Memory cap for the engine, in MiB: virtual address space on Linux
+    /// (not resident memory, so allow well above its real use), committed
+    /// memory shared with its workers on Windows.
+    pub memory_mb: Option<u64>,
+}
+
+/// The port a healthy engine last served on. `preferred` records the
+/// preferred-port setting in effect then, so changing that setting
+/// invalidates the remembered port.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LastEnginePort {
+    pub port: u16,
+    pub preferred: u16,
+}
+
+/// The engine's recent spawn-to-ready times, newest last, for spotting
+/// startup regressions (see startup_timing.rs). Only samples from
+/// `engine_version` are kept.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartupBaseline {
+    pub engine_version: String,
+    pub samples_ms: Vec<u64>,
+}
+
+/// Set once this session's first store access has checked the file.
+static STORE_CHECKED: AtomicBool = AtomicBool::new(false);
+
+/// The settings store. The first call each session replaces a corrupt file
+/// (see recover_corrupt_store); if the store still fails to open, the file
+/// is checked again before giving up, in case it was damaged since.
+pub fn store(app: &AppHandle) -> Result<Arc<Store<Wry>>, String> {
+    if !STORE_CHECKED.swap(true, Ordering::SeqCst) {
+        recover_corrupt_store(app);
+    }
+    match app.store(STORE_FILE) {
+        Ok(store) => Ok(store),
+        Err(_) if recover_corrupt_store(app) => app
+            .store(STORE_FILE)
+            .map_err(|e| format!("Failed to open {} after resetting it: {}", STORE_FILE, e)),
+        Err(e) => Err(format!("Failed to open {}: {}", STORE_FILE, e)),
+    }
+}
+
+/// If the store file exists but isn't a JSON object, move it aside, write
+/// an empty store in its place and tell the user. Returns whether it did.
+/// A file that can't be read at all is left alone — that isn't corruption.
+fn recover_corrupt_store(app: &AppHandle) -> bool {
+    let Ok(path) = app.path().resolve(STORE_FILE, BaseDirectory::AppData) else {
+        return false;
+    };
+    let Ok(bytes) = std::fs::read(&path) else {
+        return false;
+    };
+    let Err(parse_error) = serde_json::from_slice::<serde_json::Map<String, Value>>(&bytes) else {
+        return false;
+    };
+
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let backup = PathBuf::from(format!("{}.corrupt-{}", path.display(), secs));
+    log::error!(
+        "[settings] {} is corrupt ({}) — moving it to {} and starting from defaults",
+        path.display(),
+        parse_error,
+        backup.display()
+    );
+    if let Err(e) = std::fs::rename(&path, &backup) {
+        log::error!("[settings] Could not move the corrupt store aside: {}", e);
+    }
+    if let Err(e) = std::fs::write(&path, "{}") {
+        log::error!("[settings] Could not write a fresh {}: {}", STORE_FILE, e);
+    }
+    crate::notifications::notify(
+        app,
+        "AI Matrx settings were reset",
+        &format!(
+            "The settings file was damaged, so defaults are in use. The old file was kept as {}.",
+            backup.display()
+        ),
+    );
+    true
+}
+
+/// Read a raw value from the store. `None` if unset or the store can't load.
+pub fn get(app: &AppHandle, key: &str) -> Option<Value> {
+    store(app).ok()?.get(key)
+}
+
+/// Write a value and flush the store to disk.
+pub fn set(app: &AppHandle, key: &str, value: Value) -> Result<(), String> {
+    let store = store(app)?;
+    store.set(key, value);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save {}: {}", STORE_FILE, e))
+}
+
+/// Remove every key and flush the now-empty store to disk.
+pub fn clear(app: &AppHandle) -> Result<(), String> {
+    let store = store(app)?;
+    store.clear();
+    store
+        .save()
+        .map_err(|e| format!("Failed to save {}: {}", STORE_FILE, e))
+}
+
+pub fn preferred_port(app: &AppHandle) -> u16 {
+    get(app, KEY_PREFERRED_PORT)
+        .and_then(|v| v.as_u64())
+        .and_then(|p| u16::try_from(p).ok())
+        .filter(|p| *p != 0)
+        .unwrap_or(crate::DEFAULT_ENGINE_PORT)
+}
+
+/// Port the first engine spawn of a launch should try: the port it last
+/// served on, so cached client configs keep working across launches, unless
+/// the user has changed preferred-port since. pick_sidecar_port() still falls
+/// back to a free port if this one is taken.
+pub fn startup_engine_port(app: &AppHandle) -> u16 {
+    let preferred = preferred_port(app);
+    get(app, KEY_LAST_ENGINE_PORT)
+        .and_then(|v| serde_json::from_value::<LastEnginePort>(v).ok())
+        .filter(|last| last.preferred == preferred && last.port != 0)
+        .map_or(preferred, |last| last.port)
+}
+
+/// Remember `port` as the one the engine is serving on. Skips the disk write
+/// when nothing changed, which is the common case.
+pub fn remember_engine_port(app: &AppHandle, port: u16) -> Result<(), String> {
+    let last = LastEnginePort {
+        port,
+        preferred: preferred_port(app),
+    };
+    let current = get(app, KEY_LAST_ENGINE_PORT)
+        .and_then(|v| serde_json::from_value::<LastEnginePort>(v).ok());
+    if current == Some(last) {
+        return Ok(());
+    }
+    let value = serde_json::to_value(last)
+        .map_err(|e| format!("Failed to serialize {}: {}", KEY_LAST_ENGINE_PORT, e))?;
+    set(app, KEY_LAST_ENGINE_PORT, value)
+}
+
+pub fn autostart_sidecar(app: &AppHandle) -> bool {
+    get(app, KEY_AUTOSTART_SIDECAR)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
+pub fn minimize_to_tray_on_close(app: &AppHandle) -> bool {
+    get(app, KEY_MINIMIZE_TO_TRAY)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
+pub fn start_hidden_at_login(app: &AppHandle) -> bool {
+    get(app, KEY_START_HIDDEN_AT_LOGIN)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
+pub fn show_splash(app: &AppHandle) -> bool {
+    get(app, KEY_SHOW_SPLASH)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+pub fn notify_on_crash(app: &AppHandle) -> bool {
+    get(app, KEY_NOTIFY_ON_CRASH)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
+pub fn global_shortcut(app: &AppHandle) -> String {
+    get(app, KEY_GLOBAL_SHORTCUT)
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| crate::shortcuts::DEFAULT_TOGGLE_SHORTCUT.to_string())
+}
+
+pub fn quit_shortcut(app: &AppHandle) -> String {
+    get(app, KEY_QUIT_SHORTCUT)
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| crate::shortcuts::DEFAULT_QUIT_SHORTCUT.to_string())
+}
+
+/// True unless `tray-click-action` is "show".
+pub fn tray_click_toggles(app: &AppHandle) -> bool {
+    get(app, KEY_TRAY_CLICK_ACTION).and_then(|v| v.as_str().map(str::to_string))
+        != Some("show".to_string())
+}
+
+pub fn update_manifest_url(app: &AppHandle) -> Option<String> {
+    get(app, KEY_UPDATE_MANIFEST_URL)
+        .and_then(|v| v.as_str().map(str::trim).map(str::to_string))
+        .filter(|url| !url.is_empty())
+}
+
+/// Base URL of the externally run dev engine, if that mode is on.
+pub fn dev_engine_url(app: &AppHandle) -> Option<String> {
+    if let Some(url) = std::env::var("MATRX_DEV_ENGINE_URL")
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+    {
+        return Some(url.trim().to_string());
+    }
+    match get(app, KEY_DEV_EXTERNAL_ENGINE)? {
+        Value::String(url) if !url.trim().is_empty() => Some(url.trim().to_string()),
+        Value::Bool(true) => Some(format!("http://127.0.0.1:{}", crate::DEFAULT_ENGINE_PORT)),
+        _ => None,
+    }
+}
+
+pub fn seen_tray_hint(app: &AppHandle) -> bool {
+    get(app, KEY_SEEN_TRAY_HINT)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+pub fn feature_flags(app: &AppHandle) -> Vec<String> {
+    get(app, KEY_FEATURE_FLAGS)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn engine_config_file(app: &AppHandle) -> Option<String> {
+    get(app, KEY_ENGINE_CONFIG_FILE)
+        .and_then(|v| v.as_str().map(str::trim).map(str::to_string))
+        .filter(|path| !path.is_empty())
+}
+
+pub fn ephemeral_data_dir(app: &AppHandle) -> Option<String> {
+    get(app, KEY_EPHEMERAL_DATA_DIR)
+        .and_then(|v| v.as_str().map(str::to_string))
+        .filter(|path| !path.is_empty())
+}
+
+pub fn idle_suspend_minutes(app: &AppHandle) -> u64 {
+    get(app, KEY_IDLE_SUSPEND_MINUTES)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)
+}
+
+pub fn health_endpoint(app: &AppHandle) -> String {
+    get(app, KEY_HEALTH_ENDPOINT)
+        .and_then(|v| v.as_str().map(str::trim).map(str::to_string))
+        .filter(|path| path.starts_with('/'))
+        .unwrap_or_else(|| DEFAULT_HEALTH_ENDPOINT.to_string())
+}
+
+pub fn health_interval(app: &AppHandle) -> Duration {
+    let ms = get(app, KEY_HEALTH_INTERVAL_MS)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_HEALTH_INTERVAL_MS);
+    Duration::from_millis(ms.max(MIN_HEALTH_INTERVAL_MS))
+}
+
+pub fn restart_circuit_max_failures(app: &AppHandle) -> usize {
+    get(app, KEY_RESTART_CIRCUIT_MAX_FAILURES)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_RESTART_CIRCUIT_MAX_FAILURES)
+        .max(1) as usize
+}
+
+pub fn restart_circuit_window(app: &AppHandle) -> Duration {
+    let secs = get(app, KEY_RESTART_CIRCUIT_WINDOW_SECS)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_RESTART_CIRCUIT_WINDOW_SECS);
+    Duration::from_secs(secs.max(1))
+}
+
+pub fn resource_limits(app: &AppHandle) -> ResourceLimits {
+    get(app, KEY_RESOURCE_LIMITS)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn lan_access(app: &AppHandle) -> bool {
+    get(app, KEY_LAN_ACCESS)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+pub fn external_clients(app: &AppHandle) -> Vec<String> {
+    get(app, KEY_EXTERNAL_CLIENTS)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_else(|| EXTERNAL_CLIENTS.iter().map(|c| c.to_string()).collect())
+}
+
+/// `None` when the low-disk warning is off.
+pub fn low_disk_threshold_bytes(app: &AppHandle) -> Option<u64> {
+    let mb = get(app, KEY_LOW_DISK_THRESHOLD_MB)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_LOW_DISK_THRESHOLD_MB);
+    (mb > 0).then(|| mb * 1024 * 1024)
+}
+
+pub fn startup_baseline(app: &AppHandle) -> StartupBaseline {
+    get(app, KEY_STARTUP_BASELINE)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn sidecar_config(app: &AppHandle) -> SidecarConfig {
+    get(app, KEY_SIDECAR_CONFIG)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Read one setting. Returns `null` when the key has never been set.
+#[tauri::command]
+pub fn get_setting(app: AppHandle, key: String) -> Result<Option<Value>, String> {
+    Ok(get(&app, &key))
+}
+
+/// Write one setting and persist it immediately. `lan-access` is refused:
+/// it goes through set_lan_access, which asks for confirmation and checks
+/// that the engine enforces its token.
+#[tauri::command]
+pub fn set_setting(app: AppHandle, key: String, value: Value) -> Result<(), String> {
+    if key == KEY_LAN_ACCESS {
+        return Err(format!("{} can only be changed with set_lan_access", key));
+    }
+    validate(&key, &value)?;
+    set(&app, &key, value)?;
+    if key == KEY_HEALTH_INTERVAL_MS {
+        HEALTH_INTERVAL_CHANGED.notify_waiters();
+    }
+    Ok(())
+}
+
+/// Reject values the typed accessors would silently replace with the
+/// default. `null` is always allowed and means "use the default".
+pub fn validate(key: &str, value: &Value) -> Result<(), String> {
+    if value.is_null() {
+        return Ok(());
+    }
+    match key {
+        KEY_HEALTH_ENDPOINT => match value.as_str() {
+            Some(path) if path.trim().starts_with('/') => Ok(()),
+            _ => Err(format!("{} must be a path starting with '/'", key)),
+        },
+        KEY_HEALTH_INTERVAL_MS => match value.as_u64() {
+            Some(ms) if ms >= MIN_HEALTH_INTERVAL_MS => Ok(()),
+            _ => Err(format!(
+                "{} must be a whole number of milliseconds, at least {}",
+                key, MIN_HEALTH_INTERVAL_MS
+            )),
+        },
+        KEY_RESTART_CIRCUIT_MAX_FAILURES | KEY_RESTART_CIRCUIT_WINDOW_SECS => {
+            match value.as_u64() {
+                Some(n) if n >= 1 => Ok(()),
+                _ => Err(format!("{} must be a whole number, at least 1", key)),
+            }
+        }
+        KEY_LOW_DISK_THRESHOLD_MB => match value.as_u64() {
+            Some(_) => Ok(()),
+            None => Err(format!("{} must be a whole number of MiB", key)),
+        },
+        KEY_EXTERNAL_CLIENTS => match value.as_array() {
+            Some(clients)
+                if clients
+                    .iter()
+                    .all(|c| c.as_str().is_some_and(|c| EXTERNAL_CLIENTS.contains(&c))) =>
+            {
+                Ok(())
+            }
+            _ => Err(format!(
+                "{} must be a list of {}",
+                key,
+                EXTERNAL_CLIENTS.join(", ")
+            )),
+        },
+        _ => Ok(()),
+    }
+}
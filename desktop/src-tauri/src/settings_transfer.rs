@@ -0,0 +1,240 @@
+//! settings_transfer — carry settings to another machine.
+//!
+//! export_settings writes the portable settings as a JSON document, with
+//! secret-looking values (API keys in `sidecar-config` env, say) replaced
+//! by diagnostics' `[redacted]` marker. import_settings reads one back:
+//! only keys in PORTABLE_KEYS are applied, each checked like set_setting
+//! would, and anything else is reported as ignored. Keys that only make
+//! sense on the machine that wrote them — remembered ports, window
+//! geometry, the start-at-login mirror, file paths — are not portable, and
+//! neither is `lan-access`, which must go through set_lan_access's
+//! confirmation.
+//!
+//! A value that still carries the redacted marker is skipped rather than
+//! written over the real secret. Settings that shape how the engine is
+//! spawned take effect on its next start, so importing one while it runs
+//! emits `restart-required`; shortcuts are registered at launch.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::diagnostics::{redact, REDACTED};
+use crate::features::{RestartRequired, KNOWN_FEATURES};
+use crate::settings::{self, ResourceLimits, SidecarConfig};
+use crate::{LockRecover, SidecarState};
+
+/// `format` of an exported document.
+const FORMAT: &str = "matrx-settings";
+const FORMAT_VERSION: u64 = 1;
+
+/// Settings that travel between machines, and whether each changes how the
+/// engine is spawned.
+const PORTABLE_KEYS: &[(&str, bool)] = &[
+    (settings::KEY_PREFERRED_PORT, true),
+    (settings::KEY_AUTOSTART_SIDECAR, false),
+    (settings::KEY_MINIMIZE_TO_TRAY, false),
+    (settings::KEY_START_HIDDEN_AT_LOGIN, false),
+    (settings::KEY_SHOW_SPLASH, false),
+    (settings::KEY_NOTIFY_ON_CRASH, false),
+    (settings::KEY_GLOBAL_SHORTCUT, false),
+    (settings::KEY_QUIT_SHORTCUT, false),
+    (settings::KEY_TRAY_CLICK_ACTION, false),
+    (settings::KEY_UPDATE_MANIFEST_URL, false),
+    (settings::KEY_FEATURE_FLAGS, true),
+    (settings::KEY_IDLE_SUSPEND_MINUTES, false),
+    (settings::KEY_HEALTH_ENDPOINT, false),
+    (settings::KEY_HEALTH_INTERVAL_MS, false),
+    (settings::KEY_RESTART_CIRCUIT_MAX_FAILURES, false),
+    (settings::KEY_RESTART_CIRCUIT_WINDOW_SECS, false),
+    (settings::KEY_RESOURCE_LIMITS, true),
+    (settings::KEY_LOW_DISK_THRESHOLD_MB, false),
+    (settings::KEY_SIDECAR_CONFIG, true),
+];
+
+/// What import_settings did; also the payload of `settings-imported`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsImport {
+    /// Keys written — or, when replacing, reset because the import lacked them.
+    pub imported: Vec<String>,
+    /// Keys that aren't portable settings, left untouched.
+    pub ignored: Vec<String>,
+    /// Keys skipped because their value was exported redacted.
+    pub redacted: Vec<String>,
+    pub merge: bool,
+}
+
+/// The portable settings as a pretty-printed JSON document for
+/// import_settings, with secrets redacted. Unset keys are left out.
+#[tauri::command]
+pub fn export_settings(app: AppHandle) -> Result<String, String> {
+    let mut exported = Map::new();
+    for (key, _) in PORTABLE_KEYS {
+        if let Some(mut value) = settings::get(&app, key).filter(|v| !v.is_null()) {
+            redact(key, &mut value);
+            exported.insert(key.to_string(), value);
+        }
+    }
+    let document = json!({
+        "format": FORMAT,
+        "version": FORMAT_VERSION,
+        "settings": exported,
+    });
+    serde_json::to_string_pretty(&document)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))
+}
+
+/// Apply a document from export_settings (or a bare key → value object).
+/// With `merge`, keys the import lacks keep their current values; without
+/// it, portable keys the import lacks go back to their defaults. Unknown
+/// keys are ignored and listed; an invalid value fails the whole import
+/// before anything is written.
+#[tauri::command]
+pub fn import_settings(
+    app: AppHandle,
+    json: String,
+    merge: bool,
+) -> Result<SettingsImport, String> {
+    let document: Value =
+        serde_json::from_str(&json).map_err(|e| format!("Not a settings document: {}", e))?;
+    let incoming = match &document {
+        Value::Object(map) if map.get("format").and_then(Value::as_str) == Some(FORMAT) => {
+            let version = map.get("version").and_then(Value::as_u64).unwrap_or(0);
+            if version > FORMAT_VERSION {
+                return Err(format!(
+                    "These settings come from a newer app (format version {})",
+                    version
+                ));
+            }
+            map.get("settings")
+                .and_then(Value::as_object)
+                .ok_or_else(|| "The settings document has no \"settings\" object".to_string())?
+        }
+        Value::Object(map) => map,
+        _ => return Err("Expected a JSON object of settings".to_string()),
+    };
+
+    let mut changes: Vec<(&'static str, Value)> = Vec::new();
+    let mut ignored = Vec::new();
+    let mut redacted = Vec::new();
+    for (key, value) in incoming {
+        let Some((known, _)) = PORTABLE_KEYS.iter().find(|(k, _)| *k == key.as_str()) else {
+            ignored.push(key.clone());
+            continue;
+        };
+        if contains_redacted(value) {
+            redacted.push(key.clone());
+            continue;
+        }
+        check_value(known, value)?;
+        changes.push((*known, value.clone()));
+    }
+    if !merge {
+        for (key, _) in PORTABLE_KEYS {
+            let kept = incoming.contains_key(*key);
+            if !kept && settings::get(&app, key).is_some_and(|v| !v.is_null()) {
+                changes.push((*key, Value::Null));
+            }
+        }
+    }
+
+    let store = settings::store(&app)?;
+    let mut engine_affected = false;
+    let mut imported = Vec::new();
+    for (key, value) in changes {
+        if store.get(key).as_ref() == Some(&value) {
+            continue;
+        }
+        engine_affected |= PORTABLE_KEYS.iter().any(|(k, engine)| *k == key && *engine);
+        store.set(key, value);
+        imported.push(key.to_string());
+    }
+    store
+        .save()
+        .map_err(|e| format!("Failed to save {}: {}", settings::STORE_FILE, e))?;
+    log::info!(
+        "[settings] Imported {} setting(s); ignored {:?}, skipped redacted {:?}",
+        imported.len(),
+        ignored,
+        redacted
+    );
+
+    if imported
+        .iter()
+        .any(|k| k == settings::KEY_HEALTH_INTERVAL_MS)
+    {
+        settings::HEALTH_INTERVAL_CHANGED.notify_waiters();
+    }
+    let result = SettingsImport {
+        imported,
+        ignored,
+        redacted,
+        merge,
+    };
+    let _ = app.emit("settings-imported", &result);
+    let running = app
+        .state::<SidecarState>()
+        .engine()
+        .child
+        .lock_recover()
+        .is_some();
+    if engine_affected && running {
+        let _ = app.emit(
+            "restart-required",
+            RestartRequired {
+                reason: "settings-import",
+                required: true,
+            },
+        );
+    }
+    Ok(result)
+}
+
+/// Reject a value the setting's accessor couldn't use: the shape each key
+/// needs, then settings::validate's rules.
+fn check_value(key: &str, value: &Value) -> Result<(), String> {
+    if value.is_null() {
+        return Ok(());
+    }
+    let shaped = match key {
+        settings::KEY_PREFERRED_PORT => value
+            .as_u64()
+            .is_some_and(|p| (1024..=u16::MAX as u64).contains(&p)),
+        settings::KEY_AUTOSTART_SIDECAR
+        | settings::KEY_MINIMIZE_TO_TRAY
+        | settings::KEY_START_HIDDEN_AT_LOGIN
+        | settings::KEY_SHOW_SPLASH
+        | settings::KEY_NOTIFY_ON_CRASH => value.is_boolean(),
+        settings::KEY_TRAY_CLICK_ACTION => matches!(value.as_str(), Some("toggle" | "show")),
+        settings::KEY_IDLE_SUSPEND_MINUTES
+        | settings::KEY_HEALTH_INTERVAL_MS
+        | settings::KEY_RESTART_CIRCUIT_MAX_FAILURES
+        | settings::KEY_RESTART_CIRCUIT_WINDOW_SECS
+        | settings::KEY_LOW_DISK_THRESHOLD_MB => value.is_u64(),
+        settings::KEY_FEATURE_FLAGS => value.as_array().is_some_and(|flags| {
+            flags
+                .iter()
+                .all(|f| f.as_str().is_some_and(|f| KNOWN_FEATURES.contains(&f)))
+        }),
+        settings::KEY_RESOURCE_LIMITS => {
+            serde_json::from_value::<ResourceLimits>(value.clone()).is_ok()
+        }
+        settings::KEY_SIDECAR_CONFIG => {
+            serde_json::from_value::<SidecarConfig>(value.clone()).is_ok()
+        }
+        _ => value.is_string(),
+    };
+    if !shaped {
+        return Err(format!("Invalid value for {}: {}", key, value));
+    }
+    settings::validate(key, value)
+}
+
+fn contains_redacted(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.contains(REDACTED),
+        Value::Array(items) => items.iter().any(contains_redacted),
+        Value::Object(map) => map.values().any(contains_redacted),
+        _ => false,
+    }
+}
@@ -0,0 +1,225 @@
+//! shortcuts — system-wide hotkeys: show/hide the main window, and quit.
+//!
+//! Both accelerators default to the constants below and are persisted in the
+//! settings store (`global-shortcut`, `quit-shortcut`), so a user-chosen
+//! combo survives restarts. `set_global_shortcut` / `set_quit_shortcut` swap
+//! them at runtime.
+//!
+//! The quit hotkey is for when close-to-tray has hidden the window and the
+//! tray isn't there to click (Linux tray support is patchy). It takes the
+//! same path as the tray's Quit item, so it never goes through the window's
+//! close-to-tray handling. Everything is unregistered on exit.
+//!
+//! In headless mode nothing is registered; the setters only save the setting.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::{settings, LockRecover};
+
+pub const DEFAULT_TOGGLE_SHORTCUT: &str = "CmdOrCtrl+Shift+M";
+pub const DEFAULT_QUIT_SHORTCUT: &str = "CmdOrCtrl+Shift+Q";
+
+/// The currently registered shortcuts, where registration succeeded.
+#[derive(Default)]
+struct Registered {
+    toggle: Mutex<Option<Shortcut>>,
+    quit: Mutex<Option<Shortcut>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Action {
+    Toggle,
+    Quit,
+}
+
+impl Action {
+    fn other(self) -> Self {
+        match self {
+            Action::Toggle => Action::Quit,
+            Action::Quit => Action::Toggle,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Action::Toggle => "toggle the main window",
+            Action::Quit => "quit the app",
+        }
+    }
+
+    fn setting(self) -> &'static str {
+        match self {
+            Action::Toggle => settings::KEY_GLOBAL_SHORTCUT,
+            Action::Quit => settings::KEY_QUIT_SHORTCUT,
+        }
+    }
+
+    fn saved(self, app: &AppHandle) -> String {
+        match self {
+            Action::Toggle => settings::global_shortcut(app),
+            Action::Quit => settings::quit_shortcut(app),
+        }
+    }
+
+    fn slot(self, registered: &Registered) -> &Mutex<Option<Shortcut>> {
+        match self {
+            Action::Toggle => &registered.toggle,
+            Action::Quit => &registered.quit,
+        }
+    }
+}
+
+fn parse(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))
+}
+
+fn current(app: &AppHandle, action: Action) -> Option<Shortcut> {
+    let state = app.try_state::<Registered>()?;
+    let guard = action.slot(&state).lock_recover();
+    *guard
+}
+
+fn store(app: &AppHandle, action: Action, shortcut: Option<Shortcut>) {
+    *action.slot(&app.state::<Registered>()).lock_recover() = shortcut;
+}
+
+/// Register the saved (or default) accelerators. Called once from
+/// `.setup()`. One failing doesn't stop the other; the error lists both.
+pub fn register_saved(app: &AppHandle) -> Result<(), String> {
+    app.manage(Registered::default());
+    if crate::headless() {
+        return Ok(());
+    }
+
+    let mut errors = Vec::new();
+    for action in [Action::Toggle, Action::Quit] {
+        let accelerator = action.saved(app);
+        let registered = parse(&accelerator).and_then(|shortcut| {
+            app.global_shortcut()
+                .register(shortcut)
+                .map_err(|e| registration_error(&accelerator, e))?;
+            store(app, action, Some(shortcut));
+            Ok(())
+        });
+        match registered {
+            Ok(()) => log::info!(
+                "[shortcut] Registered {} to {}",
+                accelerator,
+                action.describe()
+            ),
+            Err(e) => errors.push(e),
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// Drop every hotkey so none outlives the app. Called on RunEvent::Exit.
+pub fn unregister_all(app: &AppHandle) {
+    if let Err(e) = app.global_shortcut().unregister_all() {
+        log::error!("[shortcut] Failed to unregister shortcuts: {}", e);
+    }
+}
+
+/// Global-shortcut plugin handler.
+pub fn handle(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    if current(app, Action::Toggle).as_ref() == Some(shortcut) {
+        // show/hide touch the activation policy on macOS — main thread only.
+        let handle = app.clone();
+        let _ = app.run_on_main_thread(move || toggle_main_window(&handle));
+    } else if current(app, Action::Quit).as_ref() == Some(shortcut) {
+        log::info!("[shortcut] Quit shortcut pressed");
+        crate::quit_app(app);
+    }
+}
+
+/// Hide the window if it's in front; otherwise bring it up. A visible window
+/// buried behind others counts as "not in front", so the hotkey raises it
+/// rather than hiding something the user can't see.
+fn toggle_main_window(app: &AppHandle) {
+    let in_front = app.get_webview_window("main").is_some_and(|w| {
+        w.is_visible().unwrap_or(false)
+            && !w.is_minimized().unwrap_or(false)
+            && w.is_focused().unwrap_or(false)
+    });
+    if in_front {
+        crate::hide_main_window(app);
+    } else {
+        crate::show_main_window(app);
+    }
+}
+
+fn registration_error(accelerator: &str, e: tauri_plugin_global_shortcut::Error) -> String {
+    format!(
+        "Could not register {}: {} (it may already be in use by another app)",
+        accelerator, e
+    )
+}
+
+/// Swap the hotkey for `action` to `accelerator` and save it.
+///
+/// The previous shortcut is unregistered first. If the new one can't be
+/// registered it's restored, the setting is left unchanged, and a
+/// descriptive error is returned.
+fn replace(app: &AppHandle, action: Action, accelerator: String) -> Result<(), String> {
+    let shortcut = parse(&accelerator)?;
+    if crate::headless() {
+        return settings::set(
+            app,
+            action.setting(),
+            serde_json::Value::String(accelerator),
+        );
+    }
+    if current(app, action.other()) == Some(shortcut) {
+        return Err(format!(
+            "{} is already the shortcut to {}",
+            accelerator,
+            action.other().describe()
+        ));
+    }
+    let previous = current(app, action);
+    let gs = app.global_shortcut();
+
+    if previous != Some(shortcut) {
+        if let Some(prev) = previous {
+            let _ = gs.unregister(prev);
+        }
+        // Don't hold the Registered lock across register() — the handler
+        // locks it on the main thread, which register() may dispatch to.
+        if let Err(e) = gs.register(shortcut) {
+            let restored = previous.filter(|prev| gs.register(*prev).is_ok());
+            store(app, action, restored);
+            return Err(registration_error(&accelerator, e));
+        }
+        store(app, action, Some(shortcut));
+    }
+
+    settings::set(
+        app,
+        action.setting(),
+        serde_json::Value::String(accelerator),
+    )
+}
+
+/// Replace the show/hide hotkey with `accelerator` (e.g. "Alt+Space").
+#[tauri::command]
+pub fn set_global_shortcut(app: AppHandle, accelerator: String) -> Result<(), String> {
+    replace(&app, Action::Toggle, accelerator)
+}
+
+/// Replace the quit hotkey with `accelerator` (e.g. "CmdOrCtrl+Alt+Q").
+#[tauri::command]
+pub fn set_quit_shortcut(app: AppHandle, accelerator: String) -> Result<(), String> {
+    replace(&app, Action::Quit, accelerator)
+}
@@ -0,0 +1,131 @@
+//! sidecar_error — typed errors for the sidecar commands.
+//!
+//! Serialized as `{ "kind": "<Variant>", "message": "..." }` plus the
+//! variant's fields under their own names (`expected_path`, `path`, `port`,
+//! `jobs`), so the frontend can branch on `kind` (offer reclaim_engine_port
+//! for PortInUse, a retry for Timeout, ...) and use the details without
+//! parsing the human-readable message.
+//!
+//! Helpers that predate this type return `Result<_, String>`; those strings
+//! convert into `Other` via `?`, and a SidecarError converts back into a
+//! String for callers outside the sidecar commands.
+
+use std::fmt;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+#[derive(Debug, Clone)]
+pub enum SidecarError {
+    /// start_sidecar was called while the sidecar is already up.
+    AlreadyRunning,
+    /// The process couldn't be launched or died during startup.
+    SpawnFailed(String),
+    /// The sidecar executable isn't installed where it should be — a broken
+    /// install. `expected_path` is where it was looked for.
+    BinaryMissing { expected_path: String },
+    /// The engine config file set with set_engine_config_file is gone or
+    /// unreadable. `path` is the configured path.
+    ConfigFileMissing { path: String },
+    /// The port is held by something that isn't one of our engines.
+    /// Serialized as `port`.
+    PortInUse(u16),
+    /// The command needs a running sidecar and there isn't one.
+    NotRunning,
+    /// The sidecar didn't become ready in time.
+    Timeout,
+    /// The process is up but hasn't answered `/health` (yet).
+    HealthCheckFailed,
+    /// The engine is starting and can't serve the request until it's ready.
+    NotReady,
+    /// reload_sidecar gave up waiting for in-flight jobs (the count still
+    /// running, serialized as `jobs`). The engine stays drained;
+    /// restart_sidecar forces it.
+    DrainTimeout(u64),
+    /// Anything without a targeted recovery action.
+    Other(String),
+}
+
+impl SidecarError {
+    /// The `kind` tag sent to the frontend.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::AlreadyRunning => "AlreadyRunning",
+            Self::SpawnFailed(_) => "SpawnFailed",
+            Self::BinaryMissing { .. } => "BinaryMissing",
+            Self::ConfigFileMissing { .. } => "ConfigFileMissing",
+            Self::PortInUse(_) => "PortInUse",
+            Self::NotRunning => "NotRunning",
+            Self::Timeout => "Timeout",
+            Self::HealthCheckFailed => "HealthCheckFailed",
+            Self::NotReady => "NotReady",
+            Self::DrainTimeout(_) => "DrainTimeout",
+            Self::Other(_) => "Other",
+        }
+    }
+}
+
+impl fmt::Display for SidecarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyRunning => write!(f, "The engine is already running"),
+            Self::SpawnFailed(msg) | Self::Other(msg) => write!(f, "{}", msg),
+            Self::BinaryMissing { expected_path } => write!(
+                f,
+                "The engine executable is missing ({}) — reinstall AI Matrx",
+                expected_path
+            ),
+            Self::ConfigFileMissing { path } => write!(
+                f,
+                "The engine config file {} is missing or unreadable",
+                path
+            ),
+            Self::PortInUse(port) => write!(f, "Port {} is in use by another application", port),
+            Self::NotRunning => write!(f, "The engine is not running"),
+            Self::Timeout => write!(f, "Timed out waiting for the engine to become ready"),
+            Self::HealthCheckFailed => write!(f, "The engine is not answering its health check"),
+            Self::NotReady => write!(f, "The engine is still starting"),
+            Self::DrainTimeout(jobs) => {
+                write!(f, "Timed out waiting for {} running job(s) to finish", jobs)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SidecarError {}
+
+impl Serialize for SidecarError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = match self {
+            Self::BinaryMissing { .. }
+            | Self::ConfigFileMissing { .. }
+            | Self::PortInUse(_)
+            | Self::DrainTimeout(_) => 3,
+            _ => 2,
+        };
+        let mut state = serializer.serialize_struct("SidecarError", len)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        match self {
+            Self::BinaryMissing { expected_path } => {
+                state.serialize_field("expected_path", expected_path)?
+            }
+            Self::ConfigFileMissing { path } => state.serialize_field("path", path)?,
+            Self::PortInUse(port) => state.serialize_field("port", port)?,
+            Self::DrainTimeout(jobs) => state.serialize_field("jobs", jobs)?,
+            _ => {}
+        }
+        state.end()
+    }
+}
+
+impl From<String> for SidecarError {
+    fn from(msg: String) -> Self {
+        Self::Other(msg)
+    }
+}
+
+impl From<SidecarError> for String {
+    fn from(err: SidecarError) -> Self {
+        err.to_string()
+    }
+}
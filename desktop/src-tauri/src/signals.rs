@@ -0,0 +1,76 @@
+//! signals — shut the engine down when the OS asks the app to stop.
+//!
+//! Logout, `kill`, Ctrl+C in a dev terminal or closing the console window
+//! otherwise end the app without running the quit path, orphaning the
+//! engine. Here SIGTERM / SIGINT / SIGHUP (Unix) and the console
+//! close / logoff / shutdown / Ctrl+C / Ctrl+Break events (Windows) run the
+//! same graceful shutdown as tray Quit, minus the confirm-quit prompt —
+//! nobody is there to answer it. A second signal while that is under way
+//! exits at once.
+//!
+//! Windows only waits a few seconds after a close or logoff event before
+//! killing the process, so there the detached safety net that
+//! graceful_shutdown_sync starts first is what reliably gets the engine.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::AppHandle;
+
+/// Set by the first signal; a second one skips the graceful shutdown.
+static SIGNALLED: AtomicBool = AtomicBool::new(false);
+
+/// Install the handlers. Called once from setup.
+pub fn install(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let name = match wait_for_signal().await {
+                Ok(name) => name,
+                Err(e) => {
+                    log::error!("[signals] Could not install signal handlers: {}", e);
+                    return;
+                }
+            };
+            if SIGNALLED.swap(true, Ordering::SeqCst) {
+                log::warn!("[signals] {} again — exiting without waiting", name);
+                std::process::exit(130);
+            }
+            log::info!("[signals] Received {} — shutting down", name);
+            crate::shutdown_and_exit(&app);
+        }
+    });
+}
+
+/// Wait for the next termination signal and return its name.
+#[cfg(unix)]
+async fn wait_for_signal() -> std::io::Result<&'static str> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut term = signal(SignalKind::terminate())?;
+    let mut int = signal(SignalKind::interrupt())?;
+    let mut hup = signal(SignalKind::hangup())?;
+    Ok(tokio::select! {
+        _ = term.recv() => "SIGTERM",
+        _ = int.recv() => "SIGINT",
+        _ = hup.recv() => "SIGHUP",
+    })
+}
+
+/// Wait for the next console control event and return its name.
+#[cfg(windows)]
+async fn wait_for_signal() -> std::io::Result<&'static str> {
+    use tokio::signal::windows;
+
+    let mut close = windows::ctrl_close()?;
+    let mut logoff = windows::ctrl_logoff()?;
+    let mut shutdown = windows::ctrl_shutdown()?;
+    let mut c = windows::ctrl_c()?;
+    let mut brk = windows::ctrl_break()?;
+    Ok(tokio::select! {
+        _ = close.recv() => "CTRL_CLOSE_EVENT",
+        _ = logoff.recv() => "CTRL_LOGOFF_EVENT",
+        _ = shutdown.recv() => "CTRL_SHUTDOWN_EVENT",
+        _ = c.recv() => "CTRL_C_EVENT",
+        _ = brk.recv() => "CTRL_BREAK_EVENT",
+    })
+}
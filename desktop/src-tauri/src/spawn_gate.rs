@@ -0,0 +1,191 @@
+//! spawn_gate — one spawn at a time per sidecar.
+//!
+//! A double-clicked Start, the setup warm start and the supervisor can all
+//! ask for the same sidecar at once. Each SidecarProcess has a SpawnGate,
+//! and spawn_sidecar runs its "is a child already up? if not, spawn one"
+//! step inside it, so a second caller waits for the first and then finds
+//! its child instead of spawning another. restart_sidecar_process enters
+//! the gate before it stops the old child and keeps it until the new one is
+//! spawned.
+//!
+//! The gate is a tokio mutex and the step itself runs on the blocking pool:
+//! spawning sleeps and shells out (the orphan sweep), and a caller waiting
+//! its turn must not park an async worker thread while it does. The step is
+//! reuse_or_spawn, kept apart from the process plumbing so it can be tested.
+
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::LockRecover;
+
+#[derive(Default)]
+pub struct SpawnGate(Mutex<()>);
+
+/// A caller's turn at the gate; the next caller gets in when it drops.
+pub struct SpawnTurn<'a>(MutexGuard<'a, ()>);
+
+impl SpawnGate {
+    /// Wait for our turn, for callers that have work to do before the
+    /// spawn itself.
+    pub async fn enter(&self) -> SpawnTurn<'_> {
+        SpawnTurn(self.0.lock().await)
+    }
+
+    /// Run `spawn` on the blocking pool in a turn of its own. Fails only if
+    /// `spawn` panicked.
+    pub async fn run<T, F>(&self, spawn: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        self.enter().await.run(spawn).await
+    }
+}
+
+impl SpawnTurn<'_> {
+    /// Run `spawn` on the blocking pool while this turn is held. Fails only
+    /// if `spawn` panicked.
+    pub async fn run<T, F>(&self, spawn: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        tauri::async_runtime::spawn_blocking(spawn)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// spawn_sidecar's "is a child already up? if not, spawn one" step. Returns
+/// `running()` if `child` holds a process `alive` vouches for; otherwise
+/// clears any stale handle and returns `spawn()`, which is expected to put
+/// the new child in `child`. Only race-free inside a turn at the gate.
+pub fn reuse_or_spawn<C, T>(
+    child: &std::sync::Mutex<Option<C>>,
+    alive: impl FnOnce(&C) -> bool,
+    running: impl FnOnce() -> T,
+    spawn: impl FnOnce() -> T,
+) -> T {
+    let reuse = {
+        let mut guard = child.lock_recover();
+        match guard.as_ref() {
+            Some(held) if alive(held) => true,
+            Some(_) => {
+                *guard = None;
+                false
+            }
+            None => false,
+        }
+    };
+    if reuse {
+        running()
+    } else {
+        spawn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::{reuse_or_spawn, SpawnGate};
+
+    /// Many starts at once, each running spawn_sidecar's reuse_or_spawn in
+    /// its turn: exactly one child is spawned and every caller gets its pid.
+    #[test]
+    fn concurrent_starts_spawn_one_child() {
+        let gate = Arc::new(SpawnGate::default());
+        let child: Arc<Mutex<Option<u32>>> = Arc::new(Mutex::new(None));
+        let spawned = Arc::new(AtomicU32::new(0));
+
+        let pids = tauri::async_runtime::block_on(async {
+            let starts: Vec<_> = (0..32)
+                .map(|_| {
+                    let (gate, child, spawned) = (gate.clone(), child.clone(), spawned.clone());
+                    tauri::async_runtime::spawn(async move {
+                        gate.run(move || start(&child, &spawned, |_| true)).await
+                    })
+                })
+                .collect();
+            let mut pids = Vec::new();
+            for start in starts {
+                pids.push(start.await.unwrap().unwrap());
+            }
+            pids
+        });
+
+        assert_eq!(spawned.load(Ordering::SeqCst), 1);
+        assert!(pids.iter().all(|&pid| pid == 1000));
+    }
+
+    /// A held child whose process is gone is replaced, once, however many
+    /// starts find it.
+    #[test]
+    fn stale_child_is_respawned_once() {
+        let gate = Arc::new(SpawnGate::default());
+        let child: Arc<Mutex<Option<u32>>> = Arc::new(Mutex::new(Some(1)));
+        let spawned = Arc::new(AtomicU32::new(0));
+
+        let pids = tauri::async_runtime::block_on(async {
+            let starts: Vec<_> = (0..8)
+                .map(|_| {
+                    let (gate, child, spawned) = (gate.clone(), child.clone(), spawned.clone());
+                    tauri::async_runtime::spawn(async move {
+                        gate.run(move || start(&child, &spawned, |&pid| pid != 1))
+                            .await
+                    })
+                })
+                .collect();
+            let mut pids = Vec::new();
+            for start in starts {
+                pids.push(start.await.unwrap().unwrap());
+            }
+            pids
+        });
+
+        assert_eq!(spawned.load(Ordering::SeqCst), 1);
+        assert!(pids.iter().all(|&pid| pid == 1000));
+        assert_eq!(*child.lock().unwrap(), Some(1000));
+    }
+
+    /// One start as spawn_sidecar_blocking does it, with a spawner that
+    /// counts its calls and hands out pids from 1000.
+    fn start(child: &Mutex<Option<u32>>, spawned: &AtomicU32, alive: fn(&u32) -> bool) -> u32 {
+        reuse_or_spawn(
+            child,
+            alive,
+            || child.lock().unwrap().unwrap(),
+            || {
+                // Long enough for every other start to arrive.
+                std::thread::sleep(Duration::from_millis(50));
+                let pid = 1000 + spawned.fetch_add(1, Ordering::SeqCst);
+                *child.lock().unwrap() = Some(pid);
+                pid
+            },
+        )
+    }
+
+    /// A turn taken with enter keeps other spawns out until it drops, as
+    /// restart_sidecar_process relies on while it stops the old child.
+    #[test]
+    fn entered_turn_holds_off_other_spawns() {
+        let gate = Arc::new(SpawnGate::default());
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        tauri::async_runtime::block_on(async {
+            let turn = gate.enter().await;
+            let other = tauri::async_runtime::spawn({
+                let (gate, order) = (gate.clone(), order.clone());
+                async move { gate.run(move || order.lock().unwrap().push("start")).await }
+            });
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            order.lock().unwrap().push("restart");
+            turn.run(|| ()).await.unwrap();
+            drop(turn);
+            other.await.unwrap().unwrap();
+        });
+
+        assert_eq!(*order.lock().unwrap(), ["restart", "start"]);
+    }
+}
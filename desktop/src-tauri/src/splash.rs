@@ -0,0 +1,122 @@
+//! splash — a small window shown while the engine cold-starts.
+//!
+//! With `show-splash` on (and the engine autostarting), setup opens a
+//! borderless `splash` window instead of the main one. It is a static page
+//! (public/splash.html) rather than a route of the main app so it is up in
+//! a blink; it renders the logo and the current `sidecar-startup-progress`
+//! phase. The window comes down and the main window goes up as soon as the
+//! engine is serving (`sidecar-ready`), or when the start fails — the engine
+//! stops, crashes or its restart circuit opens — so the main window can
+//! show what went wrong. SPLASH_MAX_WAIT is the backstop in case neither
+//! is ever reported.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, EventId, Listener, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::{settings, LockRecover, ENGINE_SIDECAR};
+
+const SPLASH_LABEL: &str = "splash";
+const SPLASH_WIDTH: f64 = 360.0;
+const SPLASH_HEIGHT: f64 = 260.0;
+
+/// Longest the splash stays up: the readiness poll's own limit plus slack.
+const SPLASH_MAX_WAIT: Duration = Duration::from_secs(crate::READY_TIMEOUT.as_secs() + 5);
+
+/// Set once the splash has been taken down, so it happens exactly once.
+static DONE: AtomicBool = AtomicBool::new(false);
+
+/// The event listeners driving the splash, removed with it.
+static LISTENERS: Mutex<Vec<EventId>> = Mutex::new(Vec::new());
+
+/// Open the splash in place of the main window if `show-splash` is on and
+/// the engine is about to autostart. Returns false when no splash is shown
+/// — the caller shows the main window as usual.
+pub fn show(app: &AppHandle) -> bool {
+    if !settings::show_splash(app)
+        || !settings::autostart_sidecar(app)
+        || settings::dev_engine_url(app).is_some()
+    {
+        return false;
+    }
+    let url = WebviewUrl::App("splash.html".into());
+    let built = WebviewWindowBuilder::new(app, SPLASH_LABEL, url)
+        .title("AI Matrx")
+        .inner_size(SPLASH_WIDTH, SPLASH_HEIGHT)
+        .center()
+        .decorations(false)
+        .resizable(false)
+        .skip_taskbar(true)
+        .build();
+    let window = match built {
+        Ok(window) => window,
+        Err(e) => {
+            log::error!("[splash] Could not open the splash window: {}", e);
+            return false;
+        }
+    };
+
+    // Closed some other way (Alt+F4): don't leave the app windowless.
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if matches!(event, tauri::WindowEvent::Destroyed) {
+            finish(&handle, "splash closed");
+        }
+    });
+
+    let handle = app.clone();
+    let ready = app.listen("sidecar-ready", move |event| {
+        if engine_payload(event.payload()).is_some() {
+            finish(&handle, "engine ready");
+        }
+    });
+    let handle = app.clone();
+    let state = app.listen("sidecar-state-changed", move |event| {
+        let Some(payload) = engine_payload(event.payload()) else {
+            return;
+        };
+        match payload["state"].as_str() {
+            // Running also covers an adopted engine, which is never "ready".
+            Some("running") => finish(&handle, "engine running"),
+            Some("stopped" | "crashed" | "failed") => finish(&handle, "engine did not start"),
+            _ => {}
+        }
+    });
+    LISTENERS.lock_recover().extend([ready, state]);
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SPLASH_MAX_WAIT).await;
+        finish(&handle, "timed out");
+    });
+    true
+}
+
+/// A sidecar event's payload, if it is about the engine.
+fn engine_payload(raw: &str) -> Option<serde_json::Value> {
+    serde_json::from_str::<serde_json::Value>(raw)
+        .ok()
+        .filter(|payload| payload["name"] == ENGINE_SIDECAR)
+}
+
+/// Close the splash and bring up the main window, once.
+fn finish(app: &AppHandle, why: &str) {
+    if DONE.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log::info!("[splash] Closing the splash ({})", why);
+    let app = app.clone();
+    // show_main_window must run on the main thread (see there). Also out of
+    // the listener that called us, which is no place to unlisten from.
+    let _ = app.clone().run_on_main_thread(move || {
+        for id in LISTENERS.lock_recover().drain(..) {
+            app.unlisten(id);
+        }
+        if let Some(window) = app.get_webview_window(SPLASH_LABEL) {
+            let _ = window.close();
+        }
+        crate::show_main_window(&app);
+    });
+}
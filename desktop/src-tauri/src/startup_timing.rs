@@ -0,0 +1,97 @@
+//! startup_timing — notice when the engine starts slower than it used to.
+//!
+//! Every engine cold start is timed from spawn to its first passing health
+//! check; sidecar_status reports the last one as `last_startup_duration_ms`.
+//! The most recent BASELINE_SAMPLES times are kept in the store
+//! (`startup-baseline`) and their mean is the baseline: a start taking more
+//! than SLOW_FACTOR times that emits `slow-startup`. The samples belong to
+//! one engine version — a new engine may legitimately start faster or
+//! slower, so a version change starts the baseline over.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::settings::{self, StartupBaseline};
+use crate::{LockRecover, SidecarState};
+
+/// How many recent starts the baseline averages.
+const BASELINE_SAMPLES: usize = 10;
+
+/// A start this many times the baseline counts as slow.
+const SLOW_FACTOR: u64 = 2;
+
+/// Payload of `slow-startup`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowStartup {
+    pub duration_ms: u64,
+    pub baseline_ms: u64,
+    pub engine_version: String,
+}
+
+/// Record an engine start that took `duration_ms`. Called by the readiness
+/// poll once the engine answers; the version lookup and store update run
+/// in the background.
+pub fn record(app: &AppHandle, duration_ms: u64) {
+    let sidecars = app.state::<SidecarState>();
+    *sidecars.engine().last_startup_ms.lock_recover() = Some(duration_ms);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let version = match crate::sidecar_version(app.state(), app.state()).await {
+            Ok(version) => version.engine_version,
+            Err(e) => {
+                log::error!("[startup] Could not read the engine version: {}", e);
+                return;
+            }
+        };
+        let mut baseline = settings::startup_baseline(&app);
+        if baseline.engine_version != version {
+            if !baseline.samples_ms.is_empty() {
+                log::info!(
+                    "[startup] Engine changed from {} to {} — starting a new baseline",
+                    baseline.engine_version,
+                    version
+                );
+            }
+            baseline = StartupBaseline {
+                engine_version: version.clone(),
+                samples_ms: Vec::new(),
+            };
+        }
+
+        if let Some(baseline_ms) = mean(&baseline.samples_ms) {
+            if duration_ms > baseline_ms * SLOW_FACTOR {
+                log::warn!(
+                    "[startup] Engine took {}ms to start (baseline {}ms)",
+                    duration_ms,
+                    baseline_ms
+                );
+                let _ = app.emit(
+                    "slow-startup",
+                    SlowStartup {
+                        duration_ms,
+                        baseline_ms,
+                        engine_version: version,
+                    },
+                );
+            }
+        }
+
+        baseline.samples_ms.push(duration_ms);
+        let excess = baseline.samples_ms.len().saturating_sub(BASELINE_SAMPLES);
+        baseline.samples_ms.drain(..excess);
+        let saved = serde_json::to_value(&baseline)
+            .map_err(|e| e.to_string())
+            .and_then(|value| settings::set(&app, settings::KEY_STARTUP_BASELINE, value));
+        if let Err(e) = saved {
+            log::error!("[startup] Could not save the startup baseline: {}", e);
+        }
+    });
+}
+
+fn mean(samples: &[u64]) -> Option<u64> {
+    if samples.is_empty() {
+        return None;
+    }
+    Some(samples.iter().sum::<u64>() / samples.len() as u64)
+}
@@ -0,0 +1,97 @@
+//! stray_engines — force-kill engine processes no session is using.
+//!
+//! After a few crashes, old engines can pile up and keep the port busy.
+//! The sweep before each spawn (kill_orphaned_sidecars) only stops the
+//! engine the discovery file places on our own port, so engines it lost
+//! track of are left running. `kill_stray_engines` enumerates processes
+//! with sysinfo instead, so it can find those by name while sparing the
+//! engine in use — the managed child (with its PyInstaller child process),
+//! or an adopted engine — and report how many it killed.
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tauri::{AppHandle, Manager};
+
+use crate::{LockRecover, SidecarState};
+
+/// Process names of engine builds, current and legacy. Linux cuts process
+/// names to 15 bytes, so these are matched as prefixes (of the executable's
+/// file name, too).
+const ENGINE_NAMES: &[&str] = &["Matrx Engine", "matrx-engine", "aimatrx-engine"];
+
+/// Kill every running engine process except the one in use, plus anything
+/// it started. Returns how many were killed.
+#[tauri::command]
+pub async fn kill_stray_engines(app: AppHandle) -> Result<u32, String> {
+    let protected: Vec<u32> = {
+        let sidecars = app.state::<SidecarState>();
+        let engine = sidecars.engine();
+        let child = engine.child.lock_recover().as_ref().map(|c| c.pid());
+        let adopted = engine.adopted.lock_recover().and_then(|a| a.pid);
+        [Some(std::process::id()), child, adopted]
+            .into_iter()
+            .flatten()
+            .collect()
+    };
+    tauri::async_runtime::spawn_blocking(move || kill_strays(&protected))
+        .await
+        .map_err(|e| format!("Failed to look for stray engines: {}", e))
+}
+
+fn kill_strays(protected: &[u32]) -> u32 {
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new().with_exe(UpdateKind::OnlyIfNotSet),
+    );
+    let processes = sys.processes();
+    let mut killed = 0;
+    for (pid, process) in processes {
+        if !is_engine(process) || descends_from(processes, *pid, protected) {
+            continue;
+        }
+        if process.kill() {
+            log::info!(
+                "[strays] Killed stray engine pid={} ({})",
+                pid,
+                process.name().to_string_lossy()
+            );
+            killed += 1;
+        } else {
+            log::error!("[strays] Could not kill stray engine pid={}", pid);
+        }
+    }
+    log::info!("[strays] Killed {} stray engine process(es)", killed);
+    killed
+}
+
+fn is_engine(process: &Process) -> bool {
+    let matches = |name: &OsStr| {
+        let name = name.to_string_lossy();
+        ENGINE_NAMES.iter().any(|engine| name.starts_with(engine))
+    };
+    matches(process.name())
+        || process
+            .exe()
+            .and_then(|exe| exe.file_name())
+            .is_some_and(matches)
+}
+
+/// Whether `pid` is one of `protected` or a descendant of one.
+fn descends_from(processes: &HashMap<Pid, Process>, pid: Pid, protected: &[u32]) -> bool {
+    let mut current = Some(pid);
+    // Bounded in case the OS reports a parent cycle (PID reuse).
+    for _ in 0..64 {
+        let Some(pid) = current else {
+            return false;
+        };
+        if protected.contains(&pid.as_u32()) {
+            return true;
+        }
+        current = processes.get(&pid).and_then(Process::parent);
+    }
+    false
+}
@@ -50,7 +50,7 @@ impl AudioCapture {
             match found {
                 Some(d) => d,
                 None => {
-                    eprintln!(
+                    log::warn!(
                         "[audio_capture] Device '{}' not found; falling back to system default",
                         name
                     );
@@ -90,9 +90,12 @@ impl AudioCapture {
             (stream, TARGET_SAMPLE_RATE)
         } else {
             // Native rate/channels differ from 16kHz mono — resample in real time.
-            eprintln!(
+            log::warn!(
                 "[audio_capture] Device '{}' native: {}Hz {}ch → resampling to {}Hz mono",
-                device_name, native_rate, native_channels, TARGET_SAMPLE_RATE
+                device_name,
+                native_rate,
+                native_channels,
+                TARGET_SAMPLE_RATE
             );
             let cfg: cpal::StreamConfig = supported_config.into();
             let buf = Arc::clone(&buffer);
@@ -154,7 +157,7 @@ fn build_passthrough_stream(
                 buf.drain(0..drain_count);
             }
         },
-        |err| eprintln!("[audio_capture] Stream error: {}", err),
+        |err| log::error!("[audio_capture] Stream error: {}", err),
         None,
     )
 }
@@ -246,11 +249,11 @@ fn build_resampling_stream(
                                 out.drain(0..drain_count);
                             }
                         }
-                        Err(e) => eprintln!("[audio_capture] Resample error: {}", e),
+                        Err(e) => log::error!("[audio_capture] Resample error: {}", e),
                     }
                 }
             },
-            |err| eprintln!("[audio_capture] Stream error: {}", err),
+            |err| log::error!("[audio_capture] Stream error: {}", err),
             None,
         )
         .map_err(|e| format!("Failed to build resampling stream: {}", e))?;
@@ -168,9 +168,10 @@ pub fn is_valid_model(path: &Path) -> bool {
     let meta = match std::fs::metadata(path) {
         Ok(m) => m,
         Err(e) => {
-            eprintln!(
+            log::error!(
                 "[downloader] is_valid_model: metadata error for {:?}: {}",
-                path, e
+                path,
+                e
             );
             return false;
         }
@@ -182,7 +183,7 @@ pub fn is_valid_model(path: &Path) -> bool {
         // Silero VAD is an ONNX-derived binary — no GGML header, validate by size only.
         let valid = meta.len() > 50_000;
         if !valid {
-            eprintln!(
+            log::warn!(
                 "[downloader] is_valid_model: VAD file too small — got {} bytes, need > 50000",
                 meta.len()
             );
@@ -192,7 +193,7 @@ pub fn is_valid_model(path: &Path) -> bool {
 
     // Whisper models must be at least 1MB
     if meta.len() < 1_000_000 {
-        eprintln!(
+        log::warn!(
             "[downloader] is_valid_model: file too small — got {} bytes, need > 1000000",
             meta.len()
         );
@@ -203,9 +204,10 @@ pub fn is_valid_model(path: &Path) -> bool {
     let mut file = match std::fs::File::open(path) {
         Ok(f) => f,
         Err(e) => {
-            eprintln!(
+            log::error!(
                 "[downloader] is_valid_model: open error for {:?}: {}",
-                path, e
+                path,
+                e
             );
             return false;
         }
@@ -213,9 +215,10 @@ pub fn is_valid_model(path: &Path) -> bool {
     let mut magic = [0u8; 4];
     use std::io::Read;
     if let Err(e) = file.read_exact(&mut magic) {
-        eprintln!(
+        log::error!(
             "[downloader] is_valid_model: read error for {:?}: {}",
-            path, e
+            path,
+            e
         );
         return false;
     }
@@ -225,7 +228,7 @@ pub fn is_valid_model(path: &Path) -> bool {
         VALID_WHISPER_MAGIC.iter().any(|m| &magic == m) || magic[0..2] == [0x67, 0x67];
 
     if !is_valid_magic {
-        eprintln!(
+        log::warn!(
             "[downloader] is_valid_model: unrecognised magic bytes for {:?}: [{:#04x}, {:#04x}, {:#04x}, {:#04x}]",
             path, magic[0], magic[1], magic[2], magic[3]
         );
@@ -0,0 +1,128 @@
+//! version_compat — make sure the engine is one this UI can talk to.
+//!
+//! A partial update (an interrupted install, a hand-copied binary) can leave
+//! a new UI next to an old engine or the other way round, which shows up as
+//! odd failures much later. Every time the engine comes up, its `/version`
+//! is compared with REQUIRED_ENGINE_RANGE; an engine outside it gets
+//! `version-mismatch` emitted and the main window brought up so the UI can
+//! block use and ask for a reinstall. The last result is also reported by
+//! the self-check as "engine-version".
+//!
+//! The range is compiled in: UI and engine ship together, so the UI knows
+//! which engines it was built against.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{LockRecover, SidecarError};
+
+/// Engine versions this build of the UI works with: comma-separated
+/// comparisons (`>=`, `>`, `<=`, `<`, `=`) that must all hold.
+pub const REQUIRED_ENGINE_RANGE: &str = ">=1.3.0, <2.0.0";
+
+/// The result of the last check, for the self-check.
+static LAST: Mutex<Option<VersionCompat>> = Mutex::new(None);
+
+/// Result of check_version_compat, and the payload of `version-mismatch`.
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionCompat {
+    pub compatible: bool,
+    pub app_version: String,
+    pub engine_version: String,
+    pub required_range: &'static str,
+}
+
+/// Compare the running engine's version with REQUIRED_ENGINE_RANGE. Emits
+/// `version-mismatch` when it falls outside. Fails like sidecar_version
+/// when there is no ready engine to ask.
+#[tauri::command]
+pub async fn check_version_compat(app: AppHandle) -> Result<VersionCompat, SidecarError> {
+    let engine = crate::sidecar_version(app.state(), app.state()).await?;
+    Ok(evaluate(&app, engine.engine_version))
+}
+
+/// Judge `engine_version`, remember the result for the self-check and emit
+/// `version-mismatch` if it's out of range. For callers that already have
+/// the version, like adopting an engine left over from a previous session.
+pub fn evaluate(app: &AppHandle, engine_version: String) -> VersionCompat {
+    let result = VersionCompat {
+        compatible: is_compatible(&engine_version),
+        app_version: app.package_info().version.to_string(),
+        engine_version,
+        required_range: REQUIRED_ENGINE_RANGE,
+    };
+    if !result.compatible {
+        log::warn!(
+            "[version] Engine {} is not compatible with this app {} (needs {})",
+            result.engine_version,
+            result.app_version,
+            REQUIRED_ENGINE_RANGE
+        );
+        let _ = app.emit("version-mismatch", &result);
+    }
+    *LAST.lock_recover() = Some(result.clone());
+    result
+}
+
+/// Whether this UI works with an engine at `engine_version`.
+pub fn is_compatible(engine_version: &str) -> bool {
+    satisfies(engine_version, REQUIRED_ENGINE_RANGE)
+}
+
+/// The last check's result; `None` until an engine has been checked.
+pub fn last() -> Option<VersionCompat> {
+    LAST.lock_recover().clone()
+}
+
+/// Check a freshly started engine in the background. Called when the engine
+/// reaches Running; a mismatch also shows the main window, as a critical
+/// self-check failure does.
+pub fn check_in_background(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match check_version_compat(app.clone()).await {
+            Ok(result) if !result.compatible => crate::show_main_window(&app),
+            Ok(_) => {}
+            Err(e) => log::error!("[version] Could not check the engine version: {}", e),
+        }
+    });
+}
+
+/// `major.minor.patch`, ignoring a leading "v", any pre-release or build
+/// suffix, and treating missing parts as 0.
+fn parse(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    Some((major, minor, patch))
+}
+
+/// True if `version` meets every comparison in `range`. A version or
+/// comparison that doesn't parse fails.
+fn satisfies(version: &str, range: &str) -> bool {
+    let Some(version) = parse(version) else {
+        return false;
+    };
+    range.split(',').all(|comparison| {
+        let comparison = comparison.trim();
+        let (op, bound) = ["<=", ">=", "<", ">", "="]
+            .iter()
+            .find_map(|op| comparison.strip_prefix(op).map(|rest| (*op, rest)))
+            .unwrap_or(("=", comparison));
+        let Some(bound) = parse(bound) else {
+            return false;
+        };
+        match op {
+            "<=" => version <= bound,
+            ">=" => version >= bound,
+            "<" => version < bound,
+            ">" => version > bound,
+            _ => version == bound,
+        }
+    })
+}
@@ -0,0 +1,134 @@
+//! watchdog_pause — stand the supervisor down for a maintenance window.
+//!
+//! Attaching a debugger or restarting the engine from its own admin UI
+//! looks to the health watchdog like a hang and to the supervisor like a
+//! crash, and both would then restart it. pause_watchdog suspends that for
+//! `duration_ms`: the watchdog skips its pings, and an engine exit is still
+//! noticed — state events, exit info and the lifecycle log as usual — but
+//! not acted on. The pause lifts itself when the time is up, or earlier
+//! with resume_watchdog; `watchdog-paused` / `watchdog-resumed` report both.
+//! It applies to every sidecar. A sidecar that exited during the pause is
+//! handed back to the supervisor when it lifts, and restarted if it's still
+//! down and its circuit isn't open.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::LockRecover;
+
+/// The longest pause_watchdog accepts: one hour.
+const MAX_PAUSE_MS: u64 = 60 * 60 * 1000;
+
+/// The current pause: a counter telling pauses apart, and when it ends.
+static PAUSE: Mutex<Option<(u64, Instant)>> = Mutex::new(None);
+/// Sidecars that exited during the pause, with their exit code and signal.
+static HELD: Mutex<Vec<(&'static str, Option<i32>, Option<i32>)>> = Mutex::new(Vec::new());
+
+/// Payload of `watchdog-paused`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchdogPaused {
+    pub duration_ms: u64,
+    /// When supervision resumes, in milliseconds since the Unix epoch.
+    pub resumes_at: u64,
+}
+
+/// Payload of `watchdog-resumed`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchdogResumed {
+    /// "expired" when the pause ran out, "resumed" for resume_watchdog.
+    pub reason: &'static str,
+}
+
+/// Whether automatic supervision is currently paused.
+pub fn is_paused() -> bool {
+    PAUSE
+        .lock_recover()
+        .is_some_and(|(_, until)| Instant::now() < until)
+}
+
+/// Remember an exit the supervisor left alone because of the pause. A later
+/// exit of the same sidecar replaces the earlier one.
+pub fn hold_restart(name: &'static str, code: Option<i32>, signal: Option<i32>) {
+    let mut held = HELD.lock_recover();
+    held.retain(|(n, _, _)| *n != name);
+    held.push((name, code, signal));
+}
+
+/// The pause has lifted: let the supervisor deal with the held exits.
+fn release_held(app: &AppHandle) {
+    let held = std::mem::take(&mut *HELD.lock_recover());
+    for (name, code, signal) in held {
+        crate::restart_held_sidecar(app, name, code, signal);
+    }
+}
+
+/// Suspend hang detection and crash restarts for `duration_ms`. Pausing
+/// again while paused replaces the remaining time.
+#[tauri::command]
+pub fn pause_watchdog(app: AppHandle, duration_ms: u64) -> Result<(), String> {
+    if duration_ms == 0 || duration_ms > MAX_PAUSE_MS {
+        return Err(format!(
+            "The watchdog can be paused for 1 to {}ms",
+            MAX_PAUSE_MS
+        ));
+    }
+    let duration = Duration::from_millis(duration_ms);
+    let generation = {
+        let mut pause = PAUSE.lock_recover();
+        let generation = pause.map_or(0, |(g, _)| g) + 1;
+        *pause = Some((generation, Instant::now() + duration));
+        generation
+    };
+    log::info!("[sidecar] Watchdog paused for {}ms", duration_ms);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let _ = app.emit(
+        "watchdog-paused",
+        WatchdogPaused {
+            duration_ms,
+            resumes_at: now + duration_ms,
+        },
+    );
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(duration).await;
+        let expired = {
+            let mut pause = PAUSE.lock_recover();
+            let current = pause.is_some_and(|(g, _)| g == generation);
+            if current {
+                *pause = None;
+            }
+            current
+        };
+        if expired {
+            log::info!("[sidecar] Watchdog pause expired — supervising again");
+            let _ = app.emit("watchdog-resumed", WatchdogResumed { reason: "expired" });
+            release_held(&app);
+        }
+    });
+    Ok(())
+}
+
+/// End a pause early. Returns whether the watchdog was paused.
+#[tauri::command]
+pub fn resume_watchdog(app: AppHandle) -> bool {
+    let was_paused = {
+        let mut pause = PAUSE.lock_recover();
+        let was_paused = pause.is_some_and(|(_, until)| Instant::now() < until);
+        // Bump the counter so the pending expiry timer finds it stale.
+        *pause = pause.map(|(g, _)| (g + 1, Instant::now()));
+        was_paused
+    };
+    if was_paused {
+        log::info!("[sidecar] Watchdog resumed");
+        let _ = app.emit("watchdog-resumed", WatchdogResumed { reason: "resumed" });
+        release_held(&app);
+    }
+    was_paused
+}
@@ -0,0 +1,193 @@
+//! window_state — persist and restore the main window's geometry.
+//!
+//! The main window's outer position and inner size are written to the
+//! settings store under the `window-state` key when the window is closed
+//! (hidden to tray) and SAVE_DEBOUNCE after it stops moving or resizing, and
+//! re-applied during `.setup()` so reopening the app does not snap back to
+//! the tauri.conf.json defaults. Debouncing matters because a drag fires a
+//! move event per frame and every save is a disk write.
+//!
+//! Values are stored in physical pixels — the same unit the window events
+//! report — so no scale-factor conversion is needed on the round trip.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Window};
+
+use crate::{settings, LockRecover};
+
+const STORE_KEY: &str = "window-state";
+const MAIN_LABEL: &str = "main";
+
+/// Normal minimum window size (tauri.conf.json minWidth/minHeight, logical).
+/// Compact recorder mode (set_compact_mode) shrinks the window below this —
+/// that geometry is temporary and must never be persisted.
+const MIN_PERSIST_WIDTH: f64 = 900.0;
+const MIN_PERSIST_HEIGHT: f64 = 600.0;
+
+/// Quiet period after the last move/resize before save_debounced() writes.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// When the pending debounced save is due. Some exactly while its task is
+/// alive; an immediate save() clears it, which cancels the task.
+static SAVE_DEADLINE: Mutex<Option<Instant>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Save the main window's current geometry to the store.
+///
+/// Skipped while the window is minimized (Windows reports -32000,-32000),
+/// maximized (we want to restore the un-maximized frame), or in compact mode.
+/// Supersedes any pending save_debounced().
+pub fn save(window: &Window) {
+    if window.label() != MAIN_LABEL {
+        return;
+    }
+    SAVE_DEADLINE.lock_recover().take();
+    if window.is_minimized().unwrap_or(false) || window.is_maximized().unwrap_or(false) {
+        return;
+    }
+    let (Ok(pos), Ok(size), Ok(scale)) = (
+        window.outer_position(),
+        window.inner_size(),
+        window.scale_factor(),
+    ) else {
+        return;
+    };
+    let logical = size.to_logical::<f64>(scale);
+    if logical.width < MIN_PERSIST_WIDTH || logical.height < MIN_PERSIST_HEIGHT {
+        return;
+    }
+
+    let geometry = WindowGeometry {
+        x: pos.x,
+        y: pos.y,
+        width: size.width,
+        height: size.height,
+    };
+    let Ok(store) = settings::store(window.app_handle()) else {
+        return;
+    };
+    match serde_json::to_value(geometry) {
+        Ok(value) => {
+            store.set(STORE_KEY, value);
+            if let Err(e) = store.save() {
+                log::error!("[window-state] Failed to save geometry: {}", e);
+            }
+        }
+        Err(e) => log::error!("[window-state] Failed to serialize geometry: {}", e),
+    }
+}
+
+/// Save SAVE_DEBOUNCE after the most recent call — for move/resize events.
+///
+/// One task serves a whole burst of events: later calls only push its
+/// deadline back.
+pub fn save_debounced(window: &Window) {
+    if window.label() != MAIN_LABEL {
+        return;
+    }
+    let task_running = SAVE_DEADLINE
+        .lock_recover()
+        .replace(Instant::now() + SAVE_DEBOUNCE)
+        .is_some();
+    if task_running {
+        return;
+    }
+
+    let window = window.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let wait = {
+                let now = Instant::now();
+                let mut deadline = SAVE_DEADLINE.lock_recover();
+                match *deadline {
+                    // Cancelled by an immediate save().
+                    None => return,
+                    Some(due) if due > now => due - now,
+                    Some(_) => {
+                        *deadline = None;
+                        break;
+                    }
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+        save(&window);
+    });
+}
+
+/// Re-apply the saved geometry to the main window, if any.
+///
+/// If the saved frame no longer lands on any connected monitor (e.g. it was
+/// last on an external display that has since been unplugged), it is clamped
+/// back onto the primary monitor instead of opening invisibly off-screen.
+pub fn restore(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_LABEL) else {
+        return;
+    };
+    let Ok(store) = settings::store(app) else {
+        return;
+    };
+    let Some(mut geometry) = store
+        .get(STORE_KEY)
+        .and_then(|v| serde_json::from_value::<WindowGeometry>(v).ok())
+    else {
+        return;
+    };
+
+    let monitors = window.available_monitors().unwrap_or_default();
+    let on_screen = monitors.iter().any(|m| {
+        point_in_rect(
+            // Probe a point just inside the title bar — if that is visible
+            // the user can grab the window and drag it wherever they like.
+            (geometry.x + 50, geometry.y + 20),
+            *m.position(),
+            *m.size(),
+        )
+    });
+    if !on_screen {
+        if let Ok(Some(primary)) = window.primary_monitor() {
+            geometry = clamp_to_monitor(geometry, *primary.position(), *primary.size());
+            log::info!(
+                "[window-state] Saved position was off-screen — clamped to primary monitor at {},{}",
+                geometry.x, geometry.y
+            );
+        }
+    }
+
+    let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+}
+
+fn point_in_rect(point: (i32, i32), origin: PhysicalPosition<i32>, size: PhysicalSize<u32>) -> bool {
+    point.0 >= origin.x
+        && point.0 < origin.x + size.width as i32
+        && point.1 >= origin.y
+        && point.1 < origin.y + size.height as i32
+}
+
+fn clamp_to_monitor(
+    geometry: WindowGeometry,
+    origin: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+) -> WindowGeometry {
+    let width = geometry.width.min(size.width);
+    let height = geometry.height.min(size.height);
+    let max_x = origin.x + (size.width - width) as i32;
+    let max_y = origin.y + (size.height - height) as i32;
+    WindowGeometry {
+        x: geometry.x.clamp(origin.x, max_x),
+        y: geometry.y.clamp(origin.y, max_y),
+        width,
+        height,
+    }
+}
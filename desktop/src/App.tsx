@@ -37,6 +37,7 @@ import {
 import { ErrorBoundary } from "@/components/ErrorBoundary";
 import { EngineMonitor } from "@/components/EngineRecoveryModal";
 import { UpdateDialog } from "@/components/UpdateDialog";
+import { ConfirmQuitDialog } from "@/components/ConfirmQuitDialog";
 import { UpdateBanner } from "@/components/UpdateBanner";
 import { RestartingOverlay } from "@/components/RestartingOverlay";
 import { NotificationToastContainer } from "@/components/notifications/NotificationCenter";
@@ -544,6 +545,7 @@ export default function App() {
                             state={updateState}
                             actions={updateActions}
                           />
+                          <ConfirmQuitDialog />
                           <DownloadManagerModal />
                           <DevTerminalPanel />
                         </TooltipProvider>
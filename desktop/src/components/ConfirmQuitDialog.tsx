@@ -0,0 +1,77 @@
+/**
+ * Confirm-before-quit prompt.
+ *
+ * Rust emits `confirm-quit` instead of exiting when Quit is chosen from the
+ * tray or app menu while the engine still has jobs running. The choice goes
+ * back through confirmQuit(); closing the dialog counts as "keep running".
+ */
+
+import { useEffect, useState } from "react";
+import type { UnlistenFn } from "@tauri-apps/api/event";
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogDescription,
+  DialogFooter,
+} from "@/components/ui/dialog";
+import { Button } from "@/components/ui/button";
+import { AlertTriangle, Power } from "lucide-react";
+import { confirmQuit, type ConfirmQuitEvent } from "@/lib/sidecar";
+
+export function ConfirmQuitDialog() {
+  const [activeJobs, setActiveJobs] = useState<number | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    let unlisten: UnlistenFn | undefined;
+    (async () => {
+      try {
+        const { listen } = await import("@tauri-apps/api/event");
+        const fn = await listen<ConfirmQuitEvent>("confirm-quit", (event) => {
+          setActiveJobs(event.payload.active_jobs);
+        });
+        if (cancelled) fn();
+        else unlisten = fn;
+      } catch {
+        // Not in Tauri
+      }
+    })();
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
+  const answer = (force: boolean) => {
+    setActiveJobs(null);
+    void confirmQuit(force);
+  };
+
+  return (
+    <Dialog open={activeJobs !== null} onOpenChange={(open) => { if (!open) answer(false); }}>
+      <DialogContent className="max-w-md">
+        <DialogHeader>
+          <DialogTitle className="flex items-center gap-2">
+            <AlertTriangle className="h-5 w-5 text-amber-500" />
+            Quit while work is running?
+          </DialogTitle>
+          <DialogDescription>
+            The engine is still running {activeJobs === 1 ? "a job" : `${activeJobs} jobs`}.
+            Quitting now stops {activeJobs === 1 ? "it" : "them"} before finishing.
+          </DialogDescription>
+        </DialogHeader>
+        <DialogFooter>
+          <Button variant="ghost" onClick={() => answer(false)}>
+            Keep Running
+          </Button>
+          <Button variant="destructive" onClick={() => answer(true)} className="gap-2">
+            <Power className="h-4 w-4" />
+            Quit Anyway
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -46,8 +46,13 @@ import {
   stopSidecar,
   getSidecarStatus,
   getSidecarLogs,
+  getEngineBaseUrl,
   waitForEngine,
   discoverEnginePort,
+  expandSidecarLogBatch,
+  formatSidecarLogEvent,
+  isEngineLogEvent,
+  type SidecarLogBatchEvent,
 } from "@/lib/sidecar";
 import { getPlatformSnapshot } from "@/lib/platformCtx";
 import type { EngineStatus } from "@/hooks/use-engine";
@@ -113,7 +118,7 @@ export function EngineMonitor({
     }
   }, [logs]);
 
-  // Subscribe to sidecar-log Tauri events for live output
+  // Subscribe to sidecar-log-batch Tauri events for live output
   useEffect(() => {
     if (!open || !isTauri()) return;
 
@@ -122,8 +127,10 @@ export function EngineMonitor({
     (async () => {
       try {
         const { listen } = await import("@tauri-apps/api/event");
-        unlisten = await listen<string>("sidecar-log", (event) => {
-          addLog(event.payload);
+        unlisten = await listen<SidecarLogBatchEvent>("sidecar-log-batch", (event) => {
+          for (const entry of expandSidecarLogBatch(event.payload)) {
+            if (isEngineLogEvent(entry)) addLog(formatSidecarLogEvent(entry));
+          }
         });
       } catch {
         // Not in Tauri — ignore
@@ -340,7 +347,8 @@ export function EngineMonitor({
     handleAction("Start Engine", async () => {
       await startSidecar();
       addLog("Sidecar spawned. Waiting for engine...");
-      const ready = await waitForEngine("http://127.0.0.1:22140", 60, 1000);
+      const engineUrl = (await getEngineBaseUrl()) ?? "http://127.0.0.1:22140";
+      const ready = await waitForEngine(engineUrl, 60, 1000);
       if (ready) {
         addLog("Engine ready. Connecting...");
         await onRefresh();
@@ -13,7 +13,14 @@
  */
 
 import { useEffect, useRef, useState, useCallback } from "react";
-import { isTauri, getSidecarLogs } from "@/lib/sidecar";
+import {
+  isTauri,
+  getSidecarLogs,
+  expandSidecarLogBatch,
+  formatSidecarLogEvent,
+  isEngineLogEvent,
+  type SidecarLogBatchEvent,
+} from "@/lib/sidecar";
 import type { UnlistenFn } from "@tauri-apps/api/event";
 import { CheckCircle2, XCircle, Circle, Loader2, Copy, Check, AlertTriangle } from "lucide-react";
 import { cn } from "@/lib/utils";
@@ -159,7 +166,7 @@ export function StartupScreen({ authLoading, engineStatus }: StartupScreenProps)
 
   // ── Core log + phase listener ──────────────────────────────────────────────
   // 1. On mount: load Rust ring-buffer (catches logs that fired before mount)
-  // 2. Subscribe to live sidecar-log events going forward
+  // 2. Subscribe to live sidecar-log-batch events going forward
   // Both paths feed into the same log display and phase parser.
   useEffect(() => {
     if (!isTauri()) return;
@@ -197,10 +204,11 @@ export function StartupScreen({ authLoading, engineStatus }: StartupScreenProps)
       // ── Step 2: Subscribe to live events ──────────────────────────────────
       try {
         const { listen } = await import("@tauri-apps/api/event");
-        const unlisten = await listen<string>("sidecar-log", (event) => {
+        const unlisten = await listen<SidecarLogBatchEvent>("sidecar-log-batch", (event) => {
           if (cancelled) return;
-          const line = typeof event.payload === "string" ? event.payload : String(event.payload);
-          processLine(line);
+          for (const entry of expandSidecarLogBatch(event.payload)) {
+            if (isEngineLogEvent(entry)) processLine(formatSidecarLogEvent(entry));
+          }
         });
         unlistenRef.current = unlisten;
       } catch {
@@ -29,7 +29,7 @@ import {
   useState,
 } from "react";
 import type { ReactNode } from "react";
-import { isTauri } from "@/lib/sidecar";
+import { isTauri, withEngineToken } from "@/lib/sidecar";
 import { engine } from "@/lib/api";
 import { emitClientLog } from "@/hooks/use-unified-log";
 import type { DownloadEntry, EnqueueOptions } from "@/lib/downloads/types";
@@ -274,9 +274,11 @@ export function DownloadManagerProvider({ children }: { children: ReactNode }) {
       }
 
       const token = await engine.getAccessToken();
-      const url = token
-        ? `${engineUrl}/downloads/stream?token=${encodeURIComponent(token)}`
-        : `${engineUrl}/downloads/stream`;
+      const url = await withEngineToken(
+        token
+          ? `${engineUrl}/downloads/stream?token=${encodeURIComponent(token)}`
+          : `${engineUrl}/downloads/stream`,
+      );
 
       es = new EventSource(url);
       sseRef.current = es;
@@ -1,6 +1,13 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { engine, type SystemInfo, type BrowserStatus } from "@/lib/api";
-import { isTauri, startSidecar, stopSidecar, waitForEngine, discoverEnginePort } from "@/lib/sidecar";
+import {
+  isTauri,
+  startSidecar,
+  stopSidecar,
+  waitForEngine,
+  discoverEnginePort,
+  getEngineBaseUrl,
+} from "@/lib/sidecar";
 import { initPlatformCtx } from "@/lib/platformCtx";
 import { startBackgroundTasks, stopBackgroundTasks } from "@/lib/background-tasks";
 import supabase from "@/lib/supabase";
@@ -113,8 +120,10 @@ export function useEngine(authenticated = true) {
         update({ status: "starting" });
         emitClientLog("info", "Starting sidecar process...", "engine");
         try {
-          await startSidecar();
-          emitClientLog("success", "Sidecar process spawned", "engine");
+          // Match the 60s budget of the reachability wait below — cold
+          // PyInstaller boots on slow machines can exceed Rust's 30s default.
+          await startSidecar(60_000);
+          emitClientLog("success", "Sidecar process started", "engine");
         } catch (err) {
           emitClientLog("error", `startSidecar failed: ${err}`, "engine");
           update({
@@ -125,8 +134,8 @@ export function useEngine(authenticated = true) {
         }
 
         // ── Wait for the sidecar to become reachable ─────────────────
-        // startSidecar() returns as soon as the process is spawned.
-        // The PyInstaller binary takes 5-30s to boot and bind a port.
+        // startSidecar() already waited for /health from Rust, so this
+        // normally succeeds on the first check.
         // We poll via Rust IPC (check_engine_health) which bypasses
         // Windows WebView2 loopback isolation that blocks JS fetch().
         //
@@ -141,16 +150,19 @@ export function useEngine(authenticated = true) {
           emitClientLog("info", `Still starting up... (${heartbeatSeconds}s elapsed)`, "engine");
         }, 5000);
 
+        // Rust knows the address the engine actually bound (which may be a
+        // LAN address or a fallback port); the default is only a last resort.
+        const engineUrl = (await getEngineBaseUrl()) ?? "http://127.0.0.1:22140";
         let ready = false;
         try {
-          ready = await waitForEngine("http://127.0.0.1:22140", 60, 1000);
+          ready = await waitForEngine(engineUrl, 60, 1000);
         } finally {
           clearInterval(heartbeatInterval);
         }
 
-        let confirmedUrl: string | null = ready ? "http://127.0.0.1:22140" : null;
+        let confirmedUrl: string | null = ready ? engineUrl : null;
         if (!ready) {
-          emitClientLog("warn", "Default port not responding — scanning port range...", "engine");
+          emitClientLog("warn", `${engineUrl} not responding — scanning port range...`, "engine");
           confirmedUrl = await discoverEnginePort();
           if (!confirmedUrl) {
             // ── Full diagnostic dump on failure ──────────────────────────────
@@ -174,7 +186,7 @@ export function useEngine(authenticated = true) {
           }
           emitClientLog("success", `Engine found at alternate port: ${confirmedUrl}`, "engine");
         } else {
-          emitClientLog("success", "Engine is responding on port 22140", "engine");
+          emitClientLog("success", `Engine is responding at ${engineUrl}`, "engine");
         }
 
         // Pass the confirmed URL directly so engine.discover() doesn't do another
@@ -5,7 +5,7 @@
  *   "server"  → engine /setup/logs SSE (structured, history + live)
  *   "syslog"  → engine /logs/stream SSE (raw system.log tail)
  *   "access"  → engine /logs/access/stream SSE (structured HTTP requests)
- *   "tauri"   → Tauri IPC sidecar-log events (Rust ring buffer + live)
+ *   "tauri"   → Tauri IPC sidecar-log-batch events (Rust ring buffer + live)
  *   "llm"     → Tauri llm-server-log events (llama-server stdout/stderr)
  *   "engine"  → engine discovery/connection lifecycle (emitted by use-engine)
  *   "auth"    → Supabase auth lifecycle (emitted by use-auth)
@@ -20,6 +20,12 @@
  */
 
 import { useEffect, useRef, useState } from "react";
+import {
+  expandSidecarLogBatch,
+  formatSidecarLogEvent,
+  type SidecarLogBatchEvent,
+  withEngineToken,
+} from "@/lib/sidecar";
 
 // ---------------------------------------------------------------------------
 // Types
@@ -485,7 +491,10 @@ async function startSyslogStream(
     }
     // Token obtained — reset the no-token warning so it shows again if session lapses.
     noTokenWarned = false;
-    const url = `${engineUrl}/logs/stream?token=${encodeURIComponent(token)}`;
+    const url = await withEngineToken(
+      `${engineUrl}/logs/stream?token=${encodeURIComponent(token)}`,
+    );
+    if (!active) return;
     const es = new EventSource(url);
     esRef = es;
 
@@ -588,7 +597,10 @@ async function startAccessStream(
   const connect = async () => {
     const tok = await getToken();
     if (!tok || !active) return;
-    const url = `${engineUrl}/logs/access/stream?token=${encodeURIComponent(tok)}`;
+    const url = await withEngineToken(
+      `${engineUrl}/logs/access/stream?token=${encodeURIComponent(tok)}`,
+    );
+    if (!active) return;
     const es = new EventSource(url);
     esRef = es;
 
@@ -703,40 +715,39 @@ async function startTauriStream(): Promise<() => void> {
 
   try {
     const { listen } = await import("@tauri-apps/api/event");
-    const unlisten = await listen<string>("sidecar-log", (event) => {
+    const unlisten = await listen<SidecarLogBatchEvent>("sidecar-log-batch", (event) => {
       if (_state.paused) return;
-      const text =
-        typeof event.payload === "string"
-          ? event.payload
-          : String(event.payload);
-      _trackRecentLine(text);
+      for (const entry of expandSidecarLogBatch(event.payload)) {
+        const text = formatSidecarLogEvent(entry);
+        _trackRecentLine(text);
 
-      // If this line is a crash signal, immediately dump recent context
-      if (_isCrashSignal(text)) {
-        emitClientLog(
-          "error",
-          `[CRASH DETECTED] ${cleanTauriMessage(text)}`,
-          "tauri",
-        );
-        emitClientLog(
-          "error",
-          `━━━ Last ${_recentTauriLines.length} engine lines before crash ━━━`,
-          "tauri",
-        );
-        [..._recentTauriLines].forEach((line) => {
-          emitClientLog("error", `  ${cleanTauriMessage(line)}`, "tauri");
-        });
-        emitClientLog(
-          "error",
-          `━━━ End of crash context ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━`,
-          "tauri",
-        );
-      } else {
-        emitClientLog(
-          parseTauriLogLevel(text) ?? inferServerLevel(text),
-          cleanTauriMessage(text),
-          "tauri",
-        );
+        // If this line is a crash signal, immediately dump recent context
+        if (_isCrashSignal(text)) {
+          emitClientLog(
+            "error",
+            `[CRASH DETECTED] ${cleanTauriMessage(text)}`,
+            "tauri",
+          );
+          emitClientLog(
+            "error",
+            `━━━ Last ${_recentTauriLines.length} engine lines before crash ━━━`,
+            "tauri",
+          );
+          [..._recentTauriLines].forEach((line) => {
+            emitClientLog("error", `  ${cleanTauriMessage(line)}`, "tauri");
+          });
+          emitClientLog(
+            "error",
+            `━━━ End of crash context ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━`,
+            "tauri",
+          );
+        } else {
+          emitClientLog(
+            parseTauriLogLevel(text) ?? inferServerLevel(text),
+            cleanTauriMessage(text),
+            "tauri",
+          );
+        }
       }
     });
     unlistenFn = unlisten;
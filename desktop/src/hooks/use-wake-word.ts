@@ -309,11 +309,11 @@ export function useWakeWord(
 
   // ── OWW engine: attach SSE ────────────────────────────────────────────
 
-  const attachOwwStream = useCallback(() => {
+  const attachOwwStream = useCallback(async () => {
     if (!isTauri()) return;
     teardownSse();
 
-    const es = engineAPI.owwStream();
+    const es = await engineAPI.owwStream();
     sseRef.current = es;
 
     es.addEventListener("wake-word-rms", (ev: MessageEvent) => {
@@ -389,7 +389,7 @@ export function useWakeWord(
           await invoke("start_wake_word", { deviceName: deviceName ?? null });
         } else {
           await engineAPI.owwStart({ deviceName });
-          attachOwwStream();
+          await attachOwwStream();
         }
         setUiMode("listening");
       } catch (e) {
@@ -6,6 +6,7 @@
  */
 
 import { emitClientLog } from "@/hooks/use-client-log";
+import { getEngineToken, isTauri, withEngineToken } from "@/lib/sidecar";
 
 const DEFAULT_PORT = 22140;
 const DISCOVERY_PORTS = Array.from({ length: 20 }, (_, i) => DEFAULT_PORT + i);
@@ -150,10 +151,15 @@ class EngineAPI {
   }
 
   private async authHeaders(): Promise<Record<string, string>> {
-    if (!this._getAccessToken) return {};
+    const headers: Record<string, string> = {};
+    // Proves to the engine that the call comes from our own UI. Fetched per
+    // call because Rust rotates it on every engine restart.
+    const engineToken = isTauri() ? await getEngineToken() : null;
+    if (engineToken) headers["X-Engine-Token"] = engineToken;
+    if (!this._getAccessToken) return headers;
     const token = await this._getAccessToken();
-    if (!token) return {};
-    return { Authorization: `Bearer ${token}` };
+    if (token) headers.Authorization = `Bearer ${token}`;
+    return headers;
   }
 
   /** Headers for authenticated engine HTTP calls (same JWT as tools, settings, etc.). */
@@ -561,9 +567,9 @@ class EngineAPI {
    * The caller is responsible for closing it (eventSource.close()).
    * The base URL must be discovered before calling this.
    */
-  owwStream(): EventSource {
+  async owwStream(): Promise<EventSource> {
     if (!this.baseUrl) throw new Error("Engine not discovered");
-    return new EventSource(`${this.baseUrl}/wake-word/stream`);
+    return new EventSource(await withEngineToken(`${this.baseUrl}/wake-word/stream`));
   }
 
   /** Get the list of available tools from the engine. */
@@ -822,9 +828,9 @@ class EngineAPI {
     // WebSocket does not support arbitrary headers in the browser.
     // The server validates auth via a `?token=` query parameter instead.
     const token = this._getAccessToken ? await this._getAccessToken() : null;
-    const url = token
-      ? `${this.wsUrl}?token=${encodeURIComponent(token)}`
-      : this.wsUrl;
+    const url = await withEngineToken(
+      token ? `${this.wsUrl}?token=${encodeURIComponent(token)}` : this.wsUrl,
+    );
 
     return new Promise((resolve, reject) => {
       this.ws = new WebSocket(url);
@@ -2782,9 +2788,12 @@ class EngineAPI {
     const connect = async () => {
       const token = this._getAccessToken ? await this._getAccessToken() : null;
       if (closed) return;
-      const url = token
-        ? `${this.wsUrl!.replace(/\/ws$/, "")}/extension/bridge-events?token=${encodeURIComponent(token)}`
-        : `${this.wsUrl!.replace(/\/ws$/, "")}/extension/bridge-events`;
+      const url = await withEngineToken(
+        token
+          ? `${this.wsUrl!.replace(/\/ws$/, "")}/extension/bridge-events?token=${encodeURIComponent(token)}`
+          : `${this.wsUrl!.replace(/\/ws$/, "")}/extension/bridge-events`,
+      );
+      if (closed) return;
       socket = new WebSocket(url);
       socket.onmessage = (ev) => {
         try {
@@ -3597,9 +3606,11 @@ export function streamImageGenInstall(
 
   const connect = async () => {
     const token = await getToken();
-    const url = token
-      ? `${imageGenUrl(baseUrl, "/install/stream")}?token=${encodeURIComponent(token)}`
-      : imageGenUrl(baseUrl, "/install/stream");
+    const url = await withEngineToken(
+      token
+        ? `${imageGenUrl(baseUrl, "/install/stream")}?token=${encodeURIComponent(token)}`
+        : imageGenUrl(baseUrl, "/install/stream"),
+    );
     es = new EventSource(url);
     es.onmessage = (ev) => {
       if (closed) return;
@@ -1,4 +1,9 @@
-import { isTauri, setCloseToTray } from "@/lib/sidecar";
+import {
+  isAutostartEnabled,
+  isTauri,
+  setAutostart,
+  setCloseToTray,
+} from "@/lib/sidecar";
 import { engine } from "@/lib/api";
 
 const STORAGE_KEY = "matrx-settings";
@@ -242,15 +247,7 @@ async function syncSetting<K extends keyof AppSettings>(
   try {
     switch (key) {
       case "launchOnStartup":
-        if (isTauri()) {
-          const { enable, disable } =
-            await import("@tauri-apps/plugin-autostart");
-          if (all.launchOnStartup) {
-            await enable();
-          } else {
-            await disable();
-          }
-        }
+        await setAutostart(all.launchOnStartup);
         break;
 
       case "minimizeToTray":
@@ -327,12 +324,14 @@ export async function syncAllSettings(): Promise<SyncResult> {
   await setCloseToTray(settings.minimizeToTray);
 
   if (isTauri()) {
+    // The OS registration is the source of truth (the tray's "Start at Login"
+    // checkbox can change it behind our back) — pull it in rather than push.
     try {
-      const { enable, disable, isEnabled } =
-        await import("@tauri-apps/plugin-autostart");
-      const current = await isEnabled();
-      if (settings.launchOnStartup && !current) await enable();
-      if (!settings.launchOnStartup && current) await disable();
+      const current = await isAutostartEnabled();
+      if (current !== null && current !== settings.launchOnStartup) {
+        settings.launchOnStartup = current;
+        await saveSettings(settings);
+      }
     } catch (err) {
       console.warn("[settings] Failed to sync autostart:", err);
     }
@@ -23,34 +23,885 @@ export function isTauri(): boolean {
   return "__TAURI_INTERNALS__" in window;
 }
 
-/** Start the Python engine sidecar (Tauri only). */
-export async function startSidecar(): Promise<void> {
+/** Name of the main engine sidecar — the default for every sidecar call. */
+export const ENGINE_SIDECAR = "aimatrx-engine";
+
+/** `kind` of a failed sidecar command — mirrors the Rust SidecarError. */
+export type SidecarErrorKind =
+  | "AlreadyRunning"
+  | "SpawnFailed"
+  | "BinaryMissing"
+  | "ConfigFileMissing"
+  | "PortInUse"
+  | "NotRunning"
+  | "Timeout"
+  | "HealthCheckFailed"
+  | "NotReady"
+  | "DrainTimeout"
+  | "Other";
+
+/**
+ * Rejection of the sidecar commands. Branch on `kind` to pick a recovery
+ * action (e.g. `reclaimEnginePort()` for PortInUse); `message` is
+ * human-readable.
+ */
+export class SidecarError extends Error {
+  readonly kind: SidecarErrorKind;
+  /** For BinaryMissing: where the engine executable was expected. */
+  readonly expectedPath?: string;
+  /** For ConfigFileMissing: the configured engine config file. */
+  readonly path?: string;
+  /** For PortInUse: the port that is taken. */
+  readonly port?: number;
+  /** For DrainTimeout: how many jobs were still running. */
+  readonly jobs?: number;
+
+  constructor(kind: SidecarErrorKind, message: string, details: SidecarErrorDetails = {}) {
+    super(message);
+    this.name = "SidecarError";
+    this.kind = kind;
+    this.expectedPath = details.expected_path;
+    this.path = details.path;
+    this.port = details.port;
+    this.jobs = details.jobs;
+  }
+
+  /** Just the message, so existing `${err}` logging reads the same. */
+  override toString(): string {
+    return this.message;
+  }
+}
+
+/** Variant fields the Rust SidecarError sends next to `kind` and `message`. */
+export interface SidecarErrorDetails {
+  expected_path?: string;
+  path?: string;
+  port?: number;
+  jobs?: number;
+}
+
+/** Turn a rejected invoke's `{ kind, message }` payload into a SidecarError. */
+function toSidecarError(err: unknown): SidecarError {
+  if (err instanceof SidecarError) return err;
+  if (err && typeof err === "object" && "kind" in err && "message" in err) {
+    const { kind, message, ...details } = err as {
+      kind: SidecarErrorKind;
+      message: string;
+    } & SidecarErrorDetails;
+    return new SidecarError(kind, message, details);
+  }
+  return new SidecarError("Other", String(err));
+}
+
+/**
+ * Turn a `sidecar-autostart-failed` payload into a SidecarError. Rust emits
+ * it when the background engine start during app setup (the
+ * `autostart-sidecar-on-launch` setting, on by default) fails; it has the
+ * same `{ kind, message }` shape as a command rejection.
+ */
+export function sidecarErrorFromEvent(payload: unknown): SidecarError {
+  return toSidecarError(payload);
+}
+
+async function invokeSidecar<T>(
+  inv: NonNullable<typeof invoke>,
+  cmd: string,
+  args?: Record<string, unknown>,
+): Promise<T> {
+  try {
+    return (await inv(cmd, args)) as T;
+  } catch (err) {
+    throw toSidecarError(err);
+  }
+}
+
+/**
+ * Start the Python engine sidecar (or another sidecar by `name`) and wait
+ * until it answers /health (Tauri only). Resolves immediately if it is
+ * already running. Rejects with a `Timeout` SidecarError — after the
+ * half-started process has been killed — if it isn't ready within
+ * `readyTimeoutMs` (Rust default: 30s). Progress arrives as
+ * `sidecar-starting` events while it waits.
+ */
+export async function startSidecar(
+  readyTimeoutMs?: number,
+  name: string = ENGINE_SIDECAR,
+): Promise<void> {
   const inv = await loadTauriInvoke();
   if (!inv) {
     console.log("[sidecar] Not in Tauri, skipping sidecar start");
     return;
   }
-  await inv("start_sidecar");
+  try {
+    await invokeSidecar(inv, "start_sidecar", { name, readyTimeoutMs });
+  } catch (err) {
+    if (err instanceof SidecarError && err.kind === "AlreadyRunning") return;
+    throw err;
+  }
+}
+
+/** Payload of the `sidecar-starting` event, emitted per readiness poll. */
+export interface SidecarStartingEvent {
+  name: string;
+  pid: number;
+  port: number;
+  attempt: number;
+  elapsed_ms: number;
+}
+
+/**
+ * How far a start has got, in order: launching the process, process running
+ * but not listening, port open but the health check not passing yet, and
+ * serving.
+ */
+export type StartupPhase = "spawning" | "process-up" | "port-open" | "health-passing";
+
+/**
+ * Payload of `sidecar-startup-progress`, emitted once before the spawn
+ * (`spawning`, pid null) and then per readiness poll.
+ */
+export interface SidecarStartupProgressEvent {
+  name: string;
+  pid: number | null;
+  phase: StartupPhase;
+  attempt: number;
+  elapsed_ms: number;
+}
+
+/**
+ * Resolve with the engine's port once it is serving, starting it first if
+ * nothing is running (Tauri only). Await this before the first engine
+ * request on cold start. Rejects with a `Timeout` SidecarError after `timeoutMs`
+ * (Rust default: 30s); the engine itself is left running.
+ */
+export async function waitForReady(
+  timeoutMs?: number,
+  name: string = ENGINE_SIDECAR,
+): Promise<number | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<number>(inv, "wait_for_ready", { name, timeoutMs });
+}
+
+/** Stop the Python engine sidecar, or another sidecar by name (Tauri only). */
+export async function stopSidecar(name: string = ENGINE_SIDECAR): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await invokeSidecar(inv, "stop_sidecar", { name });
+}
+
+/**
+ * Restart the Python engine sidecar atomically (Tauri only).
+ * Resolves with the port the new engine was told to bind.
+ */
+export async function restartSidecar(name: string = ENGINE_SIDECAR): Promise<number | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<number>(inv, "restart_sidecar", { name });
+}
+
+/**
+ * Restart the engine gracefully after `delayMs` (at most an hour), e.g. once
+ * a download has finished (Tauri only). Replaces any restart already
+ * scheduled. Resolves with a handle for `cancelScheduledRestart()`.
+ */
+export async function scheduleRestart(delayMs: number): Promise<number | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("schedule_restart", { delayMs })) as number;
+}
+
+/**
+ * Cancel the scheduled restart — only if it is `handle`, when given (Tauri
+ * only). Resolves with whether one was cancelled.
+ */
+export async function cancelScheduledRestart(handle?: number): Promise<boolean> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return false;
+  return (await inv("cancel_scheduled_restart", { handle: handle ?? null })) as boolean;
+}
+
+/**
+ * Stop the supervisor from acting for `durationMs` (at most an hour) — for
+ * debugging the engine or restarting it from its own admin UI (Tauri only).
+ * Hang checks are skipped and an exit is reported but not restarted.
+ * Pausing again replaces the remaining time.
+ */
+export async function pauseWatchdog(durationMs: number): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await inv("pause_watchdog", { durationMs });
+}
+
+/**
+ * End a watchdog pause early (Tauri only). Resolves with whether one was active.
+ * A sidecar that exited during the pause is restarted, as when the pause runs out.
+ */
+export async function resumeWatchdog(): Promise<boolean> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return false;
+  return (await inv("resume_watchdog")) as boolean;
+}
+
+/**
+ * Pause the engine to save battery (Tauri only): it keeps its state but
+ * refuses new tool calls and stops background scanning until
+ * `resumeSidecar()`. Also toggled from the tray's "Pause Engine" item; both
+ * fire `sidecar-paused` with `{ paused }`. A restart always comes back unpaused.
+ */
+export async function pauseSidecar(): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await invokeSidecar(inv, "pause_sidecar");
+}
+
+/** Undo `pauseSidecar()` (Tauri only). */
+export async function resumeSidecar(): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await invokeSidecar(inv, "resume_sidecar");
+}
+
+/**
+ * Restart the engine once its in-flight jobs finish (Tauri only) — use for
+ * config reloads. New work is refused while draining. Rejects with a
+ * `DrainTimeout` SidecarError if jobs outlast `drainTimeoutMs` (default
+ * 60s); the engine stays drained, so follow up with `restartSidecar()` to
+ * force it. Resolves with the port the new engine was told to bind.
+ */
+export async function reloadSidecar(drainTimeoutMs?: number): Promise<number | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<number>(inv, "reload_sidecar", { drainTimeoutMs });
+}
+
+/** When the supervisor respawns a sidecar that exited on its own. */
+export type RestartPolicy = "always" | "on-failure" | "never";
+
+/**
+ * Set a sidecar's restart policy for the rest of the session (Tauri only).
+ * `maxRestarts` caps supervisor respawns, counting those already made; omit
+ * it for no cap. When the policy rules out a restart the sidecar stays down
+ * and a `sidecar-gave-up` event fires.
+ */
+export async function setRestartPolicy(
+  policy: RestartPolicy,
+  maxRestarts?: number,
+  name: string = ENGINE_SIDECAR,
+): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await invokeSidecar(inv, "set_restart_policy", {
+    name,
+    policy,
+    maxRestarts: maxRestarts ?? null,
+  });
+}
+
+/**
+ * Payload of `sidecar-suspended`, emitted when the idle timer pauses an
+ * engine that ran no tool calls for `idle-suspend-minutes`. Followed by
+ * `sidecar-paused` with `{ paused: false }` when a request resumes it.
+ */
+export interface SidecarSuspendedEvent {
+  idle_secs: number;
+}
+
+/**
+ * Payload of `engine-unresponsive` and `engine-responsive`. Each fires only
+ * on a change, so they can drive a banner directly: shown on the first failed
+ * health ping, hidden when the engine answers again or a replacement is
+ * ready (`recovered`) or the process is gone (`stopped`).
+ */
+export interface EngineResponsivenessEvent {
+  name: string;
+  pid: number | null;
+  reason: "ping-failed" | "recovered" | "stopped";
+}
+
+/**
+ * Payload of `system-resumed`, emitted shortly after the machine wakes from
+ * sleep once the engine has been re-probed — a cue to reconnect websockets.
+ */
+export interface SystemResumedEvent {
+  /** Roughly how long the machine was asleep. */
+  slept_secs: number;
+  /** Whether the engine answered /health; null when it wasn't running. */
+  engine_healthy: boolean | null;
+}
+
+/** Payload of the Rust `sidecar-gave-up` event — the sidecar stays down. */
+export interface SidecarGaveUpEvent {
+  name: string;
+  reason: "policy-never" | "exited-cleanly" | "max-restarts";
+  /** Supervisor respawns made before giving up. */
+  restarts: number;
+}
+
+/**
+ * Payload of `slow-startup`: the engine took more than twice its baseline —
+ * the mean of its recent starts on this engine version — to become ready.
+ */
+export interface SlowStartupEvent {
+  duration_ms: number;
+  baseline_ms: number;
+  engine_version: string;
+}
+
+/** Payload of `watchdog-paused`. */
+export interface WatchdogPausedEvent {
+  duration_ms: number;
+  /** When supervision resumes, in milliseconds since the Unix epoch. */
+  resumes_at: number;
+}
+
+/** Payload of `watchdog-resumed`: the pause ran out or `resumeWatchdog()` ended it. */
+export interface WatchdogResumedEvent {
+  reason: "expired" | "resumed";
+}
+
+/** Payload of `restart-scheduled`. */
+export interface RestartScheduledEvent {
+  handle: number;
+  delay_ms: number;
+  /** When the restart is due, in milliseconds since the Unix epoch. */
+  due_at: number;
+}
+
+/** Payload of `restart-cancelled`; "replaced" by a newer `scheduleRestart()`. */
+export interface RestartCancelledEvent {
+  handle: number;
+  reason: "cancelled" | "replaced";
+}
+
+/**
+ * Payload of `sidecar-circuit-open`: the sidecar crashed `failures` times
+ * within `window_secs` (settings `restart-circuit-max-failures` /
+ * `restart-circuit-window-secs`), so the supervisor stopped restarting it.
+ * Offer a Retry that calls `resetCircuitAndStart()`.
+ */
+export interface SidecarCircuitOpenEvent {
+  name: string;
+  failures: number;
+  window_secs: number;
+}
+
+/**
+ * Close the supervisor's circuit breaker for a sidecar (the engine by
+ * default) and start it again (Tauri only). Rejects like `startSidecar()`.
+ */
+export async function resetCircuitAndStart(
+  readyTimeoutMs?: number,
+  name: string = ENGINE_SIDECAR,
+): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await invokeSidecar(inv, "reset_circuit_and_start", { name, readyTimeoutMs });
+}
+
+/**
+ * Configure extra environment variables (e.g. `MATRX_DATA_DIR`) and CLI args
+ * (e.g. `["--log-level", "debug"]`) for the engine (Tauri only).
+ *
+ * Only applies on the next start — call `restartSidecar()` to pick it up now.
+ * Omit `args` to keep the previously configured args.
+ */
+export async function configureSidecarEnv(
+  env: Record<string, string>,
+  args?: string[],
+): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await inv("configure_sidecar_env", { env, args: args ?? null });
+}
+
+/** Payload of the Rust `data-dir-progress` event, sent while `setDataDir()` copies. */
+export interface DataDirProgressEvent {
+  copied_bytes: number;
+  total_bytes: number;
+  files_done: number;
+  files_total: number;
+}
+
+/**
+ * Move the engine's data folder to `path` (Tauri only), which must be empty
+ * and writable. The engine is stopped, the data copied across (unless
+ * `moveData` is false) with `data-dir-progress` events, and the engine
+ * restarted on the new folder. Resolves with the folder now in use.
+ */
+export async function setDataDir(path: string, moveData = true): Promise<string | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("set_data_dir", { path, moveData })) as string;
+}
+
+/** Result of `dataDirSpace()`. */
+export interface DataDirSpace {
+  path: string;
+  total_bytes: number;
+  /** Free space available to this user on the data folder's disk. */
+  free_bytes: number;
+  /** Size of the data folder itself. */
+  used_by_matrx_bytes: number;
+}
+
+/**
+ * Disk space for the engine's data folder (Tauri only). Walks the folder to
+ * size it, so can take a while for a large one; rejects if the folder isn't
+ * known yet.
+ */
+export async function dataDirSpace(): Promise<DataDirSpace | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("data_dir_space")) as DataDirSpace;
+}
+
+/**
+ * Payload of `low-disk-space`, sent (with a notification) once free space on
+ * the data folder's disk drops below `low-disk-threshold-mb`.
+ */
+export interface LowDiskSpaceEvent {
+  path: string;
+  free_bytes: number;
+  threshold_bytes: number;
+}
+
+/** Optional engine features `setFeatureFlags()` accepts. */
+export type EngineFeature = "bridge-broadcast" | "local-scheduler";
+
+/** Payload of `restart-required`. */
+export interface RestartRequiredEvent {
+  reason:
+    | "feature-flags"
+    | "engine-config"
+    | "engine-config-file"
+    | "resource-limits"
+    | "settings-import";
+  /** False when the change was undone and no restart is needed after all. */
+  required: boolean;
+}
+
+/** The engine features enabled for the next launch (Tauri only). */
+export async function getFeatureFlags(): Promise<EngineFeature[]> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return [];
+  return (await inv("get_feature_flags")) as EngineFeature[];
+}
+
+/**
+ * Save the enabled engine features (Tauri only). They are passed to the
+ * engine at startup, so a running engine emits `restart-required` instead of
+ * picking them up. Rejects on an unknown feature name.
+ */
+export async function setFeatureFlags(flags: EngineFeature[]): Promise<EngineFeature[]> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return [];
+  return (await inv("set_feature_flags", { flags })) as EngineFeature[];
+}
+
+/** CPU / memory caps for the engine; null leaves that resource unlimited. */
+export interface ResourceLimits {
+  /** Most CPU cores the engine may run on. */
+  cpu_cores: number | null;
+  /**
+   * Memory cap for the engine, in MiB. On Linux it's virtual address space,
+   * not resident memory, so allow well above its real use; on Windows it's
+   * committed memory shared with the engine's worker processes.
+   */
+  memory_mb: number | null;
+}
+
+/** The saved engine resource limits (Tauri only). */
+export async function getResourceLimits(): Promise<ResourceLimits | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("get_resource_limits")) as ResourceLimits;
+}
+
+/**
+ * Save engine resource limits (Tauri only). They apply from the next start,
+ * so a running engine emits `restart-required`. Resolves with the names of
+ * the limits this platform can't enforce — Linux enforces both, macOS only
+ * lowers the engine's priority for `cpu_cores`, Windows enforces both.
+ * Rejects on out-of-range values.
+ */
+export async function setResourceLimits(
+  limits: ResourceLimits,
+): Promise<Array<keyof ResourceLimits>> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return [];
+  return (await inv("set_resource_limits", { limits })) as Array<keyof ResourceLimits>;
+}
+
+/** One result of `runSelfCheck()`, also sent in `self-check-complete`. */
+export interface SelfCheckResult {
+  check: "sidecar-binary" | "data-dir" | "port" | "notifications" | "engine-version";
+  ok: boolean;
+  /** A failed critical check means the app can't work until it is fixed. */
+  critical: boolean;
+  detail: string;
+}
+
+/**
+ * Check the engine binary, data folders, preferred port and notification
+ * permission (Tauri only). Rust also runs this at launch — before the
+ * frontend can listen for `self-check-complete` — and shows the window if a
+ * critical check failed, so call it on mount to get the launch results.
+ */
+export async function runSelfCheck(): Promise<SelfCheckResult[]> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return [];
+  return (await inv("run_self_check")) as SelfCheckResult[];
+}
+
+/** Log levels `setLogLevel()` accepts. */
+export type EngineLogLevel = "trace" | "debug" | "info" | "warn" | "error";
+
+/**
+ * Change the engine's log level without restarting it (Tauri only). The
+ * level is also saved so later starts use it. Resolves with the level the
+ * engine reports after the change.
+ */
+export async function setLogLevel(level: EngineLogLevel): Promise<EngineLogLevel | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<EngineLogLevel>(inv, "set_log_level", { level });
+}
+
+/** Payload of the Rust `sidecar-port-in-use` event. */
+export interface SidecarPortInUseEvent {
+  name: string;
+  /** The preferred port that was occupied. */
+  port: number;
+  /** The port the engine was started on instead. */
+  fallback: number;
+  /** Set when the occupant is an orphaned engine that can be reclaimed. */
+  engine_pid: number | null;
+}
+
+/**
+ * Stop the orphaned engine holding the preferred port and restart on it
+ * (Tauri only). Rejects with a `PortInUse` SidecarError if the port is held
+ * by some other application.
+ */
+export async function reclaimEnginePort(): Promise<number | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<number>(inv, "reclaim_engine_port");
+}
+
+/**
+ * Force-kill engine processes left over from earlier sessions — the escape
+ * hatch when the engine port stays in use (Tauri only). The engine in use,
+ * managed or adopted, is spared. Resolves with how many were killed.
+ */
+export async function killStrayEngines(): Promise<number> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return 0;
+  return (await inv("kill_stray_engines")) as number;
+}
+
+/**
+ * The supervisor's recent lifecycle decisions from `lifecycle.jsonl`,
+ * oldest first — the last `limit` entries, 200 by default (Tauri only).
+ */
+export async function getLifecycleHistory(limit?: number): Promise<LifecycleEntry[]> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return [];
+  return (await inv("get_lifecycle_history", { limit: limit ?? null })) as LifecycleEntry[];
+}
+
+/** Verbosity of the Rust side's own log (console and `matrx-app.log`). */
+export type AppLogLevel = "off" | "error" | "warn" | "info" | "debug" | "trace";
+
+/**
+ * Change how much the Rust side logs, effective immediately (Tauri only).
+ * It starts at "info"; "debug" adds every health check and restart decision.
+ */
+export async function setAppLogLevel(level: AppLogLevel): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await inv("set_app_log_level", { level });
+}
+
+/**
+ * Show a sidecar's binary (the engine by default) selected in the OS file
+ * manager, e.g. to check its signature or an antivirus quarantine (Tauri
+ * only). On Linux its folder is opened instead. Resolves with the path;
+ * rejects with a `BinaryMissing` SidecarError if the binary isn't there.
+ */
+export async function revealSidecarBinary(
+  name: string = ENGINE_SIDECAR,
+): Promise<string | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<string>(inv, "reveal_sidecar_binary", { name });
+}
+
+/**
+ * Start the engine against a fresh temp data dir, for tests and demos (Tauri
+ * only). The next `stopSidecar()` deletes the dir; a dir a crash leaves
+ * behind goes on the next launch. Rejects with `AlreadyRunning` if an engine
+ * is up, and otherwise like `startSidecar()`.
+ */
+export async function startSidecarEphemeral(readyTimeoutMs?: number): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await invokeSidecar<void>(inv, "start_sidecar_ephemeral", { readyTimeoutMs });
+}
+
+/**
+ * Look for an engine a previous session left serving on our port and adopt
+ * it instead of spawning a duplicate (Tauri only). Starting the engine does
+ * this by itself; resolves with the port when an engine is adopted, null
+ * when there was none to take over. Rejects with a `PortInUse` SidecarError
+ * if something that isn't our engine answers there.
+ */
+export async function probeExistingSidecar(): Promise<number | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<number | null>(inv, "probe_existing_sidecar");
+}
+
+/** Result of `checkPort`. */
+export interface PortCheck {
+  free: boolean;
+  /**
+   * "ours" is one of our engines — the running one, or an orphan that
+   * `reclaimEnginePort` can take the port back from. "foreign" is another app.
+   */
+  occupant: "none" | "ours" | "foreign";
+}
+
+/**
+ * Check whether `port` is free and, if not, who holds it — before starting
+ * the engine on it (Tauri only). Only looks; nothing is stopped or adopted.
+ */
+export async function checkPort(port: number): Promise<PortCheck> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return { free: true, occupant: "none" };
+  return invokeSidecar<PortCheck>(inv, "check_port", { port });
+}
+
+/**
+ * Move the engine to `port` and restart it there if it is running (Tauri
+ * only); the choice is saved as the preferred-port setting. Rejects for ports
+ * below 1024, and with a `PortInUse` SidecarError if another application
+ * holds the port. Resolves with the port the engine was told to bind.
+ */
+export async function setSidecarPort(port: number): Promise<number | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<number>(inv, "set_sidecar_port", { port });
+}
+
+/**
+ * Open the engine's Swagger UI (`/docs`) in the default browser (Tauri only).
+ * Rejects with a `NotRunning` SidecarError unless the engine is up and ready.
+ */
+export async function openApiDocs(): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await invokeSidecar(inv, "open_api_docs");
+}
+
+/** Payload of `reset_app_data` and the `app-data-reset` event. */
+export interface ResetReport {
+  removed: string[];
+  errors: string[];
+  kept_logs: boolean;
+}
+
+/**
+ * Factory reset: stop the engine, then delete all native settings and app
+ * data (engine logs are kept unless `keepLogs` is false). Call
+ * restartApp() afterwards so nothing keeps using the deleted state.
+ * Null outside Tauri.
+ */
+export async function resetAppData(keepLogs = true): Promise<ResetReport | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("reset_app_data", { keepLogs })) as ResetReport;
+}
+
+/**
+ * Write a support bundle (status, versions, OS info, redacted settings,
+ * engine logs and recent engine dumps) to the Downloads folder as a zip
+ * (Tauri only). Resolves with its path — attach that file to bug reports.
+ */
+export async function exportDiagnostics(): Promise<string | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("export_diagnostics")) as string;
+}
+
+/**
+ * Save the stacks of every engine thread and asyncio task to
+ * `engine-dump-{ts}.txt` in the log folder and resolve with its path (Tauri
+ * only). Works while the engine fails its health checks, which is when a
+ * dump is useful; `exportDiagnostics()` bundles the latest ones. Rejects
+ * with `NotRunning` if there is no engine.
+ */
+export async function dumpEngineState(): Promise<string | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<string>(inv, "dump_engine_state");
+}
+
+/** Register or unregister launching the app at login (Tauri only). */
+export async function setAutostart(enabled: boolean): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await inv("set_autostart", { enabled });
+}
+
+/** Whether the app is registered to launch at login; null outside Tauri. */
+export async function isAutostartEnabled(): Promise<boolean | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("is_autostart_enabled")) as boolean;
+}
+
+/** Set whether closing the window hides to tray or quits. */
+export async function setCloseToTray(enabled: boolean): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await inv("set_close_to_tray", { enabled });
+}
+
+/**
+ * Choose what closing the main window does: hide to tray, or shut down the
+ * engine and quit. Takes effect on the next close — no restart needed.
+ */
+export async function setCloseBehavior(mode: "tray" | "exit"): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await inv("set_close_behavior", { mode });
+}
+
+/**
+ * Change the system-wide show/hide hotkey (e.g. "CmdOrCtrl+Shift+M").
+ * Rejects with a descriptive message if the combo is invalid or taken.
+ */
+export async function setGlobalShortcut(accelerator: string): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await inv("set_global_shortcut", { accelerator });
 }
 
-/** Stop the Python engine sidecar (Tauri only). */
-export async function stopSidecar(): Promise<void> {
+/**
+ * Change the system-wide quit hotkey (default "CmdOrCtrl+Shift+Q"). It quits
+ * like the tray's Quit item, even with the window hidden in the tray.
+ * Rejects with a descriptive message if the combo is invalid or taken.
+ */
+export async function setQuitShortcut(accelerator: string): Promise<void> {
   const inv = await loadTauriInvoke();
   if (!inv) return;
-  await inv("stop_sidecar");
+  await inv("set_quit_shortcut", { accelerator });
 }
 
-/** Set whether closing the window hides to tray or quits. */
-export async function setCloseToTray(enabled: boolean): Promise<void> {
+/**
+ * Whether desktop notifications can be shown (Tauri only). False when the OS
+ * has denied permission — Rust then puts alerts on the tray instead.
+ */
+export async function areNotificationsAvailable(): Promise<boolean | null> {
+  return getNativeSetting<boolean>("notifications-available");
+}
+
+/**
+ * Ask the OS for notification permission again (Tauri only). Resolves with
+ * whether notifications are now available.
+ */
+export async function requestNotificationPermission(): Promise<boolean | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("request_notification_permission")) as boolean;
+}
+
+/** Read a native setting from the Rust settings store (Tauri only). */
+export async function getNativeSetting<T = unknown>(key: string): Promise<T | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return ((await inv("get_setting", { key })) as T | null) ?? null;
+}
+
+/** Write a native setting to the Rust settings store; persisted immediately. */
+export async function setNativeSetting(key: string, value: unknown): Promise<void> {
   const inv = await loadTauriInvoke();
   if (!inv) return;
-  await inv("set_close_to_tray", { enabled });
+  await inv("set_setting", { key, value });
+}
+
+/**
+ * The portable settings as a JSON document for `importNativeSettings()` on another
+ * machine, secrets replaced with "[redacted]" (Tauri only).
+ */
+export async function exportNativeSettings(): Promise<string | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("export_settings")) as string;
+}
+
+/** What `importNativeSettings()` did; also the payload of `settings-imported`. */
+export interface SettingsImportResult {
+  /** Keys written — or, when replacing, reset because the import lacked them. */
+  imported: string[];
+  /** Keys that aren't portable settings, left untouched. */
+  ignored: string[];
+  /** Keys skipped because their value was exported redacted. */
+  redacted: string[];
+  merge: boolean;
+}
+
+/**
+ * Apply a document from `exportNativeSettings()` (Tauri only). With `merge`,
+ * settings it lacks keep their values; otherwise they go back to defaults.
+ * Rejects if any value is invalid, before anything is written. Engine
+ * settings take effect on restart — watch for `restart-required`.
+ */
+export async function importNativeSettings(
+  json: string,
+  merge = true,
+): Promise<SettingsImportResult | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("import_settings", { json, merge })) as SettingsImportResult;
+}
+
+/**
+ * Payload of the Rust `deep-link` event for an `aimatrx://` URL.
+ * `path` includes the URL host: `aimatrx://notes/123` → `/notes/123`.
+ */
+export interface DeepLinkEvent {
+  url: string;
+  path: string;
+  query: Record<string, string>;
+}
+
+/**
+ * Claim a deep link that arrived before the frontend was listening (e.g. the
+ * link that launched the app). Returns null if none is pending.
+ */
+export async function getPendingDeepLink(): Promise<DeepLinkEvent | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  try {
+    return (await inv("get_pending_deep_link")) as DeepLinkEvent | null;
+  } catch {
+    return null;
+  }
 }
 
 export interface UpdateStatus {
   status: "up_to_date" | "available" | "downloading" | "installed";
+  /** False only for `up_to_date`. */
+  update_available?: boolean;
+  /** The available version; same as `version`. */
+  latest_version?: string | null;
   version?: string;
   body?: string;
+  /** Release-notes page, when the manifest provides one. */
+  notes_url?: string | null;
   content_length?: number;
   downloaded?: number;
 }
@@ -58,7 +909,7 @@ export interface UpdateStatus {
 /** Check for updates via the Tauri updater plugin. */
 export async function checkForUpdates(install = false): Promise<UpdateStatus> {
   const inv = await loadTauriInvoke();
-  if (!inv) return { status: "up_to_date" };
+  if (!inv) return { status: "up_to_date", update_available: false };
   const result = await inv("check_for_updates", { install }) as UpdateStatus;
   return result;
 }
@@ -92,28 +943,611 @@ export async function restartApp(): Promise<void> {
   }
 }
 
+/** Payload of the Rust `confirm-quit` event. */
+export interface ConfirmQuitEvent {
+  /** Jobs the engine reported as running when Quit was chosen. */
+  active_jobs: number;
+}
+
+/**
+ * Answer a `confirm-quit` prompt (Tauri only): `force` quits, shutting the
+ * engine down gracefully; otherwise the quit is cancelled.
+ */
+export async function confirmQuit(force: boolean): Promise<void> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return;
+  await inv("confirm_quit", { force });
+}
+
+export interface SidecarStatus {
+  name: string;
+  running: boolean;
+  pid: number | null;
+  /** Effective port of the current (or last) spawn, after any fallback. */
+  port: number;
+  /** True once the engine's /health endpoint has answered 200. */
+  ready: boolean;
+  /** Seconds since the current process was spawned; 0 when not running. */
+  uptime_secs: number;
+  /** Supervisor respawns since the app launched — climbs when it's flapping. */
+  restart_count: number;
+  /** Paused with `pauseSidecar()`; false once the process is gone. */
+  paused: boolean;
+  /**
+   * Paused by the idle timer (`idle-suspend-minutes`) rather than the user.
+   * The engine proxy commands resume it by themselves.
+   */
+  suspended: boolean;
+  /** Running against a throwaway data dir — see `startSidecarEphemeral()`. */
+  ephemeral: boolean;
+  /** The supervisor stopped restarting after too many crashes. */
+  circuit_open: boolean;
+  /** Alive but failing health pings — see `EngineResponsivenessEvent`. */
+  unresponsive: boolean;
+  /** Spawn-to-ready time of the last start; null before the first. */
+  last_startup_duration_ms: number | null;
+  /**
+   * The address the process listens on — "0.0.0.0" with LAN access on (see
+   * `setLanAccess()`). Null for an external or adopted engine.
+   */
+  bind_host: string | null;
+  /** How the last process ended, if one has since the app launched. */
+  last_exit: ExitInfo | null;
+  /** What the supervisor does when it exits on its own — see setRestartPolicy. */
+  restart_policy: RestartPolicy;
+  /** Cap on supervisor respawns this session; null for no cap. */
+  max_restarts: number | null;
+  /**
+   * Where to reach the engine, e.g. "http://127.0.0.1:22140" — taken from the
+   * address it reported binding. Build request URLs from this instead of
+   * assuming localhost.
+   */
+  base_url: string;
+  /**
+   * "external" when the dev-external-engine setting (or MATRX_DEV_ENGINE_URL)
+   * points at a hand-run engine: nothing is spawned, stop is a no-op, and
+   * running/ready reflect its last health check. "adopted" when an engine a
+   * previous session left running was taken over instead of spawning one
+   * (see `probeExistingSidecar()`); stop and restart replace it.
+   */
+  mode: "managed" | "external" | "adopted";
+}
+
 /** Get sidecar process status from Rust (Tauri only). */
-export async function getSidecarStatus(): Promise<{ running: boolean; port: number } | null> {
+export async function getSidecarStatus(
+  name: string = ENGINE_SIDECAR,
+): Promise<SidecarStatus | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  try {
+    return (await inv("sidecar_status", { name })) as SidecarStatus;
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Base URL of the running engine according to Rust (Tauri only). `null`
+ * outside Tauri or when the engine isn't running.
+ */
+export async function getEngineBaseUrl(): Promise<string | null> {
+  const status = await getSidecarStatus();
+  return status?.running ? status.base_url : null;
+}
+
+export interface SidecarVersion {
+  engine_version: string;
+  python_version: string | null;
+  /** Null for dev-mode engines. */
+  build_date: string | null;
+}
+
+/**
+ * Build info of the running engine (Tauri only). Rejects with a
+ * `HealthCheckFailed` SidecarError while it is still booting, or
+ * `NotRunning` when there is no engine.
+ */
+export async function getSidecarVersion(): Promise<SidecarVersion | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<SidecarVersion>(inv, "sidecar_version");
+}
+
+/** Result of `pingSidecar()`. */
+export interface SidecarPing {
+  /** Round-trip time; on failure, how long the attempt took (≤1.5s). */
+  latency_ms: number;
+  ok: boolean;
+}
+
+/**
+ * Time one /health round trip to the engine (Tauri only), e.g. for a
+ * latency badge. A down or slow engine resolves with `ok: false` rather than
+ * rejecting.
+ */
+export async function pingSidecar(name: string = ENGINE_SIDECAR): Promise<SidecarPing | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<SidecarPing>(inv, "ping_sidecar", { name });
+}
+
+/** One entry of `listSidecars()`. */
+export interface BundledSidecar {
+  /** externalBin name, e.g. "matrx-engine". */
+  name: string;
+  /** Where the app looks for the binary; empty if it can't tell. */
+  path: string;
+  exists: boolean;
+  size_bytes: number | null;
+}
+
+/**
+ * List every sidecar binary the app ships and whether it is installed
+ * (Tauri only) — for diagnosing partial installs.
+ */
+export async function listSidecars(): Promise<BundledSidecar[]> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return [];
+  return (await inv("list_sidecars")) as BundledSidecar[];
+}
+
+export interface SidecarMetrics {
+  pid: number;
+  /** Summed across cores: 200 means two cores fully busy. */
+  cpu_percent: number;
+  memory_bytes: number;
+  uptime_secs: number;
+}
+
+/**
+ * Sample the engine's CPU/memory usage (Tauri only). CPU is measured between
+ * calls, so poll at a steady interval; the first sample reads 0.
+ * Resolves `null` when the engine isn't running.
+ */
+export async function getSidecarMetrics(): Promise<SidecarMetrics | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  try {
+    return (await inv("sidecar_metrics")) as SidecarMetrics | null;
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Payload of the Rust `sidecar-state-changed` event, emitted at every engine
+ * lifecycle transition so the UI doesn't have to poll `getSidecarStatus()`.
+ */
+export interface SidecarStateEvent {
+  /** Which sidecar changed — ENGINE_SIDECAR for the engine. */
+  name: string;
+  /** "failed" when the supervisor's circuit breaker opened — see `SidecarCircuitOpenEvent`. */
+  state: "starting" | "running" | "stopping" | "stopped" | "crashed" | "failed";
+  port: number;
+  /** OS process id of the engine this transition refers to. */
+  pid: number | null;
+  /**
+   * How that process ended, on "stopped"/"crashed" once the exit has been
+   * seen — always set for a crash, often null for an intentional stop.
+   */
+  exit: ExitInfo | null;
+}
+
+/** How a sidecar process ended. */
+export interface ExitInfo {
+  pid: number;
+  kind: "clean" | "error" | "signal" | "unknown";
+  code: number | null;
+  /** Unix only. */
+  signal: number | null;
+  /** Milliseconds since the Unix epoch. */
+  ts: number;
+}
+
+/** One supervisor lifecycle transition or decision, from `getLifecycleHistory`. */
+export interface LifecycleEntry {
+  /** Milliseconds since the Unix epoch. */
+  ts: number;
+  event:
+    | "start"
+    | "ready"
+    | "stopping"
+    | "stop"
+    | "crash"
+    | "restart"
+    | "hung"
+    | "gave-up"
+    | "circuit-open"
+    | "restart-suppressed";
+  name: string;
+  pid: number | null;
+  port: number;
+  /** How the process ended, on "stop"/"crash" once the exit was seen. */
+  exit?: ExitInfo;
+  /** e.g. the restart delay, or why the supervisor gave up. */
+  detail?: string;
+}
+
+/** "exited with code 1", "was killed by signal 9", … for status text. */
+export function describeExit(exit: ExitInfo): string {
+  switch (exit.kind) {
+    case "clean":
+      return "exited normally";
+    case "error":
+      return `exited with code ${exit.code}`;
+    case "signal":
+      return `was killed by signal ${exit.signal}`;
+    default:
+      return "exited for an unknown reason";
+  }
+}
+
+/** One line of sidecar output, as carried in a `sidecar-log-batch` event. */
+export interface SidecarLogEvent {
+  name: string;
+  stream: "stdout" | "stderr" | "system";
+  line: string;
+  /** Milliseconds since the Unix epoch, stamped when Rust received the line. */
+  ts: number;
+}
+
+/**
+ * Payload of the Rust `sidecar-log-batch` event — one sidecar's output from
+ * the last ~100ms, in arrival order across stdout and stderr. `dropped`
+ * counts lines left out because the batch hit its size cap (they are still
+ * in engine.log and the ring buffer).
+ */
+export interface SidecarLogBatchEvent {
+  name: string;
+  entries: SidecarLogEvent[];
+  dropped: number;
+}
+
+/**
+ * The lines of a `sidecar-log-batch`, with a trailing "system" line noting
+ * any dropped lines so the gap is visible wherever the lines are shown.
+ */
+export function expandSidecarLogBatch(batch: SidecarLogBatchEvent): SidecarLogEvent[] {
+  if (batch.dropped === 0) return batch.entries;
+  const last = batch.entries[batch.entries.length - 1];
+  return [
+    ...batch.entries,
+    {
+      name: batch.name,
+      stream: "system",
+      line: `[log] ${batch.dropped} line(s) dropped — see engine.log for the full output`,
+      ts: last?.ts ?? Date.now(),
+    },
+  ];
+}
+
+/**
+ * Render a log line in the same `[stream] line` form that
+ * `get_sidecar_logs` returns, so buffered and live lines parse identically.
+ */
+export function formatSidecarLogEvent(payload: SidecarLogEvent | string): string {
+  if (typeof payload === "string") return payload;
+  return payload.stream === "system" ? payload.line : `[${payload.stream}] ${payload.line}`;
+}
+
+/** False for log lines from sidecars other than the engine. */
+export function isEngineLogEvent(payload: SidecarLogEvent | string): boolean {
+  return typeof payload === "string" || payload.name === ENGINE_SIDECAR;
+}
+
+/**
+ * The running engine's per-launch token, sent as the `X-Engine-Token` header
+ * (Tauri only). It changes on every engine (re)start, so don't cache it.
+ * Null when no engine is running.
+ */
+export async function getEngineToken(): Promise<string | null> {
   const inv = await loadTauriInvoke();
   if (!inv) return null;
   try {
-    return (await inv("sidecar_status")) as { running: boolean; port: number };
+    return (await inv("get_engine_token")) as string | null;
   } catch {
     return null;
   }
 }
 
+/** True for http(s) URLs on this machine other than the page's own origin. */
+function isLocalEngineUrl(url: string): boolean {
+  try {
+    const parsed = new URL(url, window.location.href);
+    return (
+      (parsed.protocol === "http:" || parsed.protocol === "https:") &&
+      (parsed.hostname === "127.0.0.1" || parsed.hostname === "localhost") &&
+      parsed.origin !== window.location.origin
+    );
+  } catch {
+    return false;
+  }
+}
+
+/**
+ * Add `X-Engine-Token` to every webview fetch to the local engine, so calls
+ * that build their own headers pass the engine's token check too. Call once
+ * at startup; a no-op outside Tauri.
+ */
+export function installEngineTokenFetch(): void {
+  if (!isTauri()) return;
+  const send = window.fetch.bind(window);
+  window.fetch = async (input, init) => {
+    const url = input instanceof Request ? input.url : String(input);
+    if (!isLocalEngineUrl(url)) return send(input, init);
+    const token = await getEngineToken();
+    if (!token) return send(input, init);
+    const headers = new Headers(
+      init?.headers ?? (input instanceof Request ? input.headers : undefined),
+    );
+    if (!headers.has("X-Engine-Token")) headers.set("X-Engine-Token", token);
+    return send(input, { ...init, headers });
+  };
+}
+
+/**
+ * `url` with the engine token as `?engine_token=`, for EventSource and
+ * WebSocket connections, which can't set headers. Unchanged outside Tauri.
+ */
+export async function withEngineToken(url: string): Promise<string> {
+  const token = await getEngineToken();
+  if (!token) return url;
+  const sep = url.includes("?") ? "&" : "?";
+  return `${url}${sep}engine_token=${encodeURIComponent(token)}`;
+}
+
+/** A tool call the engine is running (`list_active_jobs`). */
+export interface EngineJob {
+  /** Pass to `cancelJob`. */
+  id: string;
+  /** The id the client chose when it sent the call. */
+  request_id: string;
+  tool: string;
+  /** Seconds since the Unix epoch. */
+  started_at: number;
+  elapsed_secs: number;
+}
+
+export interface JobCancelResult {
+  id: string;
+  /** False when the job had already finished. */
+  cancelled: boolean;
+}
+
+/**
+ * Tool calls the engine is running, oldest first (Tauri only). Rejects with a
+ * `NotRunning` SidecarError when there is no engine.
+ */
+export async function listActiveJobs(): Promise<EngineJob[]> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return [];
+  return invokeSidecar<EngineJob[]>(inv, "list_active_jobs");
+}
+
+/**
+ * Cancel a running job — the "stop generation" action (Tauri only). The
+ * client that started it receives a "was cancelled" error for the call.
+ */
+export async function cancelJob(id: string): Promise<JobCancelResult | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<JobCancelResult>(inv, "cancel_job", { id });
+}
+
+/** Result of `patchEngineConfig()`. */
+export interface EngineConfigPatch {
+  /** The whole configuration after the change. */
+  config: Record<string, unknown>;
+  /** Keys whose value actually changed. */
+  updated: string[];
+  /** Changed keys that only take effect after an engine restart. */
+  restart_required: string[];
+}
+
+/** The engine's effective configuration, defaults filled in (Tauri only). */
+export async function getEngineConfig(): Promise<Record<string, unknown> | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<Record<string, unknown>>(inv, "get_engine_config");
+}
+
+/**
+ * Change some engine settings (Tauri only). Rejects with the engine's
+ * message for unknown keys or wrong types. If any changed key needs a
+ * restart, `restart-required` is emitted as well.
+ */
+export async function patchEngineConfig(
+  partial: Record<string, unknown>,
+): Promise<EngineConfigPatch | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<EngineConfigPatch>(inv, "patch_engine_config", { partial });
+}
+
+/**
+ * Launch the engine against a TOML/YAML/JSON config file of your own from
+ * now on, or pass null to stop (Tauri only). The file must exist and be
+ * readable; its keys override the engine's stored settings. Takes effect on
+ * the next engine start — `restart-required` fires if it's running. A start
+ * whose file has since gone away fails with a `ConfigFileMissing`
+ * SidecarError.
+ */
+export async function setEngineConfigFile(path: string | null): Promise<string | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("set_engine_config_file", { path })) as string | null;
+}
+
+/**
+ * The engine's OpenAPI schema, for generating a typed client (Tauri only).
+ * Fetched through Rust, which holds the engine token; cached until the
+ * engine restarts. Rejects with `NotReady` while it's starting.
+ */
+export async function getOpenApiSchema(): Promise<Record<string, unknown> | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<Record<string, unknown>>(inv, "get_openapi_schema");
+}
+
+/** Result of `checkVersionCompat()`, and the payload of `version-mismatch`. */
+export interface VersionCompat {
+  compatible: boolean;
+  app_version: string;
+  engine_version: string;
+  /** Engine versions this app accepts, e.g. ">=1.3.0, <2.0.0". */
+  required_range: string;
+}
+
+/**
+ * Compare the running engine's version with the range this app was built
+ * for (Tauri only). Also runs on its own each time the engine starts; an
+ * incompatible engine fires `version-mismatch`, after which the UI should
+ * block use and prompt a reinstall.
+ */
+export async function checkVersionCompat(): Promise<VersionCompat | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<VersionCompat>(inv, "check_version_compat");
+}
+
+/** Payload of `engine-sse`: one event relayed from an engine SSE stream. */
+export interface EngineSseEvent {
+  /** Subscription id from `subscribeEngineStream`. */
+  id: number;
+  /** The SSE `event:` field; "message" when the engine didn't send one. */
+  event: string;
+  data: string;
+  event_id: string | null;
+}
+
+/** Payload of `engine-sse-closed`, sent once when a subscription ends. */
+export interface EngineSseClosedEvent {
+  id: number;
+  reason: "ended" | "engine-stopped" | "gave-up" | "error";
+  detail: string | null;
+}
+
+/**
+ * Open the engine's SSE endpoint at `path` (e.g. "/chat/stream?id=42") and
+ * relay its events as `engine-sse` (Tauri only). Resolves with the
+ * subscription id; dropped connections are reopened while the engine is up.
+ */
+export async function subscribeEngineStream(path: string): Promise<number | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<number>(inv, "subscribe_engine_stream", { path });
+}
+
+/** Stop a subscription. Resolves false if it had already ended. */
+export async function unsubscribeEngineStream(id: number): Promise<boolean> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return false;
+  return (await inv("unsubscribe_engine_stream", { id })) as boolean;
+}
+
 /** Get buffered sidecar stdout/stderr lines from Rust (Tauri only). */
-export async function getSidecarLogs(): Promise<string[]> {
+export async function getSidecarLogs(name: string = ENGINE_SIDECAR): Promise<string[]> {
   const inv = await loadTauriInvoke();
   if (!inv) return [];
   try {
-    return (await inv("get_sidecar_logs")) as string[];
+    return (await inv("get_sidecar_logs", { name })) as string[];
   } catch {
     return [];
   }
 }
 
+/**
+ * The command-line args a sidecar was last spawned with — base, platform
+ * overrides, feature flags, config file and `sidecar-config` args (Tauri
+ * only). Null until it has been spawned this session.
+ */
+export async function getEffectiveSidecarArgs(
+  name: string = ENGINE_SIDECAR,
+): Promise<string[] | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<string[] | null>(inv, "get_effective_sidecar_args", { name });
+}
+
+/** What `setLanAccess(true, …)` resolves with: where other devices reach the engine. */
+export interface LanAccessInfo {
+  /** e.g. "http://192.168.1.20:22140". */
+  url: string;
+  /**
+   * Send as `X-Engine-Token` (or `?engine_token=`) — the engine refuses LAN
+   * clients without it, on every route. Changes on every engine restart.
+   */
+  token: string | null;
+}
+
+/**
+ * Let devices on the local network reach the engine, or stop (Tauri only).
+ * A running engine restarts onto the new address. Turning it on exposes the
+ * engine to the whole network, so it is refused unless `confirm` is true —
+ * only pass that after the user has explicitly agreed — and needs a running
+ * engine that confirms it requires the token from LAN clients.
+ */
+export async function setLanAccess(
+  enabled: boolean,
+  confirm = false,
+): Promise<LanAccessInfo | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return invokeSidecar<LanAccessInfo | null>(inv, "set_lan_access", { enabled, confirm });
+}
+
+/**
+ * Last `lines` lines of the on-disk engine.log (Tauri only) — unlike
+ * getSidecarLogs() this survives app restarts, so it shows what happened
+ * before a crash. Empty when nothing has been logged yet.
+ */
+export async function tailEngineLog(lines = 200): Promise<string[]> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return [];
+  return (await inv("tail_engine_log", { lines })) as string[];
+}
+
+export type EngineLogLevel = "debug" | "info" | "warning" | "error" | "critical";
+
+/** Filter for `queryEngineLog()`; every field is optional. */
+export interface EngineLogQuery {
+  /** Least severe level to include — "warning" also returns errors. */
+  level?: EngineLogLevel;
+  /** Case-insensitive text the entry must contain. */
+  contains?: string;
+  /** Most entries to return, newest kept (default 200). */
+  limit?: number;
+}
+
+/** One engine log entry: a leveled line plus the lines that continue it. */
+export interface EngineLogEntry {
+  /** Null for lines before the first leveled one. */
+  level: EngineLogLevel | null;
+  /** Newline-separated, e.g. an error followed by its traceback. */
+  text: string;
+}
+
+/**
+ * Entries from the end of engine.log that match `query`, oldest first
+ * (Tauri only). Tracebacks stay attached to the line that logged them.
+ */
+export async function queryEngineLog(query: EngineLogQuery = {}): Promise<EngineLogEntry[]> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return [];
+  return (await inv("query_engine_log", { query })) as EngineLogEntry[];
+}
+
+/**
+ * Archive the current engine.log to engine.log.1 now, regardless of size, so
+ * the next bug repro starts from a clean log (Tauri only). Resolves with the
+ * archive's path; rejects when nothing has been logged yet.
+ */
+export async function rotateEngineLog(): Promise<string | null> {
+  const inv = await loadTauriInvoke();
+  if (!inv) return null;
+  return (await inv("rotate_engine_log")) as string;
+}
+
 /**
  * Detect if we are running on Windows without depending on PLATFORM data
  * from the engine (which isn't available during startup).
@@ -191,6 +1625,21 @@ export async function waitForEngine(
  * to avoid the circular dependency on engine data during startup.
  */
 export async function discoverEnginePort(): Promise<string | null> {
+  // The Rust side may have fallen back to an ephemeral port outside the scan
+  // range below when 22140 was taken, or the engine may be bound to a LAN
+  // address — ask it where the engine actually is.
+  const baseUrl = await getEngineBaseUrl();
+  if (baseUrl) {
+    try {
+      const resp = await fetch(`${baseUrl}/tools/list`, {
+        signal: AbortSignal.timeout(1000),
+      });
+      if (resp.ok) return baseUrl;
+    } catch {
+      // Fall through to the range scan
+    }
+  }
+
   if (isTauri() && isWindowsPlatform()) {
     const inv = await loadTauriInvoke();
     if (inv) {
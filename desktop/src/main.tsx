@@ -1,8 +1,11 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import { installEngineTokenFetch } from "@/lib/sidecar";
 import "./index.css";
 
+installEngineTokenFetch();
+
 ReactDOM.createRoot(document.getElementById("root")!).render(
   <React.StrictMode>
     <App />
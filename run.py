@@ -51,6 +51,119 @@ if _sys.platform == "win32":
     except Exception:
         pass
 
+# ── Resource limits — before anything starts a thread or a worker ────────────
+# The desktop shell passes the user's resource-limits setting as
+# MATRX_CPU_CORES / MATRX_MEMORY_MB (see desktop/src-tauri/src/resource_limits.rs).
+# Applied here, in-process, they cover every thread and child the engine
+# starts later; the shell can't set them from outside without racing the
+# PyInstaller bootloader's fork. A limit that can't be applied is reported
+# on stderr, which the shell keeps in the engine log.
+def _apply_resource_limits() -> None:
+    cores = _os.environ.get("MATRX_CPU_CORES")
+    memory_mb = _os.environ.get("MATRX_MEMORY_MB")
+    if _sys.platform == "win32":
+        if cores or memory_mb:
+            try:
+                _limit_windows_job(cores, memory_mb)
+            except (ValueError, OSError) as exc:
+                print(
+                    f"[limits] Could not apply MATRX_CPU_CORES={cores} MATRX_MEMORY_MB={memory_mb}: {exc}",
+                    file=_sys.stderr,
+                    flush=True,
+                )
+        return
+    if cores:
+        try:
+            if hasattr(_os, "sched_setaffinity"):
+                _os.sched_setaffinity(0, set(range(int(cores))))
+            elif _sys.platform == "darwin":
+                # No affinity API; run at a lower priority instead.
+                _os.nice(10)
+        except (ValueError, OSError) as exc:
+            print(f"[limits] Could not apply MATRX_CPU_CORES={cores}: {exc}", file=_sys.stderr, flush=True)
+    if memory_mb and _sys.platform.startswith("linux"):
+        try:
+            import resource as _resource
+
+            # Address space, not resident memory — see resource_limits.rs.
+            limit = int(memory_mb) * 1024 * 1024
+            _resource.setrlimit(_resource.RLIMIT_AS, (limit, limit))
+        except (ValueError, OSError) as exc:
+            print(f"[limits] Could not apply MATRX_MEMORY_MB={memory_mb}: {exc}", file=_sys.stderr, flush=True)
+
+
+# Windows: put this process in a job object limited to the first N cores
+# and to a total of memory_mb committed memory. Processes the engine starts
+# later join the job with it. The handle is never closed — the job ends
+# with the last process in it.
+def _limit_windows_job(cores: str | None, memory_mb: str | None) -> None:
+    import ctypes
+    from ctypes import wintypes
+
+    class _IoCounters(ctypes.Structure):
+        _fields_ = [(name, ctypes.c_ulonglong) for name in (
+            "ReadOperationCount", "WriteOperationCount", "OtherOperationCount",
+            "ReadTransferCount", "WriteTransferCount", "OtherTransferCount",
+        )]
+
+    class _BasicLimits(ctypes.Structure):
+        _fields_ = [
+            ("PerProcessUserTimeLimit", ctypes.c_longlong),
+            ("PerJobUserTimeLimit", ctypes.c_longlong),
+            ("LimitFlags", wintypes.DWORD),
+            ("MinimumWorkingSetSize", ctypes.c_size_t),
+            ("MaximumWorkingSetSize", ctypes.c_size_t),
+            ("ActiveProcessLimit", wintypes.DWORD),
+            ("Affinity", ctypes.c_size_t),
+            ("PriorityClass", wintypes.DWORD),
+            ("SchedulingClass", wintypes.DWORD),
+        ]
+
+    class _ExtendedLimits(ctypes.Structure):
+        _fields_ = [
+            ("BasicLimitInformation", _BasicLimits),
+            ("IoInfo", _IoCounters),
+            ("ProcessMemoryLimit", ctypes.c_size_t),
+            ("JobMemoryLimit", ctypes.c_size_t),
+            ("PeakProcessMemoryUsed", ctypes.c_size_t),
+            ("PeakJobMemoryUsed", ctypes.c_size_t),
+        ]
+
+    job_object_limit_affinity = 0x10
+    job_object_limit_job_memory = 0x200
+    job_object_extended_limit_information = 9
+
+    limits = _ExtendedLimits()
+    if cores:
+        count = min(int(cores), _os.cpu_count() or 1)
+        limits.BasicLimitInformation.Affinity = (1 << count) - 1
+        limits.BasicLimitInformation.LimitFlags |= job_object_limit_affinity
+    if memory_mb:
+        limits.JobMemoryLimit = int(memory_mb) * 1024 * 1024
+        limits.BasicLimitInformation.LimitFlags |= job_object_limit_job_memory
+
+    kernel32 = ctypes.WinDLL("kernel32", use_last_error=True)
+    kernel32.CreateJobObjectW.restype = wintypes.HANDLE
+    kernel32.CreateJobObjectW.argtypes = [wintypes.LPVOID, wintypes.LPCWSTR]
+    kernel32.SetInformationJobObject.argtypes = [
+        wintypes.HANDLE, ctypes.c_int, wintypes.LPVOID, wintypes.DWORD,
+    ]
+    kernel32.GetCurrentProcess.restype = wintypes.HANDLE
+    kernel32.AssignProcessToJobObject.argtypes = [wintypes.HANDLE, wintypes.HANDLE]
+
+    job = kernel32.CreateJobObjectW(None, None)
+    if not job:
+        raise ctypes.WinError(ctypes.get_last_error())
+    if not kernel32.SetInformationJobObject(
+        job, job_object_extended_limit_information, ctypes.byref(limits), ctypes.sizeof(limits)
+    ):
+        raise ctypes.WinError(ctypes.get_last_error())
+    if not kernel32.AssignProcessToJobObject(job, kernel32.GetCurrentProcess()):
+        raise ctypes.WinError(ctypes.get_last_error())
+
+
+_apply_resource_limits()
+
 import json
 import logging
 import os
@@ -297,7 +410,8 @@ def start_server(port: int) -> None:
     global _uvicorn_server
     config = uvicorn.Config(
         app,
-        host="127.0.0.1",
+        # The desktop shell sets 0.0.0.0 when the user turns on LAN access.
+        host=os.environ.get("MATRX_HOST", "127.0.0.1"),
         port=port,
         log_level="info",
         log_config=_build_log_config(),
@@ -320,6 +434,53 @@ def on_quit(icon: Icon, item: MenuItem) -> None:
         os._exit(0)
 
 
+# Optional features the desktop shell switches on with ``--enable NAME``
+# (desktop/src-tauri/src/features.rs holds the same list). Each one is gated
+# by an env var, so the flag just sets it before the server starts.
+FEATURE_ENV_VARS = {
+    "bridge-broadcast": "MATRX_BRIDGE_BROADCAST_ENABLED",
+    "local-scheduler": "MATRX_LOCAL_SCHEDULER_ENABLED",
+}
+
+
+def _apply_feature_flags(argv: list[str]) -> None:
+    """Turn each ``--enable NAME`` / ``--enable=NAME`` in argv into its env var.
+
+    Unknown names are logged and ignored rather than failing startup — the
+    shell validates them, and an older engine may not know a newer feature.
+    """
+    names: list[str] = []
+    for i, arg in enumerate(argv):
+        if arg == "--enable" and i + 1 < len(argv):
+            names.append(argv[i + 1])
+        elif arg.startswith("--enable="):
+            names.append(arg.split("=", 1)[1])
+    for name in names:
+        env_var = FEATURE_ENV_VARS.get(name)
+        if env_var is None:
+            logger.warning("Ignoring unknown feature flag --enable %s", name)
+            continue
+        os.environ[env_var] = "1"
+        logger.info("Feature %s enabled (%s=1)", name, env_var)
+
+
+def _apply_config_file(argv: list[str]) -> None:
+    """Pass ``--config PATH`` / ``--config=PATH`` on to the settings store.
+
+    The file's keys override the stored settings for this run; see
+    _load_config_file in app/services/cloud_sync/settings_sync.py.
+    """
+    path = None
+    for i, arg in enumerate(argv):
+        if arg == "--config" and i + 1 < len(argv):
+            path = argv[i + 1]
+        elif arg.startswith("--config="):
+            path = arg.split("=", 1)[1]
+    if path:
+        os.environ["MATRX_CONFIG_FILE"] = path
+        logger.info("Using config file %s", path)
+
+
 def _is_tauri_sidecar() -> bool:
     """Return True when this process was launched by the Tauri desktop shell.
 
@@ -543,6 +704,9 @@ def main() -> None:
     if _is_tauri_sidecar():
         _start_parent_watchdog()
 
+    _apply_feature_flags(sys.argv[1:])
+    _apply_config_file(sys.argv[1:])
+
     # ── Preflight ────────────────────────────────────────────────────────────
     # Sweep every managed service (engine sidecars from prior installs, stray
     # cloudflared tunnel processes, orphaned llama-server) BEFORE we touch a